@@ -7,7 +7,13 @@ fn build_go_backend() {
                 .unwrap(),
         )
         .arg("build-go")
-        .spawn()
+        // The Go side needs to know the field of Noir's field elements.
+        .arg(if std::env::var_os("CARGO_FEATURE_BLS12_381").is_some() {
+            "GO_TAGS=bls12_381"
+        } else {
+            "GO_TAGS="
+        })
+        .status()
         .unwrap();
 }
 
@@ -4,15 +4,14 @@ import (
 	"encoding/json"
 
 	"gnark_backend_ffi/acir/term"
+	common "gnark_backend_ffi/internal"
 	backend_helpers "gnark_backend_ffi/internal/backend"
-
-	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
 )
 
 type ArithmeticOpcode struct {
 	MulTerms    term.MulTerms
 	SimpleTerms term.SimpleTerms
-	QC          fr_bn254.Element
+	QC          common.Felt
 }
 
 func (g *ArithmeticOpcode) UnmarshalJSON(data []byte) error {
@@ -38,7 +37,7 @@ func (g *ArithmeticOpcode) UnmarshalJSON(data []byte) error {
 
 	var mulTerms term.MulTerms
 	var addTerms term.SimpleTerms
-	var constantTerm fr_bn254.Element
+	var constantTerm common.Felt
 
 	// Deserialize mul terms.
 	if mulTermsValue, ok := gateMap["mul_terms"].([]interface{}); ok {
@@ -70,7 +69,10 @@ func (g *ArithmeticOpcode) UnmarshalJSON(data []byte) error {
 
 	// Deserialize constant term.
 	if encodedConstantTerm, ok := gateMap["q_c"].(string); ok {
-		constantTerm = backend_helpers.DeserializeFelt(encodedConstantTerm)
+		constantTerm, err = backend_helpers.DeserializeFelt(encodedConstantTerm)
+		if err != nil {
+			return err
+		}
 	} else {
 		return &json.UnmarshalTypeError{}
 	}
@@ -44,15 +44,30 @@ var (
 	}
 )
 
+// BlackBoxFunctionNamed returns the black box function called name in ACIR.
+func BlackBoxFunctionNamed(name string) (blackBoxFunctionName, bool) {
+	function, ok := blackBoxFunctionsNameMap[name]
+	return function, ok
+}
+
+// BlackBoxFunctionNames returns the ACIR names of every black box function.
+func BlackBoxFunctionNames() []string {
+	names := make([]string, 0, len(blackBoxFunctionsNameMap))
+	for name := range blackBoxFunctionsNameMap {
+		names = append(names, name)
+	}
+	return names
+}
+
 type BlackBoxFunction struct {
 	Name    blackBoxFunctionName
-	Inputs  functionInputs
+	Inputs  FunctionInputs
 	Outputs common.Witnesses
 }
 
-type functionInputs = []functionInput
+type FunctionInputs = []FunctionInput
 
-type functionInput struct {
+type FunctionInput struct {
 	Witness common.Witness
 	NumBits uint32
 }
@@ -79,7 +94,7 @@ func (bbf *BlackBoxFunction) UnmarshalJSON(data []byte) error {
 	}
 
 	var name blackBoxFunctionName
-	var inputs functionInputs
+	var inputs FunctionInputs
 	var outputs common.Witnesses
 
 	if inputsValue, ok := blackBoxFunctionMap["inputs"].([]interface{}); ok {
@@ -121,7 +136,7 @@ func (bbf *BlackBoxFunction) UnmarshalJSON(data []byte) error {
 	return nil
 }
 
-func (fi *functionInput) UnmarshalJSON(data []byte) error {
+func (fi *FunctionInput) UnmarshalJSON(data []byte) error {
 	var functionInputMap map[string]interface{}
 	err := json.Unmarshal(data, &functionInputMap)
 	if err != nil {
@@ -6,14 +6,12 @@ import (
 
 	common "gnark_backend_ffi/internal"
 	backend_helpers "gnark_backend_ffi/internal/backend"
-
-	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
 )
 
 type MulTerms = []MulTerm
 
 type MulTerm struct {
-	Coefficient       fr_bn254.Element
+	Coefficient       common.Felt
 	MultiplicandIndex common.Witness
 	MultiplierIndex   common.Witness
 }
@@ -25,14 +23,22 @@ func (m *MulTerm) UnmarshalJSON(data []byte) error {
 		log.Print(err)
 		return err
 	}
+	if len(mulTerm) != 3 {
+		log.Print("Error: a mul term must have three elements.")
+		return &json.UnmarshalTypeError{}
+	}
 
-	var coefficient fr_bn254.Element
+	var coefficient common.Felt
 	var multiplicand common.Witness
 	var multiplier common.Witness
 
 	// Deserialize coefficient.
 	if coefficientValue, ok := mulTerm[0].(string); ok {
-		coefficient = backend_helpers.DeserializeFelt(coefficientValue)
+		coefficient, err = backend_helpers.DeserializeFelt(coefficientValue)
+		if err != nil {
+			log.Print(err)
+			return err
+		}
 	} else {
 		log.Print("Error: couldn't deserialize coefficient.")
 		return &json.UnmarshalTypeError{}
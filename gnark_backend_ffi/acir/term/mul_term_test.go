@@ -79,7 +79,6 @@ func TestMulTermUnmarshalJSONThrowsErrorWrongJSONFormatMultiplier(t *testing.T)
 }
 
 func TestMulTermUnmarshalJSONThrowsErrorOddCoefficientLength(t *testing.T) {
-	t.Skip("DeserializeFelt exits when error")
 	encodedCoefficient := "123"
 	multiplicand := rand.Uint32()
 	multiplier := rand.Uint32()
@@ -6,14 +6,12 @@ import (
 
 	common "gnark_backend_ffi/internal"
 	backend_helpers "gnark_backend_ffi/internal/backend"
-
-	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
 )
 
 type SimpleTerms = []SimpleTerm
 
 type SimpleTerm struct {
-	Coefficient   fr_bn254.Element
+	Coefficient   common.Felt
 	VariableIndex common.Witness
 }
 
@@ -24,13 +22,21 @@ func (m *SimpleTerm) UnmarshalJSON(data []byte) error {
 		log.Print(err)
 		return err
 	}
+	if len(linearTerm) != 2 {
+		log.Print("Error: a linear term must have two elements.")
+		return &json.UnmarshalTypeError{}
+	}
 
-	var coefficient fr_bn254.Element
+	var coefficient common.Felt
 	var variable common.Witness
 
 	// Deserialize coefficient.
 	if coefficientValue, ok := linearTerm[0].(string); ok {
-		coefficient = backend_helpers.DeserializeFelt(coefficientValue)
+		coefficient, err = backend_helpers.DeserializeFelt(coefficientValue)
+		if err != nil {
+			log.Print(err)
+			return err
+		}
 	} else {
 		log.Print("Error: couldn't deserialize coefficient.")
 		return &json.UnmarshalTypeError{}
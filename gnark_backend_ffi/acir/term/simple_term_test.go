@@ -60,7 +60,6 @@ func TestAddTermUnmarshalJSONThrowsErrorWrongJSONFormatSum(t *testing.T) {
 }
 
 func TestAddTermUnmarshalJSONThrowsErrorOddCoefficientLength(t *testing.T) {
-	t.Skip("DeserializeFelt exits when error")
 	sum := rand.Uint32()
 	addTerm := fmt.Sprintf(`{"coefficient":"%s","sum":%d}`, "123", sum)
 
@@ -1,25 +1,30 @@
 package backend
 
 import (
-	"bytes"
 	"crypto/rand"
-	"encoding/hex"
 	"fmt"
-	"gnark_backend_ffi/acir"
-	"io/ioutil"
-	"log"
+	common "gnark_backend_ffi/internal"
 	"math/big"
-	"os"
+	"sync"
 
 	"github.com/consensys/gnark-crypto/ecc"
-	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
-	"github.com/consensys/gnark-crypto/ecc/bn254/fr/kzg"
+	kzg_bls12377 "github.com/consensys/gnark-crypto/ecc/bls12-377/fr/kzg"
+	kzg_bls12381 "github.com/consensys/gnark-crypto/ecc/bls12-381/fr/kzg"
+	kzg_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr/kzg"
+	kzg_bw6761 "github.com/consensys/gnark-crypto/ecc/bw6-761/fr/kzg"
 	kzgg "github.com/consensys/gnark-crypto/kzg"
 	"github.com/consensys/gnark/backend/witness"
 	"github.com/consensys/gnark/constraint"
 )
 
-func BuildWitnesses(scalarField *big.Int, publicVariables fr_bn254.Vector, privateVariables fr_bn254.Vector, nbPublicVariables int, nbSecretVariables int) witness.Witness {
+// BuildWitnesses fills a witness over scalarField with the values, elements
+// of that field.
+func BuildWitnesses[T any](scalarField *big.Int, publicVariables []T, privateVariables []T, nbPublicVariables int, nbSecretVariables int) (witness.Witness, error) {
+	w, err := witness.New(scalarField)
+	if err != nil {
+		return nil, common.NewError(common.InternalError, err)
+	}
+
 	witnessValues := make(chan any)
 
 	go func() {
@@ -32,113 +37,155 @@ func BuildWitnesses(scalarField *big.Int, publicVariables fr_bn254.Vector, priva
 		}
 	}()
 
-	witness, err := witness.New(scalarField)
+	err = w.Fill(nbPublicVariables, nbSecretVariables, witnessValues)
+	// Drain whatever Fill did not consume so the goroutine above returns.
+	for range witnessValues {
+	}
 	if err != nil {
-		log.Fatal(err)
+		return nil, common.NewError(common.InvalidValues, err)
 	}
 
-	witness.Fill(nbPublicVariables, nbSecretVariables, witnessValues)
-
-	return witness
+	return w, nil
 }
 
-func HandleValues(a acir.ACIR, cs constraint.ConstraintSystem, values fr_bn254.Vector) (publicVariables fr_bn254.Vector, secretVariables fr_bn254.Vector, indexMap map[string]int) {
+// HandleValues adds a variable to the constraint system for every value and
+// returns the public and secret values split, together with a map from the
+// ACIR witness index to the index of its variable in the constraint system.
+// Public variables are added first because gnark expects their indices to
+// precede the secret ones.
+func HandleValues[T any](publicInputs common.Witnesses, cs constraint.ConstraintSystem, values []T) (publicVariables []T, secretVariables []T, indexMap map[string]int) {
 	indexMap = make(map[string]int)
+	isPublic := make(map[common.Witness]bool)
+	for _, publicInput := range publicInputs {
+		isPublic[publicInput] = true
+	}
+
 	var index int
 	for i, value := range values {
-		i++
-		for _, publicInput := range a.PublicInputs {
-			if uint32(i) == publicInput {
-				index = cs.AddPublicVariable(fmt.Sprintf("public_%d", i))
-				publicVariables = append(publicVariables, value)
-				indexMap[fmt.Sprint(i)] = index
-			}
+		witness := common.Witness(i + 1)
+		if isPublic[witness] {
+			index = cs.AddPublicVariable(fmt.Sprintf("public_%d", witness))
+			publicVariables = append(publicVariables, value)
+			indexMap[fmt.Sprint(witness)] = index
 		}
-
 	}
 	for i, value := range values {
-		i++
-		if len(a.PublicInputs) > 0 {
-			for _, publicInput := range a.PublicInputs {
-				if uint32(i) != publicInput {
-					index = cs.AddSecretVariable(fmt.Sprintf("secret_%d", i))
-					secretVariables = append(secretVariables, value)
-					indexMap[fmt.Sprint(i)] = index
-				}
-			}
-		} else {
-			index = cs.AddSecretVariable(fmt.Sprintf("secret_%d", i))
+		witness := common.Witness(i + 1)
+		if !isPublic[witness] {
+			index = cs.AddSecretVariable(fmt.Sprintf("secret_%d", witness))
 			secretVariables = append(secretVariables, value)
-			indexMap[fmt.Sprint(i)] = index
+			indexMap[fmt.Sprint(witness)] = index
 		}
 	}
 	return
 }
 
-func getFilePath() (string, error) {
-	userConfigDir, err := os.UserConfigDir()
-	if err != nil {
-		return userConfigDir, err
+// VariableIndex returns the index of the constraint system variable of an ACIR
+// witness, failing if no value was provided for it.
+func VariableIndex(indexMap map[string]int, witness common.Witness) (int, error) {
+	index, ok := indexMap[fmt.Sprint(witness)]
+	if !ok {
+		return 0, common.Errorf(common.InvalidCircuit, "witness %d has no value", witness)
 	}
-	return userConfigDir + "/noir-lang/srs.hex", nil
+	return index, nil
 }
 
-func LoadSRS() (srs kzgg.SRS, err error) {
-	filepath, err := getFilePath()
+// NewInsecureSRS generates a KZG SRS of size points from local randomness.
+// Whoever runs it may learn the secret it is made of and forge proofs, so it
+// must only be used for tests.
+func NewInsecureSRS(curveID ecc.ID, size int) (kzgg.SRS, error) {
+	alpha, err := rand.Int(rand.Reader, curveID.ScalarField())
 	if err != nil {
-		return
+		return nil, common.NewError(common.InternalError, err)
 	}
-
-	srsEncoded, err := os.ReadFile(filepath)
-	if err != nil {
-		return
+	var srs kzgg.SRS
+	switch curveID {
+	case ecc.BN254:
+		srs, err = kzg_bn254.NewSRS(uint64(size), alpha)
+	case ecc.BLS12_381:
+		srs, err = kzg_bls12381.NewSRS(uint64(size), alpha)
+	case ecc.BLS12_377:
+		srs, err = kzg_bls12377.NewSRS(uint64(size), alpha)
+	case ecc.BW6_761:
+		srs, err = kzg_bw6761.NewSRS(uint64(size), alpha)
+	default:
+		return nil, common.Errorf(common.SRSError, "SRS over %s are not supported", curveID)
 	}
-	decodedSrs, err := hex.DecodeString(string(srsEncoded))
 	if err != nil {
-		return
+		return nil, common.NewError(common.SRSError, err)
+	}
+	return srs, nil
+}
+
+// SRSSize returns the number of G1 points of srs, which bounds the size of the
+// circuits it can be used with.
+func SRSSize(srs kzgg.SRS) (int, error) {
+	switch srs := srs.(type) {
+	case *kzg_bn254.SRS:
+		return len(srs.G1), nil
+	case *kzg_bls12381.SRS:
+		return len(srs.G1), nil
+	case *kzg_bls12377.SRS:
+		return len(srs.G1), nil
+	case *kzg_bw6761.SRS:
+		return len(srs.G1), nil
 	}
+	return 0, common.Errorf(common.SRSError, "unsupported SRS %T", srs)
+}
+
+// The SRS loaded by the Rust side, parsed and checked once by LoadSRS and kept
+// until FreeSRS so that the PLONK functions are only handed their handle.
+var (
+	srsHandles = make(map[uint64]kzgg.SRS)
+	// Handles start at 1, so 0 never refers to an SRS.
+	lastSRSHandle   uint64
+	srsHandlesMutex sync.Mutex
+)
 
-	srs = kzgg.NewSRS(ecc.BN254)
-	srs.ReadFrom(bytes.NewReader(decodedSrs))
+// LoadSRS keeps srs until FreeSRS is called with the returned handle.
+func LoadSRS(srs kzgg.SRS) uint64 {
+	srsHandlesMutex.Lock()
+	defer srsHandlesMutex.Unlock()
 
-	return
+	lastSRSHandle++
+	srsHandles[lastSRSHandle] = srs
+	return lastSRSHandle
 }
 
-func SaveSRS(srs kzgg.SRS) (err error) {
-	// Make a hex encode of the SRS.
-	var serializedSRS bytes.Buffer
-	srs.WriteTo(&serializedSRS)
-	encodedSRS := hex.EncodeToString(serializedSRS.Bytes())
-
-	// Save the encoded SRS in a file named srs.hex in the user config dir.
-	// We need to save the encoded SRS because the struct VerifyingKey has a pointer
-	// to a SRS struct but we can't rely on a pointer because memory is volatile.
-	// When we deserialize the VerifyingKey we will deserialize the SRS and insert
-	// a valid pointer.
-	filepath, err := getFilePath()
-	if err != nil {
-		return err
+// SRSOf returns the SRS loaded with handle, failing if it was freed or is not
+// over curveID.
+func SRSOf(handle uint64, curveID ecc.ID) (kzgg.SRS, error) {
+	srsHandlesMutex.Lock()
+	srs, ok := srsHandles[handle]
+	srsHandlesMutex.Unlock()
+
+	if !ok {
+		return nil, common.Errorf(common.SRSError, "no SRS is loaded with handle %d", handle)
+	}
+	if srsCurveID(srs) != curveID {
+		return nil, common.Errorf(common.SRSError, "the SRS is over %s but the circuit over %s", srsCurveID(srs), curveID)
 	}
-	err = ioutil.WriteFile(filepath, []byte(encodedSRS), 0644)
+	return srs, nil
+}
 
-	return
+// FreeSRS forgets the SRS loaded with handle, if any.
+func FreeSRS(handle uint64) {
+	srsHandlesMutex.Lock()
+	defer srsHandlesMutex.Unlock()
+
+	delete(srsHandles, handle)
 }
 
-func TryLoadSRS(curveID ecc.ID) (srs kzgg.SRS, err error) {
-	// Load SRS if it is already generated.
-	srs, err = LoadSRS()
-	if err != nil {
-		// SRS wasn't generated so we generate it.
-		alpha, err2 := rand.Int(rand.Reader, curveID.ScalarField())
-		if err2 != nil {
-			err = err2
-			return
-		}
-		srs, err = kzg.NewSRS(1_000_000, alpha)
-		if err != nil {
-			return
-		}
-		SaveSRS(srs)
+func srsCurveID(srs kzgg.SRS) ecc.ID {
+	switch srs.(type) {
+	case *kzg_bn254.SRS:
+		return ecc.BN254
+	case *kzg_bls12381.SRS:
+		return ecc.BLS12_381
+	case *kzg_bls12377.SRS:
+		return ecc.BLS12_377
+	case *kzg_bw6761.SRS:
+		return ecc.BW6_761
 	}
-	return
+	return ecc.UNKNOWN
 }
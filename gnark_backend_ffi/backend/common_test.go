@@ -0,0 +1,31 @@
+package backend
+
+import (
+	"testing"
+
+	common "gnark_backend_ffi/internal"
+
+	"github.com/consensys/gnark-crypto/ecc"
+	"github.com/stretchr/testify/assert"
+)
+
+func TestLoadedSRSIsKeptUntilFreed(t *testing.T) {
+	srs, err := NewInsecureSRS(ecc.BN254, 8)
+	assert.NoError(t, err)
+
+	handle := LoadSRS(srs)
+	assert.NotEqual(t, handle, LoadSRS(srs))
+
+	loaded, err := SRSOf(handle, ecc.BN254)
+	assert.NoError(t, err)
+	assert.Same(t, srs, loaded)
+
+	_, err = SRSOf(handle, ecc.BLS12_381)
+	assert.Equal(t, common.SRSError, common.Code(err))
+
+	FreeSRS(handle)
+	_, err = SRSOf(handle, ecc.BN254)
+	assert.Equal(t, common.SRSError, common.Code(err))
+	_, err = SRSOf(0, ecc.BN254)
+	assert.Equal(t, common.SRSError, common.Code(err))
+}
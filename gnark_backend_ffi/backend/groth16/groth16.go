@@ -0,0 +1,234 @@
+package groth16_backend
+
+import (
+	"crypto/sha256"
+	"encoding/hex"
+	"gnark_backend_ffi/backend"
+	common "gnark_backend_ffi/internal"
+	"math/big"
+	"sync"
+
+	"github.com/consensys/gnark-crypto/ecc"
+	"github.com/consensys/gnark/backend/groth16"
+	"github.com/consensys/gnark/backend/witness"
+	"github.com/consensys/gnark/constraint"
+)
+
+type keyPair struct {
+	provingKey   groth16.ProvingKey
+	verifyingKey groth16.VerifyingKey
+}
+
+// maxKeyPairs bounds the number of circuits whose keys are kept around for
+// VerifyWithMeta, the least recently used ones being evicted first.
+const maxKeyPairs = 16
+
+// Groth16's setup samples fresh toxic waste on every call, so the keys used
+// by ProveWithMeta are kept around for VerifyWithMeta to check the proof
+// against. They are indexed by the digest of the R1CS they were generated
+// for and of its curve, which do not depend on the witness values.
+var (
+	keyPairs = make(map[string]keyPair)
+	// The digests of keyPairs, from the least to the most recently used.
+	keyPairsUsage []string
+	keyPairsMutex sync.Mutex
+)
+
+// SupportOf tells how the circuits built by this backend handle the calls to
+// the black box function called name in ACIR. The raw R1CS only holds the
+// arithmetic opcodes, so every call is left out.
+func SupportOf(name string) backend.BlackBoxFunctionSupport {
+	return backend.Unconstrained
+}
+
+func Preprocess(r RawR1CS, curveID ecc.ID) (pk groth16.ProvingKey, vk groth16.VerifyingKey, err error) {
+	r1cs, _, _, err := BuildR1CS(r, curveID)
+	if err != nil {
+		return
+	}
+
+	pk, vk, err = groth16.Setup(r1cs)
+	if err != nil {
+		err = common.NewError(common.InternalError, err)
+	}
+
+	return
+}
+
+func ProveWithPK(r RawR1CS, provingKey groth16.ProvingKey, curveID ecc.ID) (groth16.Proof, error) {
+	r1cs, witness, err := buildWitness(r, curveID)
+	if err != nil {
+		return nil, err
+	}
+	return prove(r1cs, provingKey, witness)
+}
+
+func VerifyWithVK(r RawR1CS, verifyingKey groth16.VerifyingKey, proof groth16.Proof, curveID ecc.ID) (bool, error) {
+	_, witness, err := buildWitness(r, curveID)
+	if err != nil {
+		return false, err
+	}
+	return verify(verifyingKey, proof, witness)
+}
+
+func ProveWithMeta(r RawR1CS, curveID ecc.ID) (groth16.Proof, error) {
+	r1cs, witness, err := buildWitness(r, curveID)
+	if err != nil {
+		return nil, err
+	}
+	keys, err := cachedKeyPair(r1cs)
+	if err != nil {
+		return nil, err
+	}
+	return prove(r1cs, keys.provingKey, witness)
+}
+
+// VerifyWithMeta verifies a proof generated by ProveWithMeta in the same
+// process. It fails with KeysUnavailable if the process does not hold the keys
+// of the circuit, because the proof was generated elsewhere or its keys were
+// evicted since, instead of failing to verify.
+func VerifyWithMeta(r RawR1CS, proof groth16.Proof, curveID ecc.ID) (bool, error) {
+	r1cs, witness, err := buildWitness(r, curveID)
+	if err != nil {
+		return false, err
+	}
+	keys, err := keptKeyPair(r1cs)
+	if err != nil {
+		return false, err
+	}
+	return verify(keys.verifyingKey, proof, witness)
+}
+
+// CircuitSize returns the number of constraints of the R1CS built for r,
+// which does not depend on the witness values.
+func CircuitSize(r RawR1CS, curveID ecc.ID) (int, error) {
+	r1cs, _, _, err := BuildR1CS(r, curveID)
+	if err != nil {
+		return 0, err
+	}
+	return r1cs.GetNbConstraints(), nil
+}
+
+func buildWitness(r RawR1CS, curveID ecc.ID) (constraint.R1CS, witness.Witness, error) {
+	r1cs, publicVariables, secretVariables, err := BuildR1CS(r, curveID)
+	if err != nil {
+		return nil, nil, err
+	}
+
+	// The ONE_WIRE is not part of the witness.
+	witness, err := backend.BuildWitnesses(curveID.ScalarField(), bigInts(publicVariables), bigInts(secretVariables), r1cs.GetNbPublicVariables()-1, r1cs.GetNbSecretVariables())
+	if err != nil {
+		return nil, nil, err
+	}
+
+	return r1cs, witness, nil
+}
+
+// prove tells an unsatisfied witness apart from a proving key that does not
+// belong to the circuit by solving the constraint system first.
+func prove(r1cs constraint.R1CS, provingKey groth16.ProvingKey, witness witness.Witness) (proof groth16.Proof, err error) {
+	err = r1cs.IsSolved(witness)
+	if err != nil {
+		return nil, common.NewError(common.UnsatisfiedConstraint, err)
+	}
+
+	// gnark indexes the key with the circuit sizes, so a key made for
+	// another circuit may panic instead of failing.
+	defer func() {
+		if r := recover(); r != nil {
+			proof, err = nil, common.Errorf(common.KeyMismatch, "the proving key does not match the circuit: %v", r)
+		}
+	}()
+
+	proof, err = groth16.Prove(r1cs, provingKey, witness)
+	if err != nil {
+		return nil, common.NewError(common.KeyMismatch, err)
+	}
+
+	return proof, nil
+}
+
+func verify(verifyingKey groth16.VerifyingKey, proof groth16.Proof, witness witness.Witness) (bool, error) {
+	publicWitness, err := witness.Public()
+	if err != nil {
+		return false, common.NewError(common.InternalError, err)
+	}
+
+	return groth16.Verify(proof, verifyingKey, publicWitness) == nil, nil
+}
+
+func cachedKeyPair(r1cs constraint.R1CS) (keyPair, error) {
+	keyPairsMutex.Lock()
+	defer keyPairsMutex.Unlock()
+
+	d, err := digest(r1cs)
+	if err != nil {
+		return keyPair{}, err
+	}
+	keys, ok := keyPairs[d]
+	if !ok {
+		pk, vk, err := groth16.Setup(r1cs)
+		if err != nil {
+			return keyPair{}, common.NewError(common.InternalError, err)
+		}
+		keys = keyPair{provingKey: pk, verifyingKey: vk}
+		if len(keyPairs) >= maxKeyPairs {
+			delete(keyPairs, keyPairsUsage[0])
+			keyPairsUsage = keyPairsUsage[1:]
+		}
+		keyPairs[d] = keys
+	}
+	markUsed(d)
+
+	return keys, nil
+}
+
+// keptKeyPair returns the keys cachedKeyPair generated for r1cs, failing if
+// they were never generated or were evicted since.
+func keptKeyPair(r1cs constraint.R1CS) (keyPair, error) {
+	keyPairsMutex.Lock()
+	defer keyPairsMutex.Unlock()
+
+	d, err := digest(r1cs)
+	if err != nil {
+		return keyPair{}, err
+	}
+	keys, ok := keyPairs[d]
+	if !ok {
+		return keyPair{}, common.Errorf(common.KeysUnavailable, "the keys of the circuit are not held by this process, prove with ProveWithPK and verify with VerifyWithVK instead")
+	}
+	markUsed(d)
+
+	return keys, nil
+}
+
+// markUsed moves d to the end of keyPairsUsage.
+func markUsed(d string) {
+	for i, used := range keyPairsUsage {
+		if used == d {
+			keyPairsUsage = append(keyPairsUsage[:i], keyPairsUsage[i+1:]...)
+			break
+		}
+	}
+	keyPairsUsage = append(keyPairsUsage, d)
+}
+
+func digest(cs constraint.ConstraintSystem) (string, error) {
+	h := sha256.New()
+	h.Write([]byte(cs.CurveID().String()))
+	_, err := cs.WriteTo(h)
+	if err != nil {
+		return "", common.NewError(common.InternalError, err)
+	}
+	return hex.EncodeToString(h.Sum(nil)), nil
+}
+
+// bigInts converts felts to the integers they stand for, which the witness
+// reduces modulo the order of its field.
+func bigInts(felts []common.Felt) []*big.Int {
+	integers := make([]*big.Int, len(felts))
+	for i, felt := range felts {
+		integers[i] = felt.BigInt()
+	}
+	return integers
+}
@@ -0,0 +1,252 @@
+package groth16_backend
+
+import (
+	"encoding/json"
+	"fmt"
+	"math/big"
+	"testing"
+
+	"gnark_backend_ffi/acir/opcode"
+	"gnark_backend_ffi/acir/term"
+	"gnark_backend_ffi/backend"
+	common "gnark_backend_ffi/internal"
+	backend_helpers "gnark_backend_ffi/internal/backend"
+
+	"github.com/consensys/gnark-crypto/ecc"
+	"github.com/stretchr/testify/assert"
+)
+
+// x⋅y - z == 0 and x⋅x + y⋅y - w == 0 where z is public.
+func testRawR1CS(x, y, z, w uint64) RawR1CS {
+	return testRawR1CSOver(ecc.BN254, x, y, z, w)
+}
+
+// testRawR1CSOver is testRawR1CS with the -1 coefficients of the scalar field
+// of curveID.
+func testRawR1CSOver(curveID ecc.ID, x, y, z, w uint64) RawR1CS {
+	one := felt(curveID, big.NewInt(1))
+	minusOne := felt(curveID, new(big.Int).Sub(curveID.ScalarField(), big.NewInt(1)))
+
+	return RawR1CS{
+		Gates: []RawGate{
+			{
+				MulTerms: term.MulTerms{{Coefficient: one, MultiplicandIndex: 1, MultiplierIndex: 2}},
+				AddTerms: term.SimpleTerms{{Coefficient: minusOne, VariableIndex: 3}},
+			},
+			{
+				MulTerms: term.MulTerms{
+					{Coefficient: one, MultiplicandIndex: 1, MultiplierIndex: 1},
+					{Coefficient: one, MultiplicandIndex: 2, MultiplierIndex: 2},
+				},
+				AddTerms: term.SimpleTerms{{Coefficient: minusOne, VariableIndex: 4}},
+			},
+		},
+		PublicInputs:   common.Witnesses{3},
+		Values:         []common.Felt{feltOf(curveID, x), feltOf(curveID, y), feltOf(curveID, z), feltOf(curveID, w)},
+		NumVariables:   5,
+		NumConstraints: 4,
+	}
+}
+
+// felt serializes value as an element of the scalar field of curveID, as the
+// Rust side does.
+func felt(curveID ecc.ID, value *big.Int) common.Felt {
+	return value.FillBytes(make([]byte, backend_helpers.FeltSize(curveID)))
+}
+
+func feltOf(curveID ecc.ID, value uint64) common.Felt {
+	return felt(curveID, new(big.Int).SetUint64(value))
+}
+
+func TestBuildR1CS(t *testing.T) {
+	r1cs, publicVariables, secretVariables, err := BuildR1CS(testRawR1CS(2, 3, 6, 13), ecc.BN254)
+
+	assert.NoError(t, err)
+	// ONE_WIRE plus witness 3.
+	assert.Equal(t, 2, r1cs.GetNbPublicVariables())
+	assert.Equal(t, 3, r1cs.GetNbSecretVariables())
+	// One constraint for the first gate and three for the second one.
+	assert.Equal(t, 4, r1cs.GetNbConstraints())
+	assert.Equal(t, []common.Felt{feltOf(ecc.BN254, 6)}, publicVariables)
+	assert.Equal(t, []common.Felt{feltOf(ecc.BN254, 2), feltOf(ecc.BN254, 3), feltOf(ecc.BN254, 13)}, secretVariables)
+}
+
+func TestBuildR1CSFailsWithUnknownWitness(t *testing.T) {
+	r := testRawR1CS(2, 3, 6, 13)
+	r.Values = r.Values[:3]
+
+	_, _, _, err := BuildR1CS(r, ecc.BN254)
+
+	assert.Equal(t, common.InvalidCircuit, common.Code(err))
+}
+
+func TestCircuitSizeDoesNotDependOnTheValues(t *testing.T) {
+	r := testRawR1CS(0, 0, 0, 0)
+
+	size, err := CircuitSize(r, ecc.BN254)
+
+	assert.NoError(t, err)
+	assert.Equal(t, 4, size)
+}
+
+func TestGroth16ProveAndVerifyWithKeys(t *testing.T) {
+	r := testRawR1CS(2, 3, 6, 13)
+
+	provingKey, verifyingKey, err := Preprocess(r, ecc.BN254)
+	assert.NoError(t, err)
+	proof, err := ProveWithPK(r, provingKey, ecc.BN254)
+	assert.NoError(t, err)
+
+	verifies, err := VerifyWithVK(r, verifyingKey, proof, ecc.BN254)
+	assert.NoError(t, err)
+	assert.True(t, verifies)
+
+	invalidPublicInput := testRawR1CS(2, 3, 7, 13)
+	verifies, err = VerifyWithVK(invalidPublicInput, verifyingKey, proof, ecc.BN254)
+	assert.NoError(t, err)
+	assert.False(t, verifies)
+}
+
+func TestGroth16ProveAndVerifyWithMeta(t *testing.T) {
+	r := testRawR1CS(5, 4, 20, 41)
+
+	proof, err := ProveWithMeta(r, ecc.BN254)
+	assert.NoError(t, err)
+
+	verifies, err := VerifyWithMeta(r, proof, ecc.BN254)
+	assert.NoError(t, err)
+	assert.True(t, verifies)
+
+	invalidPublicInput := testRawR1CS(5, 4, 21, 41)
+	verifies, err = VerifyWithMeta(invalidPublicInput, proof, ecc.BN254)
+	assert.NoError(t, err)
+	assert.False(t, verifies)
+}
+
+func TestGroth16CachedKeyPairsAreBounded(t *testing.T) {
+	r := testRawR1CS(5, 4, 20, 41)
+	proof, err := ProveWithMeta(r, ecc.BN254)
+	assert.NoError(t, err)
+
+	// Every circuit with another number of gates has another digest.
+	for i := 0; i < maxKeyPairs; i++ {
+		other := testRawR1CS(5, 4, 20, 41)
+		for j := 0; j <= i; j++ {
+			other.Gates = append(other.Gates, other.Gates[0])
+		}
+		_, err := ProveWithMeta(other, ecc.BN254)
+		assert.NoError(t, err)
+	}
+
+	assert.Len(t, keyPairs, maxKeyPairs)
+	assert.Len(t, keyPairsUsage, maxKeyPairs)
+	// The keys of r were evicted, so its proof can no longer be verified.
+	_, err = VerifyWithMeta(r, proof, ecc.BN254)
+	assert.Equal(t, common.KeysUnavailable, common.Code(err))
+}
+
+func TestGroth16VerifyWithMetaFailsWithoutTheKeys(t *testing.T) {
+	r := testRawR1CS(5, 4, 20, 41)
+	proof, err := ProveWithMeta(r, ecc.BN254)
+	assert.NoError(t, err)
+
+	// A process that did not prove r does not hold its keys.
+	other := testRawR1CS(5, 4, 20, 41)
+	other.Gates = append(other.Gates, other.Gates[0], other.Gates[0], other.Gates[0])
+	_, err = VerifyWithMeta(other, proof, ecc.BN254)
+	assert.Equal(t, common.KeysUnavailable, common.Code(err))
+}
+
+func TestGroth16ProveFailsWithUnsatisfiedConstraint(t *testing.T) {
+	r := testRawR1CS(2, 3, 7, 13)
+
+	_, err := ProveWithMeta(r, ecc.BN254)
+
+	assert.Equal(t, common.UnsatisfiedConstraint, common.Code(err))
+}
+
+func TestGroth16ProveFailsWithAnotherCircuitProvingKey(t *testing.T) {
+	r := testRawR1CS(2, 3, 6, 13)
+	otherCircuit := testRawR1CS(2, 3, 6, 13)
+	otherCircuit.Gates = otherCircuit.Gates[:1]
+	otherCircuit.NumConstraints = 1
+
+	provingKey, _, err := Preprocess(otherCircuit, ecc.BN254)
+	assert.NoError(t, err)
+
+	_, err = ProveWithPK(r, provingKey, ecc.BN254)
+
+	assert.Equal(t, common.KeyMismatch, common.Code(err))
+}
+
+func TestRawR1CSUnmarshalJSONFailsWithInvalidValues(t *testing.T) {
+	rawR1CSJSON := `{"gates":[],"public_inputs":[],"values":"zz","num_variables":1,"num_constraints":0}`
+
+	var r RawR1CS
+	err := json.Unmarshal([]byte(rawR1CSJSON), &r)
+
+	assert.Equal(t, common.InvalidValues, common.Code(err))
+}
+
+func TestRawR1CSUnmarshalJSON(t *testing.T) {
+	encodedCoefficient, nonEncodedCoefficient := backend_helpers.RandomEncodedFelt()
+	encodedConstantTerm, nonEncodedConstantTerm := backend_helpers.RandomEncodedFelt()
+	encodedValues, nonEncodedValues := backend_helpers.RandomEncodedFelts()
+	mulTerms := fmt.Sprintf(`[{"coefficient":"%s","multiplicand":1,"multiplier":2}]`, encodedCoefficient)
+	addTerms := fmt.Sprintf(`[{"coefficient":"%s","sum":2}]`, encodedCoefficient)
+	gate := fmt.Sprintf(`{"mul_terms":%s,"add_terms":%s,"constant_term":"%s"}`, mulTerms, addTerms, encodedConstantTerm)
+	rawR1CSJSON := fmt.Sprintf(`{"gates":[%s],"public_inputs":[2],"values":"%s","num_variables":3,"num_constraints":1}`, gate, encodedValues)
+
+	var r RawR1CS
+	err := json.Unmarshal([]byte(rawR1CSJSON), &r)
+
+	assert.NoError(t, err)
+	assert.Equal(t, 1, len(r.Gates))
+	assert.Equal(t, term.MulTerms{{Coefficient: nonEncodedCoefficient, MultiplicandIndex: 1, MultiplierIndex: 2}}, r.Gates[0].MulTerms)
+	assert.Equal(t, term.SimpleTerms{{Coefficient: nonEncodedCoefficient, VariableIndex: 2}}, r.Gates[0].AddTerms)
+	assert.Equal(t, nonEncodedConstantTerm, r.Gates[0].ConstantTerm)
+	assert.Equal(t, common.Witnesses{2}, r.PublicInputs)
+	assert.Equal(t, nonEncodedValues, r.Values)
+	assert.Equal(t, uint64(3), r.NumVariables)
+	assert.Equal(t, uint64(1), r.NumConstraints)
+}
+
+func TestGroth16ProveAndVerifyOverOtherCurves(t *testing.T) {
+	for _, curveID := range []ecc.ID{ecc.BLS12_381, ecc.BLS12_377, ecc.BW6_761} {
+		r := testRawR1CSOver(curveID, 2, 3, 6, 13)
+
+		provingKey, verifyingKey, err := Preprocess(r, curveID)
+		assert.NoError(t, err, curveID)
+		proof, err := ProveWithPK(r, provingKey, curveID)
+		assert.NoError(t, err, curveID)
+
+		verifies, err := VerifyWithVK(r, verifyingKey, proof, curveID)
+		assert.NoError(t, err, curveID)
+		assert.True(t, verifies, curveID)
+
+		invalidPublicInput := testRawR1CSOver(curveID, 2, 3, 7, 13)
+		verifies, err = VerifyWithVK(invalidPublicInput, verifyingKey, proof, curveID)
+		assert.NoError(t, err, curveID)
+		assert.False(t, verifies, curveID)
+
+		if curveID == ecc.BLS12_381 {
+			// The -1 of BN254 is another element of the scalar field of
+			// BLS12-381.
+			_, err = ProveWithPK(testRawR1CS(2, 3, 6, 13), provingKey, curveID)
+			assert.Equal(t, common.UnsatisfiedConstraint, common.Code(err))
+		}
+	}
+}
+
+func TestBuildR1CSFailsWithValuesOfAnotherField(t *testing.T) {
+	_, _, _, err := BuildR1CS(testRawR1CS(2, 3, 6, 13), ecc.BW6_761)
+
+	assert.Equal(t, common.InvalidValues, common.Code(err))
+}
+
+// The Rust side leaves the black box functions out of the raw R1CS.
+func TestSupportOfLeavesEveryBlackBoxFunctionUnconstrained(t *testing.T) {
+	for _, name := range opcode.BlackBoxFunctionNames() {
+		assert.Equal(t, backend.Unconstrained, SupportOf(name), name)
+	}
+}
@@ -0,0 +1,143 @@
+package groth16_backend
+
+import (
+	"encoding/json"
+
+	"gnark_backend_ffi/acir/term"
+	common "gnark_backend_ffi/internal"
+	backend_helpers "gnark_backend_ffi/internal/backend"
+)
+
+// RawR1CS is the Go mirror of the RawR1CS struct built by the Rust backend
+// wrapper (see src/gnark_backend_wrapper/groth16/acir_to_r1cs.rs).
+type RawR1CS struct {
+	Gates          []RawGate
+	PublicInputs   common.Witnesses
+	Values         []common.Felt
+	NumVariables   uint64
+	NumConstraints uint64
+}
+
+// RawGate represents the constraint
+// ∑ qM_i⋅(xa_i⋅xb_i) + ∑ qL_j⋅x_j + qC == 0
+type RawGate struct {
+	MulTerms     term.MulTerms
+	AddTerms     term.SimpleTerms
+	ConstantTerm common.Felt
+}
+
+func (r *RawR1CS) UnmarshalJSON(data []byte) error {
+	var rawR1CS struct {
+		Gates          []RawGate        `json:"gates"`
+		PublicInputs   common.Witnesses `json:"public_inputs"`
+		Values         string           `json:"values"`
+		NumVariables   uint64           `json:"num_variables"`
+		NumConstraints uint64           `json:"num_constraints"`
+	}
+	err := json.Unmarshal(data, &rawR1CS)
+	if err != nil {
+		return err
+	}
+
+	values, err := backend_helpers.DeserializeFelts(rawR1CS.Values)
+	if err != nil {
+		return err
+	}
+
+	r.Gates = rawR1CS.Gates
+	r.PublicInputs = rawR1CS.PublicInputs
+	r.Values = values
+	r.NumVariables = rawR1CS.NumVariables
+	r.NumConstraints = rawR1CS.NumConstraints
+
+	return nil
+}
+
+// RawMulTerm wraps a term.MulTerm to deserialize it from the RawR1CS format,
+// where terms are encoded as objects instead of ACIR tuples.
+type RawMulTerm struct {
+	term.MulTerm
+}
+
+// RawAddTerm wraps a term.SimpleTerm to deserialize it from the RawR1CS
+// format, where terms are encoded as objects instead of ACIR tuples.
+type RawAddTerm struct {
+	term.SimpleTerm
+}
+
+func (g *RawGate) UnmarshalJSON(data []byte) error {
+	var rawGate struct {
+		MulTerms     []RawMulTerm `json:"mul_terms"`
+		AddTerms     []RawAddTerm `json:"add_terms"`
+		ConstantTerm string       `json:"constant_term"`
+	}
+	err := json.Unmarshal(data, &rawGate)
+	if err != nil {
+		return err
+	}
+
+	mulTerms := make(term.MulTerms, 0, len(rawGate.MulTerms))
+	for _, mulTerm := range rawGate.MulTerms {
+		mulTerms = append(mulTerms, mulTerm.MulTerm)
+	}
+
+	addTerms := make(term.SimpleTerms, 0, len(rawGate.AddTerms))
+	for _, addTerm := range rawGate.AddTerms {
+		addTerms = append(addTerms, addTerm.SimpleTerm)
+	}
+
+	constantTerm, err := backend_helpers.DeserializeFelt(rawGate.ConstantTerm)
+	if err != nil {
+		return err
+	}
+
+	g.MulTerms = mulTerms
+	g.AddTerms = addTerms
+	g.ConstantTerm = constantTerm
+
+	return nil
+}
+
+func (m *RawMulTerm) UnmarshalJSON(data []byte) error {
+	var rawMulTerm struct {
+		Coefficient  string         `json:"coefficient"`
+		Multiplicand common.Witness `json:"multiplicand"`
+		Multiplier   common.Witness `json:"multiplier"`
+	}
+	err := json.Unmarshal(data, &rawMulTerm)
+	if err != nil {
+		return err
+	}
+
+	coefficient, err := backend_helpers.DeserializeFelt(rawMulTerm.Coefficient)
+	if err != nil {
+		return err
+	}
+
+	m.Coefficient = coefficient
+	m.MultiplicandIndex = rawMulTerm.Multiplicand
+	m.MultiplierIndex = rawMulTerm.Multiplier
+
+	return nil
+}
+
+func (a *RawAddTerm) UnmarshalJSON(data []byte) error {
+	var rawAddTerm struct {
+		Coefficient string         `json:"coefficient"`
+		Sum         common.Witness `json:"sum"`
+	}
+	err := json.Unmarshal(data, &rawAddTerm)
+	if err != nil {
+		return err
+	}
+
+	coefficient, err := backend_helpers.DeserializeFelt(rawAddTerm.Coefficient)
+	if err != nil {
+		return err
+	}
+
+	a.Coefficient = coefficient
+	a.VariableIndex = rawAddTerm.Sum
+
+	return nil
+}
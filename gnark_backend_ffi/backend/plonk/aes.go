@@ -0,0 +1,230 @@
+package plonk_backend
+
+import (
+	acir_opcode "gnark_backend_ffi/acir/opcode"
+	common "gnark_backend_ffi/internal"
+)
+
+// AES-128 encryption of 16 bytes blocks, see src/backend/aes.rs. Blocks are
+// 16 bytes, byte i being in column i / 4 and row i % 4 of the state. The
+// S-box is evaluated as the polynomial of degree 255 interpolating it over the
+// field, with Horner's rule on the value of the byte, which takes a gate per
+// coefficient, whereas the other steps XOR bits.
+
+const aesRounds = 10
+
+var aesSbox = [256]uint64{
+	0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+	0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+	0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+	0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+	0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+	0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+	0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+	0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+	0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+	0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+	0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+	0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+	0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+	0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+	0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+	0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+}
+
+// aesSboxPolynomial returns the coefficients of the polynomial P of degree
+// 255 such that P(x) = aesSbox[x] for every byte x, constant term first. It
+// is Σ aesSbox[i]⋅Z(x) / ((x - i)⋅Z'(i)) with Z(x) = Π (x - i), computed once
+// per builder.
+func (b *builder[T, E]) aesSboxPolynomial() []T {
+	if b.aesSboxCoefficients != nil {
+		return b.aesSboxCoefficients
+	}
+
+	// z holds the coefficients of Z.
+	z := make([]T, len(aesSbox)+1)
+	E(&z[0]).SetOne()
+	for i := range aesSbox {
+		var root T
+		E(&root).SetUint64(uint64(i))
+		for k := i + 1; k > 0; k-- {
+			var term T
+			E(&term).Mul(&z[k], &root)
+			E(&z[k]).Sub(&z[k-1], &term)
+		}
+		E(&z[0]).Mul(&z[0], &root)
+		E(&z[0]).Neg(&z[0])
+	}
+
+	polynomial := make([]T, len(aesSbox))
+	quotient := make([]T, len(aesSbox))
+	for i, value := range aesSbox {
+		// quotient = Z / (x - i) by synthetic division, and its value at i is
+		// Z'(i).
+		var root T
+		E(&root).SetUint64(uint64(i))
+		quotient[len(quotient)-1] = z[len(z)-1]
+		for k := len(quotient) - 1; k > 0; k-- {
+			E(&quotient[k-1]).Mul(&quotient[k], &root)
+			E(&quotient[k-1]).Add(&quotient[k-1], &z[k])
+		}
+		var derivative T
+		for k := len(quotient) - 1; k >= 0; k-- {
+			E(&derivative).Mul(&derivative, &root)
+			E(&derivative).Add(&derivative, &quotient[k])
+		}
+
+		var scale T
+		E(&scale).SetUint64(value)
+		E(&scale).Div(&scale, &derivative)
+		for k := range polynomial {
+			var term T
+			E(&term).Mul(&quotient[k], &scale)
+			E(&polynomial[k]).Add(&polynomial[k], &term)
+		}
+	}
+
+	b.aesSboxCoefficients = polynomial
+	return polynomial
+}
+
+// AES constrains its outputs to hold the encryption of the blocks of the
+// plaintext with the key. Its inputs are the 16 bytes of the key followed by
+// the bytes of the plaintext, whose blocks are encrypted independently.
+func AES[T comparable, E element[T]](bbf *acir_opcode.BlackBoxFunction, b *builder[T, E]) error {
+	bytes, err := b.inputBytes(bbf.Inputs)
+	if err != nil {
+		return err
+	}
+	if len(bytes) < 16 || len(bytes)%16 != 0 {
+		return common.Errorf(common.InvalidCircuit, "expected a 16 bytes key and 16 bytes blocks, got %d bytes", len(bytes))
+	}
+
+	roundKeys := b.aesExpandKey(bytes[:16])
+	var ciphertext []word
+	for i := 16; i < len(bytes); i += 16 {
+		ciphertext = append(ciphertext, b.aes128Encrypt(roundKeys, bytes[i:i+16])...)
+	}
+	return b.assertBytes(bbf.Outputs, ciphertext)
+}
+
+func (b *builder[T, E]) aes128Encrypt(roundKeys [][]word, plaintext []word) []word {
+	state := b.aesAddRoundKey(plaintext, roundKeys[0])
+	for round := 1; round <= aesRounds; round++ {
+		state = aesShiftRows(b.aesSubBytes(state))
+		if round < aesRounds {
+			state = b.aesMixColumns(state)
+		}
+		state = b.aesAddRoundKey(state, roundKeys[round])
+	}
+	return state
+}
+
+// aesExpandKey returns the round keys: the first one is the key, and every
+// column of the next ones is the XOR of the previous column and of the same
+// column of the previous round key, the first column of a round key XORing
+// the previous one rotated, substituted and XORed with the round constant.
+func (b *builder[T, E]) aesExpandKey(key []word) [][]word {
+	roundKeys := [][]word{key}
+	roundConstant := uint64(1)
+	for round := 1; round <= aesRounds; round++ {
+		previous := roundKeys[round-1]
+		column := b.aesSubBytes([]word{previous[13], previous[14], previous[15], previous[12]})
+		column[0] = b.xorConstant(column[0], roundConstant)
+		roundKey := make([]word, 0, 16)
+		for c := 0; c < 4; c++ {
+			for r := range column {
+				column[r] = b.xorWords(column[r], previous[4*c+r])
+			}
+			roundKey = append(roundKey, column...)
+		}
+		roundKeys = append(roundKeys, roundKey)
+		roundConstant = aesXtime(roundConstant)
+	}
+	return roundKeys
+}
+
+func (b *builder[T, E]) aesSubBytes(bytes []word) []word {
+	substituted := make([]word, len(bytes))
+	for i, x := range bytes {
+		substituted[i] = b.aesSbox(x)
+	}
+	return substituted
+}
+
+// aesSbox returns the bits of the image of the byte x by the S-box.
+func (b *builder[T, E]) aesSbox(x word) word {
+	value := b.fromBits(x)
+	xValue := b.value(value)
+	coefficients := b.aesSboxPolynomial()
+	n := len(coefficients)
+
+	// result = coefficients[n-1]⋅x + coefficients[n-2]
+	var resultValue T
+	E(&resultValue).Mul(&coefficients[n-1], &xValue)
+	E(&resultValue).Add(&resultValue, &coefficients[n-2])
+	result := b.newVariable(resultValue)
+	b.addGate(gate[T]{xa: value, xc: result, qL: coefficients[n-1], qO: b.minusOne(), qC: coefficients[n-2]})
+	for k := n - 3; k >= 0; k-- {
+		// result' = result⋅x + coefficients[k]
+		var nextValue T
+		E(&nextValue).Mul(&resultValue, &xValue)
+		E(&nextValue).Add(&nextValue, &coefficients[k])
+		next := b.newVariable(nextValue)
+		b.addGate(gate[T]{xa: result, xb: value, xc: next, qM: b.one(), qO: b.minusOne(), qC: coefficients[k]})
+		result, resultValue = next, nextValue
+	}
+
+	return b.toBits(result, 8)
+}
+
+// aesShiftRows rotates row r left by r bytes. It does not add any
+// constraint.
+func aesShiftRows(state []word) []word {
+	shifted := make([]word, len(state))
+	for c := 0; c < 4; c++ {
+		for r := 0; r < 4; r++ {
+			shifted[4*c+r] = state[4*((c+r)%4)+r]
+		}
+	}
+	return shifted
+}
+
+// aesMixColumns multiplies every column by the polynomial
+// 3⋅x³ + x² + x + 2 over GF(2⁸): a[r] becomes
+// a[r] ^ (a[0] ^ a[1] ^ a[2] ^ a[3]) ^ xtime(a[r] ^ a[r + 1]).
+func (b *builder[T, E]) aesMixColumns(state []word) []word {
+	mixed := make([]word, 0, len(state))
+	for c := 0; c < len(state); c += 4 {
+		a := state[c : c+4]
+		all := b.xorWords(a...)
+		for r := range a {
+			mixed = append(mixed, b.xorWords(a[r], all, b.aesXtimeWord(b.xorWords(a[r], a[(r+1)%4]))))
+		}
+	}
+	return mixed
+}
+
+// aesXtimeWord multiplies the byte x by x in GF(2⁸) =
+// GF(2)[x] / (x⁸ + x⁴ + x³ + x + 1): it is shifted left and XORed with 0x1b
+// when its most significant bit is set.
+func (b *builder[T, E]) aesXtimeWord(x word) word {
+	msb := x[7]
+	return word{msb, b.xor(x[0], msb), x[1], b.xor(x[2], msb), b.xor(x[3], msb), x[4], x[5], x[6]}
+}
+
+func aesXtime(x uint64) uint64 {
+	x <<= 1
+	if x&0x100 != 0 {
+		x ^= 0x11b
+	}
+	return x
+}
+
+func (b *builder[T, E]) aesAddRoundKey(state, roundKey []word) []word {
+	result := make([]word, len(state))
+	for i := range state {
+		result[i] = b.xorWords(state[i], roundKey[i])
+	}
+	return result
+}
@@ -0,0 +1,65 @@
+package plonk_backend
+
+import (
+	"testing"
+
+	"gnark_backend_ffi/acir/opcode"
+
+	fr_bls12381 "github.com/consensys/gnark-crypto/ecc/bls12-381/fr"
+	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
+	cs_bls12381 "github.com/consensys/gnark/constraint/bls12-381"
+	cs_bn254 "github.com/consensys/gnark/constraint/bn254"
+	"github.com/stretchr/testify/assert"
+)
+
+// FIPS-197, appendices B and C.1, and the second one encrypted as two blocks
+// with the same key, as for the native solver, see src/backend.rs.
+func TestAES(t *testing.T) {
+	testCases := []struct {
+		key, plaintext, ciphertext string
+	}{
+		{"2b7e151628aed2a6abf7158809cf4f3c", "3243f6a8885a308d313198a2e0370734", "3925841d02dc09fbdc118597196a0b32"},
+		{"000102030405060708090a0b0c0d0e0f", "00112233445566778899aabbccddeeff", "69c4e0d86a7b0430d8cdb78070b4c55a"},
+		{
+			"000102030405060708090a0b0c0d0e0f",
+			"00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff",
+			"69c4e0d86a7b0430d8cdb78070b4c55a69c4e0d86a7b0430d8cdb78070b4c55a",
+		},
+	}
+
+	for _, testCase := range testCases {
+		input := append(decodeHex(t, testCase.key), decodeHex(t, testCase.plaintext)...)
+		ciphertext := decodeHex(t, testCase.ciphertext)
+
+		circuit, values := testHashACIR(opcode.AES, input, ciphertext)
+		assert.NoError(t, isSolved(circuit, values), testCase.plaintext)
+
+		ciphertext[0] ^= 1
+		circuit, values = testHashACIR(opcode.AES, input, ciphertext)
+		assert.Error(t, isSolved(circuit, values), testCase.plaintext)
+	}
+}
+
+func TestAESRejectsPartialBlocks(t *testing.T) {
+	circuit, values := testHashACIR(opcode.AES, make([]byte, 20), make([]byte, 4))
+	assert.Error(t, isSolved(circuit, values))
+}
+
+func TestAESSboxPolynomial(t *testing.T) {
+	testAESSboxPolynomial(t, newBuilder[fr_bn254.Element, *fr_bn254.Element](cs_bn254.NewSparseR1CS(0), nil, nil, nil))
+	testAESSboxPolynomial(t, newBuilder[fr_bls12381.Element, *fr_bls12381.Element](cs_bls12381.NewSparseR1CS(0), nil, nil, nil))
+}
+
+func testAESSboxPolynomial[T comparable, E element[T]](t *testing.T, b *builder[T, E]) {
+	coefficients := b.aesSboxPolynomial()
+	for x, expected := range aesSbox {
+		var value, xValue, expectedValue T
+		E(&xValue).SetUint64(uint64(x))
+		for k := len(coefficients) - 1; k >= 0; k-- {
+			E(&value).Mul(&value, &xValue)
+			E(&value).Add(&value, &coefficients[k])
+		}
+		E(&expectedValue).SetUint64(expected)
+		assert.Equal(t, expectedValue, value, "%#x", x)
+	}
+}
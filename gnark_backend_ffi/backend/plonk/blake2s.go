@@ -0,0 +1,154 @@
+package plonk_backend
+
+import (
+	acir_opcode "gnark_backend_ffi/acir/opcode"
+	common "gnark_backend_ffi/internal"
+)
+
+// BLAKE2s-256 as specified in RFC 7693, without a key.
+
+var blake2sIV = [8]uint64{
+	0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+}
+
+var blake2sSigma = [10][16]int{
+	{0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15},
+	{14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3},
+	{11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4},
+	{7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8},
+	{9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13},
+	{2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9},
+	{12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11},
+	{13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10},
+	{6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5},
+	{10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0},
+}
+
+// Blake2s constrains its 32 outputs to hold the bytes of the BLAKE2s digest
+// of its inputs.
+func Blake2s[T comparable, E element[T]](bbf *acir_opcode.BlackBoxFunction, b *builder[T, E]) error {
+	message, err := b.inputBytes(bbf.Inputs)
+	if err != nil {
+		return err
+	}
+	return b.assertBytes(bbf.Outputs, b.blake2s(message))
+}
+
+// HashToField128Security constrains its output to hold the BLAKE2s digest of
+// its inputs, read as a big endian integer and reduced modulo the field order.
+func HashToField128Security[T comparable, E element[T]](bbf *acir_opcode.BlackBoxFunction, b *builder[T, E]) error {
+	if len(bbf.Outputs) != 1 {
+		return common.Errorf(common.InvalidCircuit, "expected 1 output, got %d", len(bbf.Outputs))
+	}
+	message, err := b.inputBytes(bbf.Inputs)
+	if err != nil {
+		return err
+	}
+	output, err := b.variable(bbf.Outputs[0])
+	if err != nil {
+		return err
+	}
+
+	// The last byte of the digest is the least significant one. The reduction
+	// is free: the linear combination is computed in the field.
+	digest := b.blake2s(message)
+	powers := b.powersOfTwo(8 * len(digest))
+	variables := make([]int, 0, len(powers))
+	for i := len(digest) - 1; i >= 0; i-- {
+		variables = append(variables, digest[i]...)
+	}
+	b.assertEqual(b.linearCombination(powers, variables), output)
+
+	return nil
+}
+
+// blake2s returns the bytes of the digest of message.
+func (b *builder[T, E]) blake2s(message []word) []word {
+	var h [8]word
+	for i, iv := range blake2sIV {
+		h[i] = b.constantWord(iv, 32)
+	}
+	// Parameter block: 32 bytes digest, no key, fanout and depth of 1.
+	h[0] = b.constantWord(blake2sIV[0]^0x01010020, 32)
+
+	// The message is split in 64 bytes blocks, the last one padded with
+	// zeros. An empty message is compressed as a single zero block.
+	nbBlocks := (len(message) + 63) / 64
+	if nbBlocks == 0 {
+		nbBlocks = 1
+	}
+	for i := 0; i < nbBlocks; i++ {
+		block := make([]word, 64)
+		for j := range block {
+			if 64*i+j < len(message) {
+				block[j] = message[64*i+j]
+			} else {
+				block[j] = b.constantWord(0, 8)
+			}
+		}
+
+		// The counter is the number of message bytes compressed so far.
+		last := i == nbBlocks-1
+		counter := uint64(64 * (i + 1))
+		if last {
+			counter = uint64(len(message))
+		}
+		h = b.blake2sCompress(h, block, counter, last)
+	}
+
+	digest := make([]word, 0, 32)
+	for _, w := range h {
+		digest = append(digest, w[0:8], w[8:16], w[16:24], w[24:32])
+	}
+	return digest
+}
+
+func (b *builder[T, E]) blake2sCompress(h [8]word, block []word, counter uint64, last bool) [8]word {
+	var m [16]word
+	for i := range m {
+		m[i] = littleEndianWord(block[4*i : 4*i+4]...)
+	}
+
+	// The counter and the final block flag are known when building the
+	// circuit, so they are folded in the constant words.
+	var v [16]word
+	copy(v[:8], h[:])
+	for i, iv := range blake2sIV {
+		v[8+i] = b.constantWord(iv, 32)
+	}
+	v[12] = b.constantWord(blake2sIV[4]^(counter&0xffffffff), 32)
+	v[13] = b.constantWord(blake2sIV[5]^(counter>>32), 32)
+	if last {
+		v[14] = b.constantWord(blake2sIV[6]^0xffffffff, 32)
+	}
+
+	for round := 0; round < 10; round++ {
+		s := blake2sSigma[round]
+		b.blake2sMix(&v, 0, 4, 8, 12, m[s[0]], m[s[1]])
+		b.blake2sMix(&v, 1, 5, 9, 13, m[s[2]], m[s[3]])
+		b.blake2sMix(&v, 2, 6, 10, 14, m[s[4]], m[s[5]])
+		b.blake2sMix(&v, 3, 7, 11, 15, m[s[6]], m[s[7]])
+		b.blake2sMix(&v, 0, 5, 10, 15, m[s[8]], m[s[9]])
+		b.blake2sMix(&v, 1, 6, 11, 12, m[s[10]], m[s[11]])
+		b.blake2sMix(&v, 2, 7, 8, 13, m[s[12]], m[s[13]])
+		b.blake2sMix(&v, 3, 4, 9, 14, m[s[14]], m[s[15]])
+	}
+
+	for i := range h {
+		h[i] = b.xorWords(h[i], v[i], v[i+8])
+	}
+	return h
+}
+
+// blake2sMix is the G function, mixing x and y into v[ia], v[ib], v[ic] and
+// v[id].
+func (b *builder[T, E]) blake2sMix(v *[16]word, ia, ib, ic, id int, x, y word) {
+	v[ia] = b.addWords(0, v[ia], v[ib], x)
+	v[id] = rotr(b.xorWords(v[id], v[ia]), 16)
+	v[ic] = b.addWords(0, v[ic], v[id])
+	v[ib] = rotr(b.xorWords(v[ib], v[ic]), 12)
+	v[ia] = b.addWords(0, v[ia], v[ib], y)
+	v[id] = rotr(b.xorWords(v[id], v[ia]), 8)
+	v[ic] = b.addWords(0, v[ic], v[id])
+	v[ib] = rotr(b.xorWords(v[ib], v[ic]), 7)
+}
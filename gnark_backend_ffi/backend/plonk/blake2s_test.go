@@ -0,0 +1,79 @@
+package plonk_backend
+
+import (
+	"math/big"
+	"testing"
+
+	"gnark_backend_ffi/acir/opcode"
+	common "gnark_backend_ffi/internal"
+
+	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
+	"github.com/stretchr/testify/assert"
+)
+
+// The digests were computed with Python's hashlib.blake2s, and the field
+// elements are the digests reduced modulo the BN254 scalar field order.
+var blake2sTestCases = []struct {
+	message []byte
+	digest  string
+	field   string
+}{
+	{
+		[]byte(""),
+		"69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9",
+		"3775512630636087568153581201926744924770092020825417501984072733438252216055",
+	},
+	{
+		[]byte("abc"),
+		"508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982",
+		"14544796147756800155470468636103266036554281360747348013150091996843875981697",
+	},
+	{
+		// 0x00, 0x01, ..., 0x63: two blocks, the last one partial.
+		rangeBytes(100),
+		"81dcc3a505eace3f879d8f702776770f9df50e521d1428a85daf04f9ad2150e0",
+		"14961928367366037350934331337277453701621754325937355565866200836547148861662",
+	},
+}
+
+func rangeBytes(n int) []byte {
+	bytes := make([]byte, n)
+	for i := range bytes {
+		bytes[i] = byte(i)
+	}
+	return bytes
+}
+
+func TestBlake2s(t *testing.T) {
+	for _, testCase := range blake2sTestCases {
+		digest := decodeHex(t, testCase.digest)
+
+		circuit, values := testHashACIR(opcode.Blake2s, testCase.message, digest)
+		assert.NoError(t, isSolved(circuit, values), testCase.digest)
+
+		digest[31] ^= 1
+		circuit, values = testHashACIR(opcode.Blake2s, testCase.message, digest)
+		assert.Error(t, isSolved(circuit, values), testCase.digest)
+	}
+}
+
+func TestHashToField128Security(t *testing.T) {
+	for _, testCase := range blake2sTestCases {
+		// The single output follows the message.
+		circuit, values := testHashACIR(opcode.HashToField128Security, testCase.message, nil)
+		output := common.Witness(len(values) + 1)
+		circuit.Opcodes[0].Data.(*opcode.BlackBoxFunction).Outputs = common.Witnesses{output}
+		circuit.PublicInputs = common.Witnesses{output}
+		circuit.CurrentWitness = output
+
+		field, ok := new(big.Int).SetString(testCase.field, 10)
+		assert.True(t, ok)
+		var expected fr_bn254.Element
+		expected.SetBigInt(field)
+		values = append(values, expected)
+		assert.NoError(t, isSolved(circuit, values), testCase.field)
+
+		values[len(values)-1].Add(&expected, &expected)
+		assert.Error(t, isSolved(circuit, values), testCase.field)
+	}
+}
@@ -0,0 +1,378 @@
+package plonk_backend
+
+import (
+	"fmt"
+	"math/big"
+
+	"gnark_backend_ffi/backend"
+	common "gnark_backend_ffi/internal"
+	backend_helpers "gnark_backend_ffi/internal/backend"
+
+	"github.com/consensys/gnark/constraint"
+)
+
+// element is the pointer type of the elements of a scalar field in
+// gnark-crypto, such as *fr_bn254.Element, which the builder computes the
+// values of the variables with.
+type element[T any] interface {
+	*T
+	SetOne() *T
+	SetUint64(v uint64) *T
+	SetInt64(v int64) *T
+	SetBytes(e []byte) *T
+	SetBigInt(v *big.Int) *T
+	Add(x, y *T) *T
+	Sub(x, y *T) *T
+	Mul(x, y *T) *T
+	Div(x, y *T) *T
+	Double(x *T) *T
+	Neg(x *T) *T
+	Inverse(x *T) *T
+	IsZero() bool
+	IsOne() bool
+	BigInt(res *big.Int) *big.Int
+}
+
+// builder adds gates to a sparse R1CS over the scalar field whose elements
+// are T. Gadgets need variables for their intermediate values, which are
+// added as secret variables whose values are computed from the values of the
+// circuit witnesses.
+type builder[T comparable, E element[T]] struct {
+	sparseR1CS constraint.SparseR1CS
+	indexMap   map[string]int
+	// The values of the variables indexed by their ids. Public variables
+	// come first, so a new secret variable is always the last one.
+	values            []T
+	nbPublicVariables int
+	// The variables holding constants, by value.
+	constants map[T]int
+	// The number of bits of the field elements.
+	nbBits int
+	// The coefficients of the polynomial of the AES S-box, computed by the
+	// first AES gadget.
+	aesSboxCoefficients []T
+}
+
+// gate is qL⋅xa + qR⋅xb + qO⋅xc + qM⋅(xa⋅xb) + qC == 0. Zero coefficients
+// leave their wires unused.
+type gate[T any] struct {
+	xa, xb, xc         int
+	qL, qR, qO, qM, qC T
+}
+
+func newBuilder[T comparable, E element[T]](sparseR1CS constraint.SparseR1CS, indexMap map[string]int, publicVariables []T, secretVariables []T) *builder[T, E] {
+	values := make([]T, 0, len(publicVariables)+len(secretVariables))
+	values = append(values, publicVariables...)
+	values = append(values, secretVariables...)
+
+	return &builder[T, E]{
+		sparseR1CS:        sparseR1CS,
+		indexMap:          indexMap,
+		values:            values,
+		nbPublicVariables: len(publicVariables),
+		constants:         make(map[T]int),
+		nbBits:            sparseR1CS.CurveID().ScalarField().BitLen(),
+	}
+}
+
+// secretVariables returns the values of the secret variables, including the
+// ones added by the gadgets.
+func (b *builder[T, E]) secretVariables() []T {
+	return b.values[b.nbPublicVariables:]
+}
+
+func (b *builder[T, E]) variable(witness common.Witness) (int, error) {
+	return backend.VariableIndex(b.indexMap, witness)
+}
+
+func (b *builder[T, E]) value(variable int) T {
+	return b.values[variable]
+}
+
+// element returns the field element a coefficient of the ACIR, an element of
+// Noir's field, stands for, see backend_helpers.Lift.
+func (b *builder[T, E]) element(felt common.Felt) (T, error) {
+	var e T
+	integer, err := backend_helpers.Lift(felt, b.sparseR1CS.CurveID())
+	if err != nil {
+		return e, err
+	}
+	E(&e).SetBigInt(integer)
+	return e, nil
+}
+
+func (b *builder[T, E]) one() T {
+	var one T
+	E(&one).SetOne()
+	return one
+}
+
+func (b *builder[T, E]) minusOne() T {
+	var minusOne T
+	E(&minusOne).SetInt64(-1)
+	return minusOne
+}
+
+func (b *builder[T, E]) newVariable(value T) int {
+	variable := b.sparseR1CS.AddSecretVariable(fmt.Sprintf("gadget_%d", len(b.values)))
+	b.values = append(b.values, value)
+	return variable
+}
+
+func (b *builder[T, E]) addGate(g gate[T]) {
+	qL := b.sparseR1CS.FromInterface(g.qL)
+	qR := b.sparseR1CS.FromInterface(g.qR)
+	qO := b.sparseR1CS.FromInterface(g.qO)
+	qM1 := b.sparseR1CS.FromInterface(g.qM)
+	qM2 := b.sparseR1CS.FromInterface(1)
+	qC := b.sparseR1CS.FromInterface(g.qC)
+
+	K := b.sparseR1CS.MakeTerm(&qC, 0)
+	K.MarkConstant()
+
+	b.sparseR1CS.AddConstraint(constraint.SparseR1C{
+		L: b.sparseR1CS.MakeTerm(&qL, g.xa),
+		R: b.sparseR1CS.MakeTerm(&qR, g.xb),
+		O: b.sparseR1CS.MakeTerm(&qO, g.xc),
+		M: [2]constraint.Term{b.sparseR1CS.MakeTerm(&qM1, g.xa), b.sparseR1CS.MakeTerm(&qM2, g.xb)},
+		K: K.CoeffID(),
+	})
+}
+
+// assertBoolean adds x⋅x - x == 0.
+func (b *builder[T, E]) assertBoolean(x int) {
+	b.addGate(gate[T]{xa: x, xb: x, qL: b.minusOne(), qM: b.one()})
+}
+
+// assertEqual adds x - y == 0.
+func (b *builder[T, E]) assertEqual(x, y int) {
+	b.addGate(gate[T]{xa: x, xb: y, qL: b.one(), qR: b.minusOne()})
+}
+
+// mul returns a new variable constrained to hold x⋅y.
+func (b *builder[T, E]) mul(x, y int) int {
+	var value T
+	xValue, yValue := b.value(x), b.value(y)
+	E(&value).Mul(&xValue, &yValue)
+	result := b.newVariable(value)
+	b.addGate(gate[T]{xa: x, xb: y, xc: result, qM: b.one(), qO: b.minusOne()})
+	return result
+}
+
+// and returns a new variable constrained to hold the AND of the booleans x
+// and y, which is x⋅y.
+func (b *builder[T, E]) and(x, y int) int {
+	return b.mul(x, y)
+}
+
+// xor returns a new variable constrained to hold the XOR of the booleans x
+// and y, which is x + y - 2⋅x⋅y.
+func (b *builder[T, E]) xor(x, y int) int {
+	var value, product T
+	xValue, yValue := b.value(x), b.value(y)
+	E(&product).Mul(&xValue, &yValue)
+	E(&product).Double(&product)
+	E(&value).Add(&xValue, &yValue)
+	E(&value).Sub(&value, &product)
+	result := b.newVariable(value)
+
+	var minusTwo T
+	E(&minusTwo).SetInt64(-2)
+	b.addGate(gate[T]{xa: x, xb: y, xc: result, qL: b.one(), qR: b.one(), qM: minusTwo, qO: b.minusOne()})
+	return result
+}
+
+// or returns a new variable constrained to hold the OR of the booleans x and
+// y, which is x + y - x⋅y.
+func (b *builder[T, E]) or(x, y int) int {
+	var value, product T
+	xValue, yValue := b.value(x), b.value(y)
+	E(&product).Mul(&xValue, &yValue)
+	E(&value).Add(&xValue, &yValue)
+	E(&value).Sub(&value, &product)
+	result := b.newVariable(value)
+	b.addGate(gate[T]{xa: x, xb: y, xc: result, qL: b.one(), qR: b.one(), qM: b.minusOne(), qO: b.minusOne()})
+	return result
+}
+
+// not returns a new variable constrained to hold the NOT of the boolean x,
+// which is 1 - x.
+func (b *builder[T, E]) not(x int) int {
+	value := b.one()
+	xValue := b.value(x)
+	E(&value).Sub(&value, &xValue)
+	result := b.newVariable(value)
+	b.addGate(gate[T]{xa: x, xc: result, qL: b.minusOne(), qO: b.minusOne(), qC: b.one()})
+	return result
+}
+
+// andNot returns a new variable constrained to hold (NOT x) AND y for the
+// booleans x and y, which is y - x⋅y.
+func (b *builder[T, E]) andNot(x, y int) int {
+	var value, product T
+	xValue, yValue := b.value(x), b.value(y)
+	E(&product).Mul(&xValue, &yValue)
+	E(&value).Sub(&yValue, &product)
+	result := b.newVariable(value)
+	b.addGate(gate[T]{xa: x, xb: y, xc: result, qR: b.one(), qM: b.minusOne(), qO: b.minusOne()})
+	return result
+}
+
+// selectVariables returns a new variable constrained to hold x if the boolean
+// bit is 1 and y otherwise, which is y + bit⋅(x - y).
+func (b *builder[T, E]) selectVariables(bit, x, y int) int {
+	difference := b.linearCombination([]T{b.one(), b.minusOne()}, []int{x, y})
+	product := b.mul(bit, difference)
+	return b.linearCombination([]T{b.one(), b.one()}, []int{y, product})
+}
+
+// zeroIf returns a new variable constrained to hold 0 if the boolean bit is 1
+// and x otherwise, which is x - bit⋅x.
+func (b *builder[T, E]) zeroIf(bit, x int) int {
+	var value T
+	if bitValue := b.value(bit); !E(&bitValue).IsOne() {
+		value = b.value(x)
+	}
+	result := b.newVariable(value)
+	b.addGate(gate[T]{xa: bit, xb: x, xc: result, qR: b.one(), qM: b.minusOne(), qO: b.minusOne()})
+	return result
+}
+
+// isEqualConstant returns a new boolean variable constrained to be 1 exactly
+// when x is equal to c.
+func (b *builder[T, E]) isEqualConstant(x int, c T) int {
+	var differenceValue, minusC T
+	xValue := b.value(x)
+	E(&differenceValue).Sub(&xValue, &c)
+	E(&minusC).Neg(&c)
+	difference := b.newVariable(differenceValue)
+	b.addGate(gate[T]{xa: x, xc: difference, qL: b.one(), qO: b.minusOne(), qC: minusC})
+	return b.isZero(difference)
+}
+
+// isZero returns a new boolean variable constrained to be 1 exactly when x is
+// 0: the prover gives the inverse of x, or 0 when there is none, and
+// x⋅inverse + result == 1 and x⋅result == 0.
+func (b *builder[T, E]) isZero(x int) int {
+	var inverseValue, resultValue T
+	if xValue := b.value(x); E(&xValue).IsZero() {
+		E(&resultValue).SetOne()
+	} else {
+		E(&inverseValue).Inverse(&xValue)
+	}
+
+	inverse := b.newVariable(inverseValue)
+	result := b.newVariable(resultValue)
+	b.addGate(gate[T]{xa: x, xb: inverse, xc: result, qM: b.one(), qO: b.one(), qC: b.minusOne()})
+	b.addGate(gate[T]{xa: x, xb: result, qM: b.one()})
+	return result
+}
+
+// linearCombination returns a new variable constrained to hold
+// Σ coefficients[i]⋅variables[i]. The first gate takes two terms and every
+// other one adds a term to the previous partial sum.
+func (b *builder[T, E]) linearCombination(coefficients []T, variables []int) int {
+	var value T
+	if len(variables) == 0 {
+		return b.constant(0)
+	}
+
+	// result = coefficients[0]⋅variables[0] + coefficients[1]⋅variables[1]
+	g := gate[T]{xa: variables[0], qL: coefficients[0], qO: b.minusOne()}
+	x := b.value(variables[0])
+	E(&value).Mul(&coefficients[0], &x)
+	if len(variables) > 1 {
+		var term T
+		y := b.value(variables[1])
+		E(&term).Mul(&coefficients[1], &y)
+		E(&value).Add(&value, &term)
+		g.xb, g.qR = variables[1], coefficients[1]
+	}
+	result := b.newVariable(value)
+	g.xc = result
+	b.addGate(g)
+
+	for i := 2; i < len(variables); i++ {
+		// result' = result + coefficients[i]⋅variables[i]
+		var term T
+		x := b.value(variables[i])
+		E(&term).Mul(&coefficients[i], &x)
+		E(&value).Add(&value, &term)
+		sum := b.newVariable(value)
+		b.addGate(gate[T]{xa: result, xb: variables[i], xc: sum, qL: b.one(), qR: coefficients[i], qO: b.minusOne()})
+		result = sum
+	}
+
+	return result
+}
+
+// addConstant returns a new variable constrained to hold x + constant.
+func (b *builder[T, E]) addConstant(x int, constant uint64) int {
+	var c, value T
+	E(&c).SetUint64(constant)
+	xValue := b.value(x)
+	E(&value).Add(&xValue, &c)
+	result := b.newVariable(value)
+	b.addGate(gate[T]{xa: x, xc: result, qL: b.one(), qO: b.minusOne(), qC: c})
+	return result
+}
+
+// constant returns a variable constrained to hold value. It is added once
+// and shared by every gadget.
+func (b *builder[T, E]) constant(value uint64) int {
+	var c T
+	E(&c).SetUint64(value)
+	return b.constantElement(c)
+}
+
+// constantElement is constant for any field element.
+func (b *builder[T, E]) constantElement(value T) int {
+	if variable, ok := b.constants[value]; ok {
+		return variable
+	}
+
+	variable := b.newVariable(value)
+	var c T
+	E(&c).Neg(&value)
+	b.addGate(gate[T]{xa: variable, qL: b.one(), qC: c})
+	b.constants[value] = variable
+
+	return variable
+}
+
+// toBits returns nbBits new boolean variables holding the little endian
+// binary decomposition of x, and constrains x to be equal to their
+// recomposition. nbBits must be smaller than the number of bits of the field
+// elements so that the decomposition is unique.
+func (b *builder[T, E]) toBits(x int, nbBits int) []int {
+	value := b.value(x)
+	integer := E(&value).BigInt(new(big.Int))
+	bits := make([]int, nbBits)
+	for i := range bits {
+		var bit T
+		E(&bit).SetUint64(uint64(integer.Bit(i)))
+		bits[i] = b.newVariable(bit)
+		b.assertBoolean(bits[i])
+	}
+
+	b.assertEqual(b.fromBits(bits), x)
+
+	return bits
+}
+
+// fromBits returns a new variable constrained to hold the number whose little
+// endian binary decomposition is bits.
+func (b *builder[T, E]) fromBits(bits []int) int {
+	return b.linearCombination(b.powersOfTwo(len(bits)), bits)
+}
+
+func (b *builder[T, E]) powersOfTwo(n int) []T {
+	powers := make([]T, n)
+	if n > 0 {
+		E(&powers[0]).SetOne()
+	}
+	for i := 1; i < n; i++ {
+		E(&powers[i]).Double(&powers[i-1])
+	}
+	return powers
+}
@@ -1,40 +1,78 @@
 package plonk_backend
 
-// AES black box function call is not handled
-func AES() {}
+import (
+	acir_opcode "gnark_backend_ffi/acir/opcode"
+	common "gnark_backend_ffi/internal"
+)
 
-// AND black box function call is not handled
-func AND() {}
+// AND constrains its output to be the bitwise AND of its two NumBits bits
+// inputs.
+func AND[T comparable, E element[T]](bbf *acir_opcode.BlackBoxFunction, b *builder[T, E]) error {
+	return bitwise(bbf, b, b.and)
+}
 
-// XOR black box function call is not handled
-func XOR() {}
+// XOR constrains its output to be the bitwise XOR of its two NumBits bits
+// inputs.
+func XOR[T comparable, E element[T]](bbf *acir_opcode.BlackBoxFunction, b *builder[T, E]) error {
+	return bitwise(bbf, b, b.xor)
+}
 
-// RANGE black box function call is not handled
-func Range() {}
+// bitwise decomposes both inputs, applies op to every pair of bits and
+// constrains the output to be the recomposition of the results. Decomposing
+// the inputs also constrains them to fit in NumBits bits.
+func bitwise[T comparable, E element[T]](bbf *acir_opcode.BlackBoxFunction, b *builder[T, E], op func(x, y int) int) error {
+	if len(bbf.Inputs) != 2 || len(bbf.Outputs) != 1 {
+		return common.Errorf(common.InvalidCircuit, "bitwise operations expect 2 inputs and 1 output, got %d and %d", len(bbf.Inputs), len(bbf.Outputs))
+	}
+	numBits := bbf.Inputs[0].NumBits
+	if bbf.Inputs[1].NumBits != numBits {
+		return common.Errorf(common.InvalidCircuit, "bitwise operation inputs have %d and %d bits", numBits, bbf.Inputs[1].NumBits)
+	}
+	if int(numBits) >= b.nbBits {
+		return common.Errorf(common.InvalidCircuit, "bitwise operations support up to %d bits, got %d", b.nbBits-1, numBits)
+	}
 
-// SHA256 black box function call is not handled
-func SHA256() {}
+	lhs, err := b.variable(bbf.Inputs[0].Witness)
+	if err != nil {
+		return err
+	}
+	rhs, err := b.variable(bbf.Inputs[1].Witness)
+	if err != nil {
+		return err
+	}
+	output, err := b.variable(bbf.Outputs[0])
+	if err != nil {
+		return err
+	}
 
-// Blake2s black box function call is not handled
-func Blake2s() {}
+	lhsBits := b.toBits(lhs, int(numBits))
+	rhsBits := b.toBits(rhs, int(numBits))
+	outputBits := make([]int, numBits)
+	for i := range outputBits {
+		outputBits[i] = op(lhsBits[i], rhsBits[i])
+	}
+	b.assertEqual(b.fromBits(outputBits), output)
 
-// MerkleMembership black box function call is not handled
-func MerkleMembership() {}
+	return nil
+}
 
-// SchnorrVerify black box function call is not handled
-func SchnorrVerify() {}
+// Range constrains its input to fit in NumBits bits by decomposing it into
+// NumBits boolean variables.
+func Range[T comparable, E element[T]](bbf *acir_opcode.BlackBoxFunction, b *builder[T, E]) error {
+	if len(bbf.Inputs) != 1 {
+		return common.Errorf(common.InvalidCircuit, "RANGE expects 1 input, got %d", len(bbf.Inputs))
+	}
+	input := bbf.Inputs[0]
+	x, err := b.variable(input.Witness)
+	if err != nil {
+		return err
+	}
 
-// Pedersen black box function call is not handled
-func Pedersen() {}
+	// Every field element fits in b.nbBits bits.
+	if int(input.NumBits) >= b.nbBits {
+		return nil
+	}
+	b.toBits(x, int(input.NumBits))
 
-// HashToField128Security black box function call is not handled
-func HashToField128Security() {}
-
-// EcdsaSecp256k1 black box function call is not handled
-func EcdsaSecp256k1() {}
-
-// FixedBaseScalarMul black box function call is not handled
-func FixedBaseScalarMul() {}
-
-// Keccak256 black box function call is not handled
-func Keccak256() {}
+	return nil
+}
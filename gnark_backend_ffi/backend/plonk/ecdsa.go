@@ -0,0 +1,255 @@
+package plonk_backend
+
+import (
+	"crypto/sha256"
+	"encoding/binary"
+	"math/big"
+
+	acir_opcode "gnark_backend_ffi/acir/opcode"
+	common "gnark_backend_ffi/internal"
+)
+
+// ECDSA signatures on secp256k1, the curve y² = x³ + 7, see
+// src/backend/ecdsa.rs. Its base field and its order are larger than the
+// field of the circuits, so its arithmetic is emulated, see emulated.go. A
+// signature (r, s) of the hashed message z by the public key P is valid if
+// 0 < r < n, 0 < s <= n / 2 and the abscissa of (z⋅G + r⋅P) / s is r modulo n.
+
+var (
+	secp256k1P, _ = new(big.Int).SetString("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f", 16)
+	secp256k1N, _ = new(big.Int).SetString("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141", 16)
+)
+
+// secp256k1Point is a point of the curve known when building the circuit.
+type secp256k1Point struct {
+	x, y *big.Int
+}
+
+// emulatedPoint is a point of the curve held by emulated coordinates.
+type emulatedPoint struct {
+	x, y emulated
+}
+
+var secp256k1Generator = func() secp256k1Point {
+	x, _ := new(big.Int).SetString("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798", 16)
+	y, _ := new(big.Int).SetString("483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8", 16)
+	return secp256k1Point{x, y}
+}()
+
+// The scalar multiplications start from this point, whose discrete logarithm
+// nobody knows, so that their additions never involve the point at infinity
+// nor two points of the same abscissa: x is SHA-256(domain || counter)
+// reduced modulo p, with counter as a big endian uint32, for the first counter
+// such that x³ + 7 is a square, and y is the smallest of its square roots.
+var secp256k1Offset = func() secp256k1Point {
+	for counter := uint32(0); ; counter++ {
+		var suffix [4]byte
+		binary.BigEndian.PutUint32(suffix[:], counter)
+		h := sha256.New()
+		h.Write([]byte("noir_backend_using_gnark/secp256k1_offset"))
+		h.Write(suffix[:])
+
+		x := new(big.Int).SetBytes(h.Sum(nil))
+		x.Mod(x, secp256k1P)
+		rhs := new(big.Int).Exp(x, big.NewInt(3), secp256k1P)
+		rhs.Add(rhs, big.NewInt(7)).Mod(rhs, secp256k1P)
+		if y := new(big.Int).ModSqrt(rhs, secp256k1P); y != nil {
+			if minusY := new(big.Int).Sub(secp256k1P, y); minusY.Cmp(y) < 0 {
+				y = minusY
+			}
+			return secp256k1Point{x, y}
+		}
+	}
+}()
+
+// EcdsaSecp256k1 constrains its output to be 1 if the signature is valid and
+// 0 otherwise. Its inputs are the 160 bytes of the coordinates of the public
+// key, of r and s and of the hashed message, as 32 bytes big endian integers.
+func EcdsaSecp256k1(bbf *acir_opcode.BlackBoxFunction, b bn254Builder) error {
+	if len(bbf.Outputs) != 1 {
+		return common.Errorf(common.InvalidCircuit, "expected 1 output, got %d", len(bbf.Outputs))
+	}
+	bytes, err := b.inputBytes(bbf.Inputs)
+	if err != nil {
+		return err
+	}
+	if len(bytes) != 160 {
+		return common.Errorf(common.InvalidCircuit, "expected 160 bytes, got %d bytes", len(bytes))
+	}
+	output, err := b.variable(bbf.Outputs[0])
+	if err != nil {
+		return err
+	}
+
+	verified := b.ecdsaSecp256k1Verify(bytes[:32], bytes[32:64], bytes[64:96], bytes[96:128], bytes[128:])
+	b.assertEqual(verified, output)
+	return nil
+}
+
+// ecdsaSecp256k1Verify returns a new boolean variable constrained to be 1
+// exactly when (r, s) is a signature of hashedMessage by the public key (x, y).
+func (b bn254Builder) ecdsaSecp256k1Verify(x, y, r, s, hashedMessage []word) int {
+	publicKey := emulatedPoint{b.emulatedFromBits(bigEndianWord(x...)), b.emulatedFromBits(bigEndianWord(y...))}
+	rValue := b.emulatedFromBits(bigEndianWord(r...))
+	sValue := b.emulatedFromBits(bigEndianWord(s...))
+	z := b.emulatedFromBits(bigEndianWord(hashedMessage...))
+
+	// Malformed inputs are replaced by valid ones, which keep the constraints
+	// satisfiable, and the result is then 0.
+	halfN := new(big.Int).Rsh(secp256k1N, 1)
+	rValid := b.andNot(b.isZeroEmulated(rValue), b.isLessThanConstant(rValue.bits, secp256k1N))
+	sValid := b.andNot(b.isZeroEmulated(sValue), b.isLessThanConstant(sValue.bits, halfN.Add(halfN, big.NewInt(1))))
+	one := b.constantEmulated(big.NewInt(1))
+	rValue = b.selectEmulated(rValid, rValue, one)
+	sValue = b.selectEmulated(sValid, sValue, one)
+
+	safeKey := b.constantSecp256k1Point(secp256k1Generator.double())
+	keyValid := b.and(b.isLessThanConstant(publicKey.x.bits, secp256k1P), b.isLessThanConstant(publicKey.y.bits, secp256k1P))
+	publicKey = b.selectEmulatedPoint(keyValid, publicKey, safeKey)
+	onCurve := b.isOnSecp256k1(publicKey)
+	keyValid = b.and(keyValid, onCurve)
+	publicKey = b.selectEmulatedPoint(onCurve, publicKey, safeKey)
+
+	// w = 1 / s, u1 = z⋅w and u2 = r⋅w modulo n.
+	w := b.newEmulated(new(big.Int).ModInverse(b.emulatedValue(sValue), secp256k1N))
+	b.assertCongruent(secp256k1N, []emulatedProduct{{1, sValue, w}}, nil, big.NewInt(-1))
+	u1 := b.mulModulo(secp256k1N, z, w)
+	u2 := b.mulModulo(secp256k1N, rValue, w)
+
+	point, isInfinity := b.secp256k1DoubleScalarMul(u1.bits, u2.bits, publicKey)
+	b.assertCanonical(secp256k1P, point.x)
+	equal := b.isZeroModulo(secp256k1N, nil, []emulatedTerm{{1, point.x}, {-1, rValue}}, new(big.Int))
+
+	valid := b.and(b.and(rValid, sValid), keyValid)
+	return b.and(valid, b.andNot(isInfinity, equal))
+}
+
+// secp256k1DoubleScalarMul returns u1⋅G + u2⋅P, given the little endian bits
+// of u1 and u2, and a boolean variable holding whether the sum is the point at
+// infinity, the coordinates being meaningless then. The bits are processed
+// together from the most significant one, the sum starting from the offset:
+// every step doubles it and conditionally adds G, P or G + P.
+//
+// G + P can not be computed with the incomplete addition when P is G or -G,
+// the only points of the same abscissa as G, so it is then selected instead:
+// it is 2⋅G when P is G, and when P is -G it is the point at infinity, which
+// is not added at all.
+func (b bn254Builder) secp256k1DoubleScalarMul(u1, u2 []int, p emulatedPoint) (emulatedPoint, int) {
+	generator := b.constantSecp256k1Point(secp256k1Generator)
+	doubleGenerator := b.constantSecp256k1Point(secp256k1Generator.double())
+	sameX := b.isZeroModulo(secp256k1P, nil, []emulatedTerm{{1, p.x}}, new(big.Int).Neg(secp256k1Generator.x))
+	sameY := b.isZeroModulo(secp256k1P, nil, []emulatedTerm{{1, p.y}}, new(big.Int).Neg(secp256k1Generator.y))
+	isGenerator := b.and(sameX, sameY)
+	isMinusGenerator := b.andNot(sameY, sameX)
+	// 3⋅G when P is ±G, whose abscissa is not the one of G.
+	sum := b.addSecp256k1Points(generator, b.selectEmulatedPoint(sameX, doubleGenerator, p))
+	sum = b.selectEmulatedPoint(isGenerator, doubleGenerator, sum)
+
+	accumulator := b.constantSecp256k1Point(secp256k1Offset)
+	offset := secp256k1Offset
+	for i := len(u1) - 1; i >= 0; i-- {
+		accumulator = b.doubleSecp256k1Point(accumulator)
+		offset = offset.double()
+		addend := b.selectEmulatedPoint(u1[i], b.selectEmulatedPoint(u2[i], sum, generator), p)
+		adds := b.andNot(b.and(b.and(u1[i], u2[i]), isMinusGenerator), b.or(u1[i], u2[i]))
+		accumulator = b.selectEmulatedPoint(adds, b.addSecp256k1Points(accumulator, addend), accumulator)
+	}
+
+	// The accumulator is the multiple of the offset when the sum is the point
+	// at infinity, it is then replaced by another point to keep the last
+	// addition valid.
+	isInfinity := b.isZeroModulo(secp256k1P, nil, []emulatedTerm{{1, accumulator.x}}, new(big.Int).Neg(offset.x))
+	safe := b.selectEmulatedPoint(isInfinity, b.constantSecp256k1Point(offset.double()), accumulator)
+	return b.addSecp256k1Points(safe, b.constantSecp256k1Point(offset.neg())), isInfinity
+}
+
+// double returns 2⋅p, p not being the point at infinity. No point of the curve
+// has order 2.
+func (p secp256k1Point) double() secp256k1Point {
+	numerator := new(big.Int).Mul(p.x, p.x)
+	numerator.Mul(numerator, big.NewInt(3))
+	lambda := divModulo(numerator, new(big.Int).Lsh(p.y, 1), secp256k1P)
+	return p.lineIntersection(lambda, p.x)
+}
+
+func (p secp256k1Point) neg() secp256k1Point {
+	return secp256k1Point{p.x, new(big.Int).Sub(secp256k1P, p.y)}
+}
+
+// lineIntersection returns the sum of p and the point of abscissa x on the
+// line of slope λ going through p.
+func (p secp256k1Point) lineIntersection(lambda, x *big.Int) secp256k1Point {
+	sumX := new(big.Int).Mul(lambda, lambda)
+	sumX.Sub(sumX, p.x).Sub(sumX, x).Mod(sumX, secp256k1P)
+	sumY := new(big.Int).Sub(p.x, sumX)
+	sumY.Mul(sumY, lambda).Sub(sumY, p.y).Mod(sumY, secp256k1P)
+	return secp256k1Point{sumX, sumY}
+}
+
+// divModulo returns numerator / denominator modulo the prime modulus, or 0
+// when the denominator is a multiple of it.
+func divModulo(numerator, denominator, modulus *big.Int) *big.Int {
+	inverse := new(big.Int).ModInverse(new(big.Int).Mod(denominator, modulus), modulus)
+	if inverse == nil {
+		return new(big.Int)
+	}
+	quotient := new(big.Int).Mul(numerator, inverse)
+	return quotient.Mod(quotient, modulus)
+}
+
+func (b bn254Builder) constantSecp256k1Point(p secp256k1Point) emulatedPoint {
+	return emulatedPoint{b.constantEmulated(p.x), b.constantEmulated(p.y)}
+}
+
+// selectEmulatedPoint returns new variables constrained to hold p if the
+// boolean bit is 1 and q otherwise.
+func (b bn254Builder) selectEmulatedPoint(bit int, p, q emulatedPoint) emulatedPoint {
+	return emulatedPoint{b.selectEmulated(bit, p.x, q.x), b.selectEmulated(bit, p.y, q.y)}
+}
+
+func (b bn254Builder) pointValue(p emulatedPoint) secp256k1Point {
+	return secp256k1Point{b.emulatedValue(p.x), b.emulatedValue(p.y)}
+}
+
+// addSecp256k1Points returns new variables constrained to hold p + q, with the
+// incomplete addition formulas: the constraints can not be satisfied when p
+// and q have the same abscissa.
+func (b bn254Builder) addSecp256k1Points(p, q emulatedPoint) emulatedPoint {
+	// λ⋅(q.x - p.x) ≡ q.y - p.y
+	pValue, qValue := b.pointValue(p), b.pointValue(q)
+	lambdaValue := divModulo(new(big.Int).Sub(qValue.y, pValue.y), new(big.Int).Sub(qValue.x, pValue.x), secp256k1P)
+	lambda := b.newEmulated(lambdaValue)
+	b.assertCongruent(secp256k1P, []emulatedProduct{{1, lambda, q.x}, {-1, lambda, p.x}}, []emulatedTerm{{-1, q.y}, {1, p.y}}, new(big.Int))
+
+	return b.secp256k1LineIntersection(lambda, p, q.x)
+}
+
+// doubleSecp256k1Point returns new variables constrained to hold 2⋅p.
+func (b bn254Builder) doubleSecp256k1Point(p emulatedPoint) emulatedPoint {
+	// λ⋅2⋅p.y ≡ 3⋅p.x²
+	pValue := b.pointValue(p)
+	numerator := new(big.Int).Mul(pValue.x, pValue.x)
+	numerator.Mul(numerator, big.NewInt(3))
+	lambda := b.newEmulated(divModulo(numerator, new(big.Int).Lsh(pValue.y, 1), secp256k1P))
+	b.assertCongruent(secp256k1P, []emulatedProduct{{2, lambda, p.y}, {-3, p.x, p.x}}, nil, new(big.Int))
+
+	return b.secp256k1LineIntersection(lambda, p, p.x)
+}
+
+// secp256k1LineIntersection returns new variables constrained to hold the sum
+// of p and the point of abscissa x on the line of slope λ going through p:
+// (λ² - p.x - x, λ⋅(p.x - (λ² - p.x - x)) - p.y).
+func (b bn254Builder) secp256k1LineIntersection(lambda emulated, p emulatedPoint, x emulated) emulatedPoint {
+	sumValue := b.pointValue(p).lineIntersection(b.emulatedValue(lambda), b.emulatedValue(x))
+	sum := emulatedPoint{b.newEmulated(sumValue.x), b.newEmulated(sumValue.y)}
+	b.assertCongruent(secp256k1P, []emulatedProduct{{1, lambda, lambda}}, []emulatedTerm{{-1, p.x}, {-1, x}, {-1, sum.x}}, new(big.Int))
+	b.assertCongruent(secp256k1P, []emulatedProduct{{1, lambda, p.x}, {-1, lambda, sum.x}}, []emulatedTerm{{-1, p.y}, {-1, sum.y}}, new(big.Int))
+	return sum
+}
+
+// isOnSecp256k1 returns a new boolean variable constrained to be 1 exactly when
+// p is on the curve, that is y² - x³ - 7 ≡ 0.
+func (b bn254Builder) isOnSecp256k1(p emulatedPoint) int {
+	xSquare := b.mulModulo(secp256k1P, p.x, p.x)
+	return b.isZeroModulo(secp256k1P, []emulatedProduct{{1, p.y, p.y}, {-1, xSquare, p.x}}, nil, big.NewInt(-7))
+}
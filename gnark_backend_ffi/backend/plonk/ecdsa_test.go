@@ -0,0 +1,133 @@
+package plonk_backend
+
+import (
+	"math/big"
+	"testing"
+
+	"gnark_backend_ffi/acir"
+	"gnark_backend_ffi/acir/opcode"
+	common "gnark_backend_ffi/internal"
+
+	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
+	"github.com/stretchr/testify/assert"
+)
+
+// testEcdsaSecp256k1ACIR verifies the signature given by the first witnesses,
+// holding the bytes of the inputs, into the last witness, holding verified.
+func testEcdsaSecp256k1ACIR(inputBytes []byte, verified bool) (acir.ACIR, fr_bn254.Vector) {
+	var inputs opcode.FunctionInputs
+	var values fr_bn254.Vector
+	for _, b := range inputBytes {
+		values = append(values, fr_bn254.NewElement(uint64(b)))
+		inputs = append(inputs, opcode.FunctionInput{Witness: common.Witness(len(values)), NumBits: 8})
+	}
+	var output fr_bn254.Element
+	if verified {
+		output.SetOne()
+	}
+	values = append(values, output)
+
+	circuit := acir.ACIR{
+		CurrentWitness: common.Witness(len(values)),
+		Opcodes: []opcode.Opcode{
+			{Data: &opcode.BlackBoxFunction{Name: opcode.EcdsaSecp256k1, Inputs: inputs, Outputs: common.Witnesses{common.Witness(len(values))}}},
+		},
+		PublicInputs: common.Witnesses{common.Witness(len(values))},
+	}
+
+	return circuit, values
+}
+
+// The same vector as the native solver, see src/backend/ecdsa.rs: the public
+// key, r, s and SHA-256("hello").
+const ecdsaSecp256k1Inputs = "c2dd8b4d1e91daadcf305153a4cb4caf471f2cfe0c49bad5f912e6330a7fac09" +
+	"c06bb3a4a5745826bc149fe131e41092f7fd367fa5e497311a38c150136c61bf" +
+	"4f064b69736960bad78e428f71f8276a63dda86a573eae887ffd48a985afc967" +
+	"2e447ec0526c8f9eb8165f911efa8386d545de4efa02d56e44af5826d9410846" +
+	"2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+
+// 2²⁵⁶ - 1, larger than both p and n.
+const ecdsaSecp256k1FF = "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+
+// The circuit takes a few million gates, so the test is skipped in short mode.
+func TestEcdsaSecp256k1(t *testing.T) {
+	if testing.Short() {
+		t.Skip("the circuit is large")
+	}
+	inputs := decodeHex(t, ecdsaSecp256k1Inputs)
+
+	circuit, values := testEcdsaSecp256k1ACIR(inputs, true)
+	assert.NoError(t, isSolved(circuit, values))
+	circuit, values = testEcdsaSecp256k1ACIR(inputs, false)
+	assert.Error(t, isSolved(circuit, values))
+
+	// Invalid and malformed signatures are rejected.
+	testCases := []struct {
+		name  string
+		patch func(inputs []byte)
+	}{
+		{"tampered message", func(inputs []byte) { inputs[159] ^= 1 }},
+		{"tampered signature", func(inputs []byte) { inputs[100] ^= 1 }},
+		{"public key off the curve", func(inputs []byte) { inputs[63] ^= 2 }},
+		{"public key larger than p", func(inputs []byte) { copy(inputs[:32], decodeHex(t, ecdsaSecp256k1FF)) }},
+		{"r = 0", func(inputs []byte) { copy(inputs[64:96], make([]byte, 32)) }},
+		{"s >= n", func(inputs []byte) { copy(inputs[96:128], decodeHex(t, ecdsaSecp256k1FF)) }},
+		// n - s is a valid signature which isn't normalized.
+		{"high s", func(inputs []byte) {
+			copy(inputs[96:128], decodeHex(t, "d1bb813fad93706147e9a06ee1057c77e568fe97b545cacd7b230665f6f538fb"))
+		}},
+	}
+	for _, testCase := range testCases {
+		patched := append([]byte{}, inputs...)
+		testCase.patch(patched)
+		circuit, values := testEcdsaSecp256k1ACIR(patched, false)
+		assert.NoError(t, isSolved(circuit, values), testCase.name)
+		circuit, values = testEcdsaSecp256k1ACIR(patched, true)
+		assert.Error(t, isSolved(circuit, values), testCase.name)
+	}
+}
+
+// Signatures of SHA-256("hello") by the private keys 1 and n - 1, whose public
+// keys are G and -G, with the nonce 1. The native solver accepts them too.
+var ecdsaSecp256k1GeneratorInputs = []string{
+	"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798" +
+		"483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8" +
+		"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798" +
+		"594f4bc6a672a1458377623f6bbf12599cfcc1af61d33503f2dba9cf25b29185" +
+		"2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+	"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798" +
+		"b7c52588d95c3b9aa25b0403f1eef75702e84bb7597aabe663b82f6f04ef2777" +
+		"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798" +
+		"4ccc18c49a2c189e2eb8276b08cd2868e785de7f0e26e67ae6ee4df8836c7f74" +
+		"2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+}
+
+func TestEcdsaSecp256k1WithThePublicKeyGOrMinusG(t *testing.T) {
+	if testing.Short() {
+		t.Skip("the circuit is large")
+	}
+	for _, encodedInputs := range ecdsaSecp256k1GeneratorInputs {
+		inputs := decodeHex(t, encodedInputs)
+		circuit, values := testEcdsaSecp256k1ACIR(inputs, true)
+		assert.NoError(t, isSolved(circuit, values))
+
+		inputs[159] ^= 1
+		circuit, values = testEcdsaSecp256k1ACIR(inputs, false)
+		assert.NoError(t, isSolved(circuit, values))
+	}
+}
+
+func TestEcdsaSecp256k1RejectsOtherLengths(t *testing.T) {
+	inputs := decodeHex(t, ecdsaSecp256k1Inputs)
+	circuit, values := testEcdsaSecp256k1ACIR(inputs[:159], false)
+	assert.Error(t, isSolved(circuit, values))
+}
+
+func TestSecp256k1OffsetIsOnTheCurve(t *testing.T) {
+	for _, p := range []secp256k1Point{secp256k1Generator, secp256k1Offset, secp256k1Offset.double()} {
+		lhs := new(big.Int).Mul(p.y, p.y)
+		rhs := new(big.Int).Exp(p.x, big.NewInt(3), nil)
+		rhs.Add(rhs, big.NewInt(7))
+		assert.Zero(t, lhs.Sub(lhs, rhs).Mod(lhs, secp256k1P).Sign())
+	}
+}
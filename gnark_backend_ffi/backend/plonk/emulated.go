@@ -0,0 +1,338 @@
+package plonk_backend
+
+import (
+	"math/big"
+
+	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
+)
+
+// Arithmetic modulo primes larger than the field of the circuits, such as the
+// fields of secp256k1. An integer smaller than 2²⁵⁶ is held by limbs of 64
+// bits, and a congruence between such integers is checked as an equality over
+// the integers with a multiple of the modulus given by the prover. The
+// equality is checked limb by limb, carrying the excess of every limb into the
+// next one, and every variable involved is range checked so that no sum wraps
+// around the field modulus.
+
+const (
+	limbBits = 64
+	nbLimbs  = 4
+)
+
+// emulated is an integer smaller than 2²⁵⁶ held by nbLimbs limbs of limbBits
+// bits, least significant first. bits holds its little endian bits when they
+// are known.
+type emulated struct {
+	limbs [nbLimbs]int
+	bits  []int
+}
+
+// emulatedProduct is coefficient⋅x⋅y and emulatedTerm is coefficient⋅x in a
+// congruence.
+type emulatedProduct struct {
+	coefficient int64
+	x, y        emulated
+}
+
+type emulatedTerm struct {
+	coefficient int64
+	x           emulated
+}
+
+var limbMax = new(big.Int).Sub(new(big.Int).Lsh(big.NewInt(1), limbBits), big.NewInt(1))
+
+var emulatedMax = new(big.Int).Sub(new(big.Int).Lsh(big.NewInt(1), limbBits*nbLimbs), big.NewInt(1))
+
+// newEmulated returns new variables holding value, which must be smaller than
+// 2²⁵⁶, with their bits.
+func (b bn254Builder) newEmulated(value *big.Int) emulated {
+	var x emulated
+	for i := range x.limbs {
+		var limb fr_bn254.Element
+		limb.SetBigInt(limbOf(value, i))
+		x.limbs[i] = b.newVariable(limb)
+		x.bits = append(x.bits, b.toBits(x.limbs[i], limbBits)...)
+	}
+	return x
+}
+
+// emulatedFromBits returns new variables holding the number whose 256 little
+// endian bits are bits.
+func (b bn254Builder) emulatedFromBits(bits []int) emulated {
+	x := emulated{bits: bits}
+	for i := range x.limbs {
+		x.limbs[i] = b.fromBits(bits[i*limbBits : (i+1)*limbBits])
+	}
+	return x
+}
+
+func (b bn254Builder) constantEmulated(value *big.Int) emulated {
+	var x emulated
+	for i := range x.limbs {
+		var limb fr_bn254.Element
+		limb.SetBigInt(limbOf(value, i))
+		x.limbs[i] = b.constantElement(limb)
+	}
+	return x
+}
+
+// emulatedBits returns the 256 little endian bits of x.
+func (b bn254Builder) emulatedBits(x emulated) []int {
+	if x.bits != nil {
+		return x.bits
+	}
+	bits := make([]int, 0, limbBits*nbLimbs)
+	for _, limb := range x.limbs {
+		bits = append(bits, b.toBits(limb, limbBits)...)
+	}
+	return bits
+}
+
+func (b bn254Builder) emulatedValue(x emulated) *big.Int {
+	value := new(big.Int)
+	for i := len(x.limbs) - 1; i >= 0; i-- {
+		limb := b.value(x.limbs[i])
+		bytes := limb.Bytes()
+		value.Lsh(value, limbBits).Add(value, new(big.Int).SetBytes(bytes[:]))
+	}
+	return value
+}
+
+// selectEmulated returns new variables constrained to hold x if the boolean
+// bit is 1 and y otherwise.
+func (b bn254Builder) selectEmulated(bit int, x, y emulated) emulated {
+	var selected emulated
+	for i := range selected.limbs {
+		selected.limbs[i] = b.selectVariables(bit, x.limbs[i], y.limbs[i])
+	}
+	return selected
+}
+
+// mulModulo returns new variables constrained to hold x⋅y modulo modulus.
+func (b bn254Builder) mulModulo(modulus *big.Int, x, y emulated) emulated {
+	product := new(big.Int).Mul(b.emulatedValue(x), b.emulatedValue(y))
+	result := b.newEmulated(product.Mod(product, modulus))
+	b.assertCongruent(modulus, []emulatedProduct{{1, x, y}}, []emulatedTerm{{-1, result}}, new(big.Int))
+	return result
+}
+
+// isZeroModulo returns a new boolean variable constrained to be 1 exactly when
+// Σ products + Σ terms + constant is a multiple of modulus. The prover gives
+// the remainder, which is constrained to be smaller than the modulus.
+func (b bn254Builder) isZeroModulo(modulus *big.Int, products []emulatedProduct, terms []emulatedTerm, constant *big.Int) int {
+	value := b.emulatedSum(products, terms, constant)
+	remainder := b.newEmulated(value.Mod(value, modulus))
+	b.assertCanonical(modulus, remainder)
+	b.assertCongruent(modulus, products, append(append([]emulatedTerm{}, terms...), emulatedTerm{-1, remainder}), constant)
+	return b.isZeroEmulated(remainder)
+}
+
+// assertCanonical constrains x to be smaller than modulus.
+func (b bn254Builder) assertCanonical(modulus *big.Int, x emulated) {
+	b.assertLessOrEqualConstant(b.emulatedBits(x), new(big.Int).Sub(modulus, big.NewInt(1)))
+}
+
+// isZeroEmulated returns a new boolean variable constrained to be 1 exactly
+// when x is zero.
+func (b bn254Builder) isZeroEmulated(x emulated) int {
+	// The limbs are not negative, so they are all zero when their sum is.
+	ones := []fr_bn254.Element{fr_bn254.One(), fr_bn254.One(), fr_bn254.One(), fr_bn254.One()}
+	return b.isZero(b.linearCombination(ones, x.limbs[:]))
+}
+
+// isLessThanConstant returns a new boolean variable constrained to be 1
+// exactly when the number whose little endian bits are bits is smaller than
+// c. Walking from the most significant bit, equal holds whether the bits seen
+// so far are the ones of c, and less becomes 1 at the first zero bit where c
+// has a one.
+func (b bn254Builder) isLessThanConstant(bits []int, c *big.Int) int {
+	less, equal := b.constant(0), b.constant(1)
+	for i := len(bits) - 1; i >= 0; i-- {
+		if c.Bit(i) == 1 {
+			less = b.linearCombination([]fr_bn254.Element{fr_bn254.One(), fr_bn254.One()}, []int{less, b.andNot(bits[i], equal)})
+			equal = b.and(equal, bits[i])
+		} else {
+			equal = b.andNot(bits[i], equal)
+		}
+	}
+	return less
+}
+
+// assertCongruent constrains Σ products + Σ terms + constant to be a multiple
+// of modulus. The sum is shifted by a multiple of the modulus to be
+// nonnegative whatever the values of the variables, and the prover gives the
+// quotient q of the shifted sum by the modulus, so that the constraint is that
+// the shifted sum minus q⋅modulus is zero over the integers.
+func (b bn254Builder) assertCongruent(modulus *big.Int, products []emulatedProduct, terms []emulatedTerm, constant *big.Int) {
+	// The bounds of the sum.
+	productMax := new(big.Int).Mul(emulatedMax, emulatedMax)
+	lowest, highest := new(big.Int).Set(constant), new(big.Int).Set(constant)
+	for _, product := range products {
+		bound := new(big.Int).Mul(big.NewInt(product.coefficient), productMax)
+		if product.coefficient < 0 {
+			lowest.Add(lowest, bound)
+		} else {
+			highest.Add(highest, bound)
+		}
+	}
+	for _, term := range terms {
+		bound := new(big.Int).Mul(big.NewInt(term.coefficient), emulatedMax)
+		if term.coefficient < 0 {
+			lowest.Add(lowest, bound)
+		} else {
+			highest.Add(highest, bound)
+		}
+	}
+
+	// shift = ⌈-lowest / modulus⌉⋅modulus, which is at least -constant.
+	shift := new(big.Int)
+	if lowest.Sign() < 0 {
+		shift.Neg(lowest).Add(shift, modulus).Sub(shift, big.NewInt(1))
+		shift.Div(shift, modulus).Mul(shift, modulus)
+	}
+	shiftedConstant := new(big.Int).Add(constant, shift)
+	quotientMax := new(big.Int).Add(highest, shift)
+	quotientMax.Div(quotientMax, modulus)
+
+	// The quotient, whose last limb may be shorter.
+	quotientValue := b.emulatedSum(products, terms, shiftedConstant)
+	quotientValue.Div(quotientValue, modulus)
+	quotientBits := quotientMax.BitLen()
+	var quotient []int
+	for i := 0; i*limbBits < quotientBits; i++ {
+		var limb fr_bn254.Element
+		limb.SetBigInt(limbOf(quotientValue, i))
+		quotient = append(quotient, b.newVariable(limb))
+		nbBits := quotientBits - i*limbBits
+		if nbBits > limbBits {
+			nbBits = limbBits
+		}
+		b.toBits(quotient[i], nbBits)
+	}
+
+	// The terms of every limb of the equality, as coefficients of variables,
+	// with the bound of their absolute value.
+	nbColumns := 2*nbLimbs - 1
+	if n := len(quotient) + nbLimbs - 1; n > nbColumns {
+		nbColumns = n
+	}
+	if n := limbCount(shiftedConstant); n > nbColumns {
+		nbColumns = n
+	}
+	columns := make([]limbColumn, nbColumns)
+	for i := range columns {
+		columns[i].constant = limbOf(shiftedConstant, i)
+		columns[i].bound = new(big.Int).Set(columns[i].constant)
+	}
+	limbProductMax := new(big.Int).Mul(limbMax, limbMax)
+	for _, product := range products {
+		for i, x := range product.x.limbs {
+			for j, y := range product.y.limbs {
+				columns[i+j].add(b.mul(x, y), big.NewInt(product.coefficient), limbProductMax)
+			}
+		}
+	}
+	for _, term := range terms {
+		for i, x := range term.x.limbs {
+			columns[i].add(x, big.NewInt(term.coefficient), limbMax)
+		}
+	}
+	for i, q := range quotient {
+		for j := 0; j < nbLimbs; j++ {
+			columns[i+j].add(q, new(big.Int).Neg(limbOf(modulus, j)), limbMax)
+		}
+	}
+
+	b.assertZeroOverIntegers(columns)
+}
+
+// limbColumn is Σ coefficients[i]⋅variables[i] + constant, whose absolute
+// value is at most bound.
+type limbColumn struct {
+	coefficients []*big.Int
+	variables    []int
+	constant     *big.Int
+	bound        *big.Int
+}
+
+func (c *limbColumn) add(variable int, coefficient *big.Int, variableMax *big.Int) {
+	c.coefficients = append(c.coefficients, coefficient)
+	c.variables = append(c.variables, variable)
+	c.bound.Add(c.bound, new(big.Int).Mul(new(big.Int).Abs(coefficient), variableMax))
+}
+
+// assertZeroOverIntegers constrains Σ columns[i]⋅2^(64⋅i) to be zero over the
+// integers. The prover gives the carries, carry[i] being
+// (columns[i] + carry[i-1]) / 2⁶⁴, which must be exact, and the last column
+// plus the last carry must be zero. A carry may be negative, so it is shifted
+// by its bound before being range checked.
+func (b bn254Builder) assertZeroOverIntegers(columns []limbColumn) {
+	carry, carryValue, carryBound := -1, new(big.Int), new(big.Int)
+	for i, column := range columns {
+		coefficients := make([]fr_bn254.Element, 0, len(column.variables)+2)
+		variables := append([]int{}, column.variables...)
+		value := new(big.Int).Set(column.constant)
+		for j, coefficient := range column.coefficients {
+			coefficients = append(coefficients, bigElement(coefficient))
+			x := b.value(column.variables[j])
+			bytes := x.Bytes()
+			value.Add(value, new(big.Int).Mul(coefficient, new(big.Int).SetBytes(bytes[:])))
+		}
+		// constant - carryBound + shiftedCarry is the incoming carry.
+		constant := new(big.Int).Sub(column.constant, carryBound)
+		if carry >= 0 {
+			coefficients = append(coefficients, fr_bn254.One())
+			variables = append(variables, carry)
+		}
+		value.Add(value, carryValue)
+		bound := new(big.Int).Add(column.bound, carryBound)
+
+		if i < len(columns)-1 {
+			// The outgoing carry is subtracted as -2⁶⁴⋅(shiftedCarry - carryBound).
+			carryValue = new(big.Int).Rsh(value, limbBits)
+			carryBound = new(big.Int).Rsh(bound, limbBits)
+			var shiftedCarry fr_bn254.Element
+			shiftedCarry.SetBigInt(new(big.Int).Add(carryValue, carryBound))
+			carry = b.newVariable(shiftedCarry)
+			b.toBits(carry, new(big.Int).Lsh(carryBound, 1).BitLen())
+			base := new(big.Int).Lsh(big.NewInt(1), limbBits)
+			coefficients = append(coefficients, bigElement(new(big.Int).Neg(base)))
+			variables = append(variables, carry)
+			constant.Add(constant, new(big.Int).Mul(base, carryBound))
+		}
+
+		sum := b.linearCombination(coefficients, variables)
+		b.addGate(bn254Gate{xa: sum, qL: fr_bn254.One(), qC: bigElement(constant)})
+	}
+}
+
+// emulatedSum returns the value of Σ products + Σ terms + constant.
+func (b bn254Builder) emulatedSum(products []emulatedProduct, terms []emulatedTerm, constant *big.Int) *big.Int {
+	sum := new(big.Int).Set(constant)
+	for _, product := range products {
+		value := new(big.Int).Mul(b.emulatedValue(product.x), b.emulatedValue(product.y))
+		sum.Add(sum, value.Mul(value, big.NewInt(product.coefficient)))
+	}
+	for _, term := range terms {
+		value := new(big.Int).Mul(b.emulatedValue(term.x), big.NewInt(term.coefficient))
+		sum.Add(sum, value)
+	}
+	return sum
+}
+
+// limbOf returns the i-th limb of the nonnegative value.
+func limbOf(value *big.Int, i int) *big.Int {
+	limb := new(big.Int).Rsh(value, uint(i*limbBits))
+	return limb.And(limb, limbMax)
+}
+
+// limbCount returns the number of limbs of the nonnegative value.
+func limbCount(value *big.Int) int {
+	return (value.BitLen() + limbBits - 1) / limbBits
+}
+
+func bigElement(value *big.Int) fr_bn254.Element {
+	var e fr_bn254.Element
+	e.SetBigInt(value)
+	return e
+}
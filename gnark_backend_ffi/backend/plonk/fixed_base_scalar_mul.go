@@ -0,0 +1,34 @@
+package plonk_backend
+
+import (
+	acir_opcode "gnark_backend_ffi/acir/opcode"
+	common "gnark_backend_ffi/internal"
+)
+
+// FixedBaseScalarMul constrains its 2 outputs to hold the coordinates of its
+// input times the generator of Grumpkin, (0, 0) standing for the point at
+// infinity.
+func FixedBaseScalarMul(bbf *acir_opcode.BlackBoxFunction, b bn254Builder) error {
+	if len(bbf.Inputs) != 1 || len(bbf.Outputs) != 2 {
+		return common.Errorf(common.InvalidCircuit, "expected 1 input and 2 outputs, got %d and %d", len(bbf.Inputs), len(bbf.Outputs))
+	}
+	scalar, err := b.variable(bbf.Inputs[0].Witness)
+	if err != nil {
+		return err
+	}
+
+	product := b.multiScalarMul([]scalarTerm{{bits: b.toCanonicalBits(scalar), base: grumpkinOne}})
+	return b.assertPoint(bbf.Outputs, product)
+}
+
+// assertPoint constrains the two outputs to hold the coordinates of p.
+func (b bn254Builder) assertPoint(outputs common.Witnesses, p pointVariable) error {
+	for i, coordinate := range []int{p.x, p.y} {
+		output, err := b.variable(outputs[i])
+		if err != nil {
+			return err
+		}
+		b.assertEqual(coordinate, output)
+	}
+	return nil
+}
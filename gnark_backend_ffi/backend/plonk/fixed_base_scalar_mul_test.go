@@ -0,0 +1,92 @@
+package plonk_backend
+
+import (
+	"testing"
+
+	"gnark_backend_ffi/acir"
+	"gnark_backend_ffi/acir/opcode"
+	common "gnark_backend_ffi/internal"
+
+	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
+	"github.com/stretchr/testify/assert"
+)
+
+// testPointACIR calls the function on the first witnesses, holding the
+// inputs, into the two witnesses after them, holding the coordinates of the
+// resulting point.
+func testPointACIR(name int, inputs fr_bn254.Vector, x, y fr_bn254.Element) (acir.ACIR, fr_bn254.Vector) {
+	var functionInputs opcode.FunctionInputs
+	for i := range inputs {
+		functionInputs = append(functionInputs, opcode.FunctionInput{Witness: common.Witness(i + 1), NumBits: fr_bn254.Bits})
+	}
+	outputs := common.Witnesses{common.Witness(len(inputs) + 1), common.Witness(len(inputs) + 2)}
+	values := append(append(fr_bn254.Vector{}, inputs...), x, y)
+
+	circuit := acir.ACIR{
+		CurrentWitness: common.Witness(len(values)),
+		Opcodes: []opcode.Opcode{
+			{Data: &opcode.BlackBoxFunction{Name: name, Inputs: functionInputs, Outputs: outputs}},
+		},
+		PublicInputs: outputs,
+	}
+
+	return circuit, values
+}
+
+func hexElement(t *testing.T, s string) fr_bn254.Element {
+	var e fr_bn254.Element
+	e.SetBytes(decodeHex(t, s))
+	return e
+}
+
+// The same vectors as the native solver, see test_solve_fixed_base_scalar_mul
+// in src/backend.rs.
+func TestFixedBaseScalarMul(t *testing.T) {
+	one := fr_bn254.One()
+	var minusOne fr_bn254.Element
+	minusOne.Neg(&one)
+
+	testCases := []struct {
+		scalar fr_bn254.Element
+		x, y   string
+	}{
+		{
+			one,
+			"0000000000000000000000000000000000000000000000000000000000000001",
+			"0000000000000002cf135e7506a45d632d270d45f1181294833fc48d823f272c",
+		},
+		{
+			fr_bn254.NewElement(2),
+			"06ce1b0827aafa85ddeb49cdaa36306d19a74caa311e13d46d8bc688cdbffffe",
+			"1c122f81a3a14964909ede0ba2a6855fc93faf6fa1a788bf467be7e7a43f80ac",
+		},
+		{
+			fr_bn254.NewElement(42),
+			"1043dd984b697ba6c4861b6e33bd8bad627385a2598c7f669879663d8b521add",
+			"127804e7e5bfe71f1bcee05d237daa28340c90e1bd439dc978fe2b84cc127d8b",
+		},
+		{
+			minusOne,
+			"2b9d4f8d4925e90f1e8b70d68e9cbe55b2f19d785cd2def25827f1d638cdb59d",
+			"0cd340c3cdf8f2bed9c2298fea4719f023e3c98962d9185e4d4f92e6ca0987a3",
+		},
+		{
+			// The point at infinity.
+			fr_bn254.NewElement(0),
+			"00",
+			"00",
+		},
+	}
+
+	for _, testCase := range testCases {
+		x, y := hexElement(t, testCase.x), hexElement(t, testCase.y)
+		inputs := fr_bn254.Vector{testCase.scalar}
+
+		circuit, values := testPointACIR(opcode.FixedBaseScalarMul, inputs, x, y)
+		assert.NoError(t, isSolved(circuit, values), testCase.x)
+
+		y.Add(&y, &one)
+		circuit, values = testPointACIR(opcode.FixedBaseScalarMul, inputs, x, y)
+		assert.Error(t, isSolved(circuit, values), testCase.x)
+	}
+}
@@ -0,0 +1,198 @@
+package plonk_backend
+
+import (
+	"crypto/sha256"
+	"encoding/binary"
+	"math/big"
+
+	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
+)
+
+// Grumpkin, the curve y² = x³ - 17 defined over the scalar field of BN254,
+// see src/backend/grumpkin.rs. Its points are pairs of field elements, so its
+// arithmetic only takes a few gates, and every field element is a valid
+// scalar.
+
+// bn254Builder is a builder over the scalar field of BN254, which the gadgets
+// working with Grumpkin points and emulated secp256k1 arithmetic need.
+type bn254Builder struct {
+	*builder[fr_bn254.Element, *fr_bn254.Element]
+}
+
+type bn254Gate = gate[fr_bn254.Element]
+
+// point is a point of the curve known when building the circuit.
+type point struct {
+	x, y fr_bn254.Element
+}
+
+// pointVariable is a point of the curve held by two variables, (0, 0)
+// standing for the point at infinity.
+type pointVariable struct {
+	x, y int
+}
+
+// scalarTerm is a scalar, given by its little endian bits, times a point
+// known when building the circuit.
+type scalarTerm struct {
+	bits []int
+	base point
+}
+
+// grumpkinOne is the generator of barretenberg, (1, √-16).
+var grumpkinOne = func() point {
+	y, _ := new(big.Int).SetString("2cf135e7506a45d632d270d45f1181294833fc48d823f272c", 16)
+	var p point
+	p.x.SetOne()
+	p.y.SetBigInt(y)
+	return p
+}()
+
+// The scalar multiplications start from this point so that their additions
+// never involve the point at infinity nor two equal points.
+var grumpkinOffset = grumpkinGenerator("noir_backend_using_gnark/offset", 0)
+
+// grumpkinGenerator derives the index-th generator of a domain by hashing to
+// the curve: x is SHA-256(domain || index || counter) reduced modulo the field
+// order, with index and counter as big endian uint32, for the first counter
+// such that x³ - 17 is a square, and y is the smallest of its square roots.
+func grumpkinGenerator(domain string, index uint32) point {
+	var b fr_bn254.Element
+	b.SetInt64(-17)
+	for counter := uint32(0); ; counter++ {
+		var suffix [8]byte
+		binary.BigEndian.PutUint32(suffix[:4], index)
+		binary.BigEndian.PutUint32(suffix[4:], counter)
+		h := sha256.New()
+		h.Write([]byte(domain))
+		h.Write(suffix[:])
+
+		var p point
+		var rhs fr_bn254.Element
+		p.x.SetBytes(h.Sum(nil))
+		rhs.Square(&p.x).Mul(&rhs, &p.x).Add(&rhs, &b)
+		if p.y.Sqrt(&rhs) != nil {
+			if p.y.LexicographicallyLargest() {
+				p.y.Neg(&p.y)
+			}
+			return p
+		}
+	}
+}
+
+// double returns 2⋅p, p being neither the point at infinity nor of order 2.
+func (p point) double() point {
+	var lambda, numerator, denominator fr_bn254.Element
+	numerator.Square(&p.x)
+	numerator.Add(&numerator, &numerator).Add(&numerator, &numerator)
+	denominator.Double(&p.y)
+	lambda.Div(&numerator, &denominator)
+
+	var doubled point
+	doubled.x.Square(&lambda).Sub(&doubled.x, &p.x).Sub(&doubled.x, &p.x)
+	doubled.y.Sub(&p.x, &doubled.x).Mul(&doubled.y, &lambda).Sub(&doubled.y, &p.y)
+	return doubled
+}
+
+func (p point) neg() point {
+	p.y.Neg(&p.y)
+	return p
+}
+
+// multiScalarMul returns Σ scalar⋅base over the terms, (0, 0) standing for
+// the point at infinity. The sum starts from the offset, every bit of a scalar
+// conditionally adds a multiple of its base, and the offset is subtracted at
+// the end.
+func (b bn254Builder) multiScalarMul(terms []scalarTerm) pointVariable {
+	accumulator := b.constantPoint(grumpkinOffset)
+	for _, term := range terms {
+		multiple := term.base
+		for _, bit := range term.bits {
+			accumulator = b.selectPoint(bit, b.addPoints(accumulator, b.constantPoint(multiple)), accumulator)
+			multiple = multiple.double()
+		}
+	}
+
+	// The accumulator is the offset when the sum is the point at infinity, it
+	// is then replaced by another point to keep the last addition valid.
+	isInfinity := b.isEqualConstant(accumulator.x, grumpkinOffset.x)
+	safe := b.selectPoint(isInfinity, b.constantPoint(grumpkinOffset.double()), accumulator)
+	sum := b.addPoints(safe, b.constantPoint(grumpkinOffset.neg()))
+	return pointVariable{x: b.zeroIf(isInfinity, sum.x), y: b.zeroIf(isInfinity, sum.y)}
+}
+
+// add returns p + q for distinct points that are not opposite, which the
+// offset and the generators are not.
+func (p point) add(q point) point {
+	var lambda, numerator, denominator fr_bn254.Element
+	numerator.Sub(&q.y, &p.y)
+	denominator.Sub(&q.x, &p.x)
+	lambda.Div(&numerator, &denominator)
+
+	var sum point
+	sum.x.Square(&lambda).Sub(&sum.x, &p.x).Sub(&sum.x, &q.x)
+	sum.y.Sub(&p.x, &sum.x).Mul(&sum.y, &lambda).Sub(&sum.y, &p.y)
+	return sum
+}
+
+func (b bn254Builder) constantPoint(p point) pointVariable {
+	return pointVariable{x: b.constantElement(p.x), y: b.constantElement(p.y)}
+}
+
+// selectPoint returns new variables constrained to hold p if the boolean bit
+// is 1 and q otherwise.
+func (b bn254Builder) selectPoint(bit int, p, q pointVariable) pointVariable {
+	return pointVariable{x: b.selectVariables(bit, p.x, q.x), y: b.selectVariables(bit, p.y, q.y)}
+}
+
+// addPoints returns new variables constrained to hold p + q, with the
+// incomplete addition formulas: the constraints can not be satisfied when p
+// and q have the same abscissa.
+func (b bn254Builder) addPoints(p, q pointVariable) pointVariable {
+	// λ⋅(q.x - p.x) == q.y - p.y
+	dx := b.linearCombination([]fr_bn254.Element{fr_bn254.One(), b.minusOne()}, []int{q.x, p.x})
+	dy := b.linearCombination([]fr_bn254.Element{fr_bn254.One(), b.minusOne()}, []int{q.y, p.y})
+	var lambdaValue fr_bn254.Element
+	dxValue, dyValue := b.value(dx), b.value(dy)
+	lambdaValue.Div(&dyValue, &dxValue)
+	lambda := b.newVariable(lambdaValue)
+	b.addGate(bn254Gate{xa: lambda, xb: dx, xc: dy, qM: fr_bn254.One(), qO: b.minusOne()})
+
+	return b.lineIntersection(lambda, p, q.x)
+}
+
+// lineIntersection returns new variables constrained to hold the sum of p and
+// the point of abscissa x on the line of slope λ going through p:
+// (λ² - p.x - x, λ⋅(p.x - (λ² - p.x - x)) - p.y).
+func (b bn254Builder) lineIntersection(lambda int, p pointVariable, x int) pointVariable {
+	square := b.mul(lambda, lambda)
+	sumX := b.linearCombination([]fr_bn254.Element{fr_bn254.One(), b.minusOne(), b.minusOne()}, []int{square, p.x, x})
+	difference := b.linearCombination([]fr_bn254.Element{fr_bn254.One(), b.minusOne()}, []int{p.x, sumX})
+	product := b.mul(lambda, difference)
+	sumY := b.linearCombination([]fr_bn254.Element{fr_bn254.One(), b.minusOne()}, []int{product, p.y})
+	return pointVariable{x: sumX, y: sumY}
+}
+
+// toCanonicalBits returns the fr_bn254.Bits little endian bits of x. The
+// decomposition is constrained to be smaller than the field modulus so that
+// it is unique.
+func (b bn254Builder) toCanonicalBits(x int) []int {
+	bits := b.toBits(x, fr_bn254.Bits)
+	b.assertLessOrEqualConstant(bits, new(big.Int).Sub(fr_bn254.Modulus(), big.NewInt(1)))
+	return bits
+}
+
+// assertLessOrEqualConstant constrains the number whose little endian bits are
+// bits to be at most c. Walking from the most significant bit, equal holds
+// whether the bits seen so far are the ones of c, and a bit must be zero where
+// c has a zero while they are.
+func (b bn254Builder) assertLessOrEqualConstant(bits []int, c *big.Int) {
+	equal := b.constant(1)
+	for i := len(bits) - 1; i >= 0; i-- {
+		if c.Bit(i) == 1 {
+			equal = b.and(equal, bits[i])
+		} else {
+			b.addGate(bn254Gate{xa: equal, xb: bits[i], qM: fr_bn254.One()})
+		}
+	}
+}
@@ -0,0 +1,18 @@
+package plonk_backend
+
+import (
+	"testing"
+
+	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
+	"github.com/stretchr/testify/assert"
+)
+
+func TestGrumpkinPointsAreOnTheCurve(t *testing.T) {
+	var b, lhs, rhs fr_bn254.Element
+	b.SetInt64(-17)
+	for _, p := range []point{grumpkinOne, grumpkinOffset, grumpkinOffset.double()} {
+		lhs.Square(&p.y)
+		rhs.Square(&p.x).Mul(&rhs, &p.x).Add(&rhs, &b)
+		assert.True(t, lhs.Equal(&rhs))
+	}
+}
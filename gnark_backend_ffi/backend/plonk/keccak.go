@@ -0,0 +1,132 @@
+package plonk_backend
+
+import (
+	acir_opcode "gnark_backend_ffi/acir/opcode"
+)
+
+// Keccak-256 as used by Ethereum: the Keccak-f[1600] sponge with a 1088 bits
+// rate and the original 0x01 padding byte, rather than the 0x06 of SHA3-256.
+
+const keccakRate = 136
+
+var keccakRoundConstants = [24]uint64{
+	0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+	0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+	0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+	0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+	0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+	0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+}
+
+// The rotation of every lane in the ρ step, indexed like the state.
+var keccakRotations = [5][5]int{
+	{0, 1, 62, 28, 27},
+	{36, 44, 6, 55, 20},
+	{3, 10, 43, 25, 39},
+	{41, 45, 15, 21, 8},
+	{18, 2, 61, 56, 14},
+}
+
+// keccakState holds the 64 bits lanes, indexed by row and then column: lane
+// (x, y) is state[y][x].
+type keccakState = [5][5]word
+
+// Keccak256 constrains its 32 outputs to hold the bytes of the Keccak-256
+// digest of its inputs.
+func Keccak256[T comparable, E element[T]](bbf *acir_opcode.BlackBoxFunction, b *builder[T, E]) error {
+	message, err := b.inputBytes(bbf.Inputs)
+	if err != nil {
+		return err
+	}
+	return b.assertBytes(bbf.Outputs, b.keccak256(message))
+}
+
+// keccak256 returns the bytes of the digest of message.
+func (b *builder[T, E]) keccak256(message []word) []word {
+	// Padding: a 0x01 byte, zeros up to a multiple of the rate and the last
+	// bit set. Both ends share a byte when a single one is missing.
+	padding := make([]uint64, keccakRate-len(message)%keccakRate)
+	padding[0] |= 0x01
+	padding[len(padding)-1] |= 0x80
+	padded := append([]word{}, message...)
+	for _, value := range padding {
+		padded = append(padded, b.constantWord(value, 8))
+	}
+
+	var state keccakState
+	for y := range state {
+		for x := range state[y] {
+			state[y][x] = b.constantWord(0, 64)
+		}
+	}
+
+	for block := 0; block < len(padded); block += keccakRate {
+		for i := 0; i < keccakRate/8; i++ {
+			lane := littleEndianWord(padded[block+8*i : block+8*i+8]...)
+			state[i/5][i%5] = b.xorWords(state[i/5][i%5], lane)
+		}
+		state = b.keccakF(state)
+	}
+
+	// The digest is the first 4 lanes, least significant byte first.
+	digest := make([]word, 0, 32)
+	for _, lane := range state[0][:4] {
+		for i := 0; i < 64; i += 8 {
+			digest = append(digest, lane[i:i+8])
+		}
+	}
+	return digest
+}
+
+// keccakF is the Keccak-f[1600] permutation.
+func (b *builder[T, E]) keccakF(state keccakState) keccakState {
+	for _, roundConstant := range keccakRoundConstants {
+		// θ: every lane is XORed with the parities of the columns on its left
+		// and, rotated by one bit, on its right.
+		var parities, theta [5]word
+		for x := range parities {
+			parities[x] = b.xorWords(state[0][x], state[1][x], state[2][x], state[3][x], state[4][x])
+		}
+		for x := range theta {
+			theta[x] = b.xorWords(parities[(x+4)%5], rotl(parities[(x+1)%5], 1))
+		}
+		for y := range state {
+			for x := range state[y] {
+				state[y][x] = b.xorWords(state[y][x], theta[x])
+			}
+		}
+
+		// ρ and π: lane (x, y) is rotated and moved to (y, 2x + 3y).
+		var permuted keccakState
+		for y := range state {
+			for x := range state[y] {
+				permuted[(2*x+3*y)%5][y] = rotl(state[y][x], keccakRotations[y][x])
+			}
+		}
+
+		// χ: a[x] ^= NOT a[x + 1] AND a[x + 2] on every row.
+		for y := range state {
+			for x := range state[y] {
+				state[y][x] = b.xorWords(permuted[y][x], b.andNotWords(permuted[y][(x+1)%5], permuted[y][(x+2)%5]))
+			}
+		}
+
+		// ι
+		state[0][0] = b.xorConstant(state[0][0], roundConstant)
+	}
+	return state
+}
+
+// andNotWords returns (NOT x) AND y bitwise.
+func (b *builder[T, E]) andNotWords(x, y word) word {
+	result := make(word, len(x))
+	for i := range result {
+		result[i] = b.andNot(x[i], y[i])
+	}
+	return result
+}
+
+// rotl rotates w left by r bits. It does not add any constraint.
+func rotl(w word, r int) word {
+	return rotr(w, len(w)-r)
+}
@@ -0,0 +1,36 @@
+package plonk_backend
+
+import (
+	"testing"
+
+	"gnark_backend_ffi/acir/opcode"
+
+	"github.com/stretchr/testify/assert"
+)
+
+// The digests of the first three messages are the usual Keccak-256 test
+// vectors, the last one was computed with a reference implementation that
+// also gives the SHA3-256 digests when the padding byte is 0x06.
+func TestKeccak256(t *testing.T) {
+	testCases := []struct {
+		message []byte
+		digest  string
+	}{
+		{[]byte(""), "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"},
+		{[]byte("abc"), "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"},
+		{[]byte("The quick brown fox jumps over the lazy dog"), "4d741b6f1eb29cb2a9b9911c82f56fa8d73b04959d3d9d222895df6c0b28aa15"},
+		// 0x00, 0x01, ..., 0xc7: two blocks.
+		{rangeBytes(200), "bfb0aa97863e797943cf7c33bb7e880bb4543f3d2703c0923c6901c2af57b890"},
+	}
+
+	for _, testCase := range testCases {
+		digest := decodeHex(t, testCase.digest)
+
+		circuit, values := testHashACIR(opcode.Keccak256, testCase.message, digest)
+		assert.NoError(t, isSolved(circuit, values), testCase.digest)
+
+		digest[16] ^= 0x80
+		circuit, values = testHashACIR(opcode.Keccak256, testCase.message, digest)
+		assert.Error(t, isSolved(circuit, values), testCase.digest)
+	}
+}
@@ -2,72 +2,154 @@ package plonk_backend
 
 import (
 	"gnark_backend_ffi/acir"
-	"gnark_backend_ffi/backend"
-	"log"
+	common "gnark_backend_ffi/internal"
+	backend_helpers "gnark_backend_ffi/internal/backend"
 
 	"github.com/consensys/gnark-crypto/ecc"
-	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
+	"github.com/consensys/gnark-crypto/kzg"
 	"github.com/consensys/gnark/backend/plonk"
+	"github.com/consensys/gnark/backend/witness"
+	"github.com/consensys/gnark/constraint"
 )
 
-func Preprocess(acir acir.ACIR, values fr_bn254.Vector) (pk plonk.ProvingKey, vk plonk.VerifyingKey) {
-	sparseR1CS, _, _ := BuildSparseR1CS(acir, values)
+// The SRS every function takes is the one the caller picked, see
+// backend.NewInsecureSRS for tests. The keys only hold a pointer to it, so it
+// has to be given again when proving and verifying with them.
 
-	srs, err := backend.TryLoadSRS(sparseR1CS.CurveID())
+func Preprocess(acir acir.ACIR, values []common.Felt, curveID ecc.ID, srs kzg.SRS) (pk plonk.ProvingKey, vk plonk.VerifyingKey, err error) {
+	sparseR1CS, _, err := BuildSparseR1CS(acir, values, curveID)
 	if err != nil {
-		log.Fatal(err)
+		return
 	}
+	return setup(sparseR1CS, srs)
+}
 
-	pk, vk, err = plonk.Setup(sparseR1CS, srs)
+// ProveWithMeta proves without a proving key at hand. PLONK's setup is
+// deterministic given the SRS, so the key pair is derived from the circuit
+// on every call and VerifyWithMeta derives the very same one.
+func ProveWithMeta(circuit acir.ACIR, values []common.Felt, curveID ecc.ID, srs kzg.SRS) (proof plonk.Proof, err error) {
+	sparseR1CS, witness, err := BuildSparseR1CS(circuit, values, curveID)
 	if err != nil {
-		log.Fatal(err)
+		return
 	}
 
-	return
+	provingKey, _, err := setup(sparseR1CS, srs)
+	if err != nil {
+		return
+	}
+
+	return prove(sparseR1CS, provingKey, witness)
 }
 
-func VerifyWithVK(circuit acir.ACIR, verifyingKey plonk.VerifyingKey, proof plonk.Proof, publicVariables fr_bn254.Vector, curveID ecc.ID) bool {
-	sparseR1CS, publicVariables, secretVariables := BuildSparseR1CS(circuit, publicVariables)
-	witness := backend.BuildWitnesses(curveID.ScalarField(), publicVariables, secretVariables, sparseR1CS.GetNbPublicVariables(), sparseR1CS.GetNbSecretVariables())
+// VerifyWithMeta verifies a proof generated by ProveWithMeta. The values only
+// need to hold the public inputs in their witness positions.
+func VerifyWithMeta(circuit acir.ACIR, proof plonk.Proof, values []common.Felt, curveID ecc.ID, srs kzg.SRS) (bool, error) {
+	sparseR1CS, witness, err := BuildSparseR1CS(circuit, values, curveID)
+	if err != nil {
+		return false, err
+	}
 
-	// Setup.
-	srs, err := backend.TryLoadSRS(curveID)
+	_, verifyingKey, err := setup(sparseR1CS, srs)
 	if err != nil {
-		log.Fatal(err)
+		return false, err
 	}
-	if verifyingKey.InitKZG(srs) != nil {
-		log.Fatal(err)
+
+	return verify(verifyingKey, proof, witness)
+}
+
+func VerifyWithVK(circuit acir.ACIR, verifyingKey plonk.VerifyingKey, proof plonk.Proof, publicVariables []common.Felt, curveID ecc.ID, srs kzg.SRS) (bool, error) {
+	_, witness, err := BuildSparseR1CS(circuit, publicVariables, curveID)
+	if err != nil {
+		return false, err
 	}
 
-	// Verify.
-	witnessPublics, err := witness.Public()
+	err = verifyingKey.InitKZG(srs)
+	if err != nil {
+		return false, common.NewError(common.SRSError, err)
+	}
+
+	return verify(verifyingKey, proof, witness)
+}
+
+func ProveWithPK(circuit acir.ACIR, provingKey plonk.ProvingKey, values []common.Felt, curveID ecc.ID, srs kzg.SRS) (proof plonk.Proof, err error) {
+	sparseR1CS, witness, err := BuildSparseR1CS(circuit, values, curveID)
+	if err != nil {
+		return
+	}
+
+	err = provingKey.InitKZG(srs)
 	if err != nil {
-		log.Fatal(err)
+		err = common.NewError(common.SRSError, err)
+		return
 	}
-	if plonk.Verify(proof, verifyingKey, witnessPublics) != nil {
-		return false
+
+	return prove(sparseR1CS, provingKey, witness)
+}
+
+// CircuitSize returns the number of constraints of the sparse R1CS built for
+// the circuit, which does not depend on the witness values.
+func CircuitSize(circuit acir.ACIR, curveID ecc.ID) (int, error) {
+	values := backend_helpers.ZeroFelts(int(circuit.CurrentWitness), curveID)
+	sparseR1CS, _, err := BuildSparseR1CS(circuit, values, curveID)
+	if err != nil {
+		return 0, err
 	}
-	return true
+	return sparseR1CS.GetNbConstraints(), nil
 }
 
-func ProveWithPK(circuit acir.ACIR, provingKey plonk.ProvingKey, values fr_bn254.Vector, curveID ecc.ID) (proof plonk.Proof) {
-	sparseR1CS, publicVariables, secretVariables := BuildSparseR1CS(circuit, values)
-	witness := backend.BuildWitnesses(sparseR1CS.CurveID().ScalarField(), publicVariables, secretVariables, sparseR1CS.GetNbPublicVariables(), sparseR1CS.GetNbSecretVariables())
+// RequiredSRSSize returns the number of G1 points plonk.Setup needs to accept
+// the circuit: the size of its evaluation domain, the next power of two of its
+// number of constraints and public inputs, and 3 more for the openings of the
+// blinded polynomials.
+func RequiredSRSSize(circuit acir.ACIR, curveID ecc.ID) (int, error) {
+	values := backend_helpers.ZeroFelts(int(circuit.CurrentWitness), curveID)
+	sparseR1CS, _, err := BuildSparseR1CS(circuit, values, curveID)
+	if err != nil {
+		return 0, err
+	}
+	sizeSystem := uint64(sparseR1CS.GetNbConstraints() + sparseR1CS.GetNbPublicVariables())
+	return int(ecc.NextPowerOfTwo(sizeSystem)) + 3, nil
+}
 
-	// Setup.
-	srs, err := backend.TryLoadSRS(sparseR1CS.CurveID())
+func setup(sparseR1CS constraint.SparseR1CS, srs kzg.SRS) (pk plonk.ProvingKey, vk plonk.VerifyingKey, err error) {
+	// Setup only fails when the SRS is too small for the circuit.
+	pk, vk, err = plonk.Setup(sparseR1CS, srs)
 	if err != nil {
-		log.Fatal(err)
+		err = common.NewError(common.SRSError, err)
 	}
-	if provingKey.InitKZG(srs) != nil {
-		log.Fatal(err)
+
+	return
+}
+
+// prove tells an unsatisfied witness apart from a proving key that does not
+// belong to the circuit by solving the constraint system first.
+func prove(sparseR1CS constraint.SparseR1CS, provingKey plonk.ProvingKey, witness witness.Witness) (proof plonk.Proof, err error) {
+	err = sparseR1CS.IsSolved(witness)
+	if err != nil {
+		return nil, common.NewError(common.UnsatisfiedConstraint, err)
 	}
 
-	// Prove
+	// gnark indexes the key with the circuit sizes, so a key made for
+	// another circuit may panic instead of failing.
+	defer func() {
+		if r := recover(); r != nil {
+			proof, err = nil, common.Errorf(common.KeyMismatch, "the proving key does not match the circuit: %v", r)
+		}
+	}()
+
 	proof, err = plonk.Prove(sparseR1CS, provingKey, witness)
 	if err != nil {
-		log.Fatal(err)
+		return nil, common.NewError(common.KeyMismatch, err)
 	}
 
-	return
+	return proof, nil
+}
+
+func verify(verifyingKey plonk.VerifyingKey, proof plonk.Proof, witness witness.Witness) (bool, error) {
+	witnessPublics, err := witness.Public()
+	if err != nil {
+		return false, common.NewError(common.InternalError, err)
+	}
+
+	return plonk.Verify(proof, verifyingKey, witnessPublics) == nil, nil
 }
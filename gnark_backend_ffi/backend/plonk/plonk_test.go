@@ -0,0 +1,318 @@
+package plonk_backend
+
+import (
+	"math/big"
+	"testing"
+
+	"gnark_backend_ffi/acir"
+	"gnark_backend_ffi/acir/opcode"
+	"gnark_backend_ffi/acir/term"
+	"gnark_backend_ffi/backend"
+	common "gnark_backend_ffi/internal"
+	backend_helpers "gnark_backend_ffi/internal/backend"
+
+	"github.com/consensys/gnark-crypto/ecc"
+	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
+	"github.com/consensys/gnark-crypto/kzg"
+	"github.com/stretchr/testify/assert"
+)
+
+// x⋅y - z == 0 where z is public.
+func testACIR() acir.ACIR {
+	return acir.ACIR{
+		CurrentWitness: 3,
+		Opcodes: []opcode.Opcode{
+			{
+				Data: &opcode.ArithmeticOpcode{
+					MulTerms:    term.MulTerms{{Coefficient: coefficient(1), MultiplicandIndex: 1, MultiplierIndex: 2}},
+					SimpleTerms: term.SimpleTerms{{Coefficient: coefficient(-1), VariableIndex: 3}},
+				},
+			},
+		},
+		PublicInputs: common.Witnesses{3},
+	}
+}
+
+// testSRS generates an SRS just large enough for the circuit.
+func testSRS(t *testing.T, circuit acir.ACIR, curveID ecc.ID) kzg.SRS {
+	size, err := RequiredSRSSize(circuit, curveID)
+	assert.NoError(t, err)
+	srs, err := backend.NewInsecureSRS(curveID, size)
+	assert.NoError(t, err)
+	return srs
+}
+
+// coefficient serializes value as an element of Noir's field, as ACIR does.
+func coefficient(value int64) common.Felt {
+	integer := big.NewInt(value)
+	return integer.Mod(integer, common.NoirCurve.ScalarField()).Bytes()
+}
+
+// felts serializes elements of the scalar field of BN254 as the Rust side
+// does.
+func felts(values fr_bn254.Vector) []common.Felt {
+	felts := make([]common.Felt, len(values))
+	for i := range values {
+		bytes := values[i].Bytes()
+		felts[i] = bytes[:]
+	}
+	return felts
+}
+
+// feltsOf serializes values as elements of the scalar field of curveID, as
+// the Rust side does.
+func feltsOf(curveID ecc.ID, values ...uint64) []common.Felt {
+	felts := make([]common.Felt, len(values))
+	for i, value := range values {
+		felts[i] = new(big.Int).SetUint64(value).FillBytes(make([]byte, backend_helpers.FeltSize(curveID)))
+	}
+	return felts
+}
+
+func TestPlonkProveAndVerifyWithMeta(t *testing.T) {
+	circuit := testACIR()
+	srs := testSRS(t, circuit, ecc.BN254)
+	values := feltsOf(ecc.BN254, 2, 3, 6)
+
+	proof, err := ProveWithMeta(circuit, values, ecc.BN254, srs)
+	assert.NoError(t, err)
+
+	// The verifier only knows the public inputs.
+	publicValues := feltsOf(ecc.BN254, 0, 0, 6)
+	verifies, err := VerifyWithMeta(circuit, proof, publicValues, ecc.BN254, srs)
+	assert.NoError(t, err)
+	assert.True(t, verifies)
+
+	invalidPublicValues := feltsOf(ecc.BN254, 0, 0, 7)
+	verifies, err = VerifyWithMeta(circuit, proof, invalidPublicValues, ecc.BN254, srs)
+	assert.NoError(t, err)
+	assert.False(t, verifies)
+}
+
+func TestPlonkProveWithMetaFailsWithUnsatisfiedConstraint(t *testing.T) {
+	circuit := testACIR()
+	srs := testSRS(t, circuit, ecc.BN254)
+	values := feltsOf(ecc.BN254, 2, 3, 7)
+
+	_, err := ProveWithMeta(circuit, values, ecc.BN254, srs)
+
+	assert.Equal(t, common.UnsatisfiedConstraint, common.Code(err))
+}
+
+func TestPreprocessFailsWithTooSmallSRS(t *testing.T) {
+	circuit := testACIR()
+	values := backend_helpers.ZeroFelts(int(circuit.CurrentWitness), ecc.BN254)
+	size, err := RequiredSRSSize(circuit, ecc.BN254)
+	assert.NoError(t, err)
+
+	srs, err := backend.NewInsecureSRS(ecc.BN254, size-1)
+	assert.NoError(t, err)
+	_, _, err = Preprocess(circuit, values, ecc.BN254, srs)
+	assert.Equal(t, common.SRSError, common.Code(err))
+
+	srs, err = backend.NewInsecureSRS(ecc.BN254, size)
+	assert.NoError(t, err)
+	_, _, err = Preprocess(circuit, values, ecc.BN254, srs)
+	assert.NoError(t, err)
+}
+
+func TestCircuitSize(t *testing.T) {
+	size, err := CircuitSize(testACIR(), ecc.BN254)
+
+	assert.NoError(t, err)
+	assert.Equal(t, 1, size)
+}
+
+func TestBuildSparseR1CSFailsWithUnknownWitness(t *testing.T) {
+	circuit := testACIR()
+	values := feltsOf(ecc.BN254, 2, 3)
+
+	_, _, err := BuildSparseR1CS(circuit, values, ecc.BN254)
+
+	assert.Equal(t, common.InvalidCircuit, common.Code(err))
+}
+
+func TestPlonkProveWithMetaKeepsEveryTermOfTheGate(t *testing.T) {
+	// x⋅y + x - z == 0 where z is public.
+	circuit := testACIR()
+	srs := testSRS(t, circuit, ecc.BN254)
+	circuit.Opcodes[0].Data.(*opcode.ArithmeticOpcode).SimpleTerms = term.SimpleTerms{
+		{Coefficient: coefficient(1), VariableIndex: 1},
+		{Coefficient: coefficient(-1), VariableIndex: 3},
+	}
+
+	values := feltsOf(ecc.BN254, 2, 3, 8)
+	_, err := ProveWithMeta(circuit, values, ecc.BN254, srs)
+	assert.NoError(t, err)
+
+	// x⋅y - z == 0 holds but the x term is not dropped.
+	values = feltsOf(ecc.BN254, 2, 3, 6)
+	_, err = ProveWithMeta(circuit, values, ecc.BN254, srs)
+	assert.Equal(t, common.UnsatisfiedConstraint, common.Code(err))
+}
+
+func TestBuildSparseR1CSFailsWithWideOpcode(t *testing.T) {
+	// x⋅y + x⋅z - z == 0 has two multiplication terms.
+	circuit := testACIR()
+	arithmeticOpcode := circuit.Opcodes[0].Data.(*opcode.ArithmeticOpcode)
+	arithmeticOpcode.MulTerms = append(arithmeticOpcode.MulTerms, term.MulTerm{Coefficient: coefficient(1), MultiplicandIndex: 1, MultiplierIndex: 3})
+	values := feltsOf(ecc.BN254, 2, 3, 6)
+
+	_, _, err := BuildSparseR1CS(circuit, values, ecc.BN254)
+
+	assert.Equal(t, common.InvalidCircuit, common.Code(err))
+}
+
+// x⋅y - z == 0 where z is public and x fits in 8 bits.
+func testRangeACIR() acir.ACIR {
+	circuit := testACIR()
+	circuit.Opcodes = append(circuit.Opcodes, opcode.Opcode{
+		Data: &opcode.BlackBoxFunction{
+			Name:   opcode.RANGE,
+			Inputs: opcode.FunctionInputs{{Witness: 1, NumBits: 8}},
+		},
+	})
+	return circuit
+}
+
+func TestPlonkProveWithMetaConstrainsRange(t *testing.T) {
+	circuit := testRangeACIR()
+	srs := testSRS(t, circuit, ecc.BN254)
+
+	values := feltsOf(ecc.BN254, 255, 2, 510)
+	proof, err := ProveWithMeta(circuit, values, ecc.BN254, srs)
+	assert.NoError(t, err)
+
+	publicValues := feltsOf(ecc.BN254, 0, 0, 510)
+	verifies, err := VerifyWithMeta(circuit, proof, publicValues, ecc.BN254, srs)
+	assert.NoError(t, err)
+	assert.True(t, verifies)
+}
+
+func TestPlonkProveWithMetaFailsWithOutOfRangeValue(t *testing.T) {
+	circuit := testRangeACIR()
+	srs := testSRS(t, circuit, ecc.BN254)
+	values := feltsOf(ecc.BN254, 256, 2, 512)
+
+	_, err := ProveWithMeta(circuit, values, ecc.BN254, srs)
+
+	assert.Equal(t, common.UnsatisfiedConstraint, common.Code(err))
+}
+
+func TestCircuitSizeCountsRangeConstraints(t *testing.T) {
+	size, err := CircuitSize(testRangeACIR(), ecc.BN254)
+
+	assert.NoError(t, err)
+	// The gate, 8 boolean constraints, 7 to recompose the bits and 1 to
+	// compare the result with the input.
+	assert.Equal(t, 17, size)
+}
+
+// z == x op y where x and y fit in 8 bits and z is public.
+func testBitwiseACIR(name int) acir.ACIR {
+	return acir.ACIR{
+		CurrentWitness: 3,
+		Opcodes: []opcode.Opcode{
+			{
+				Data: &opcode.BlackBoxFunction{
+					Name:    name,
+					Inputs:  opcode.FunctionInputs{{Witness: 1, NumBits: 8}, {Witness: 2, NumBits: 8}},
+					Outputs: common.Witnesses{3},
+				},
+			},
+		},
+		PublicInputs: common.Witnesses{3},
+	}
+}
+
+func TestPlonkProveWithMetaConstrainsBitwiseOperations(t *testing.T) {
+	testCases := []struct {
+		name   int
+		output uint64
+	}{
+		{opcode.AND, 0b1000_0010},
+		{opcode.XOR, 0b0110_0101},
+	}
+
+	for _, testCase := range testCases {
+		circuit := testBitwiseACIR(testCase.name)
+		srs := testSRS(t, circuit, ecc.BN254)
+
+		values := feltsOf(ecc.BN254, 0b1100_0011, 0b1010_0110, testCase.output)
+		proof, err := ProveWithMeta(circuit, values, ecc.BN254, srs)
+		assert.NoError(t, err)
+
+		publicValues := feltsOf(ecc.BN254, 0, 0, testCase.output)
+		verifies, err := VerifyWithMeta(circuit, proof, publicValues, ecc.BN254, srs)
+		assert.NoError(t, err)
+		assert.True(t, verifies)
+
+		invalidValues := feltsOf(ecc.BN254, 0b1100_0011, 0b1010_0110, testCase.output + 1)
+		_, err = ProveWithMeta(circuit, invalidValues, ecc.BN254, srs)
+		assert.Equal(t, common.UnsatisfiedConstraint, common.Code(err))
+	}
+}
+
+func TestCircuitSizeCountsBitwiseConstraints(t *testing.T) {
+	size, err := CircuitSize(testBitwiseACIR(opcode.AND), ecc.BN254)
+
+	assert.NoError(t, err)
+	// 16 constraints to decompose each input, 8 for the bits of the output
+	// and 8 to recompose and compare it.
+	assert.Equal(t, 48, size)
+}
+
+func TestSupportOfEveryBlackBoxFunction(t *testing.T) {
+	unsupported := map[string]bool{"Pedersen": true, "SchnorrVerify": true, "MerkleMembership": true}
+	for _, name := range opcode.BlackBoxFunctionNames() {
+		if unsupported[name] {
+			assert.Equal(t, backend.Unsupported, SupportOf(ecc.BN254, name), name)
+		} else {
+			assert.Equal(t, backend.Constrained, SupportOf(ecc.BN254, name), name)
+		}
+	}
+	assert.Equal(t, backend.Unsupported, SupportOf(ecc.BN254, "Poseidon"))
+}
+
+func TestSupportOfOverOtherCurves(t *testing.T) {
+	for _, curveID := range []ecc.ID{ecc.BLS12_381, ecc.BLS12_377, ecc.BW6_761} {
+		assert.Equal(t, backend.Constrained, SupportOf(curveID, "SHA256"), curveID)
+		assert.Equal(t, backend.Constrained, SupportOf(curveID, "RANGE"), curveID)
+		// The Grumpkin and secp256k1 gadgets only exist over BN254.
+		for _, name := range []string{"Pedersen", "SchnorrVerify", "MerkleMembership", "FixedBaseScalarMul", "EcdsaSecp256k1"} {
+			assert.Equal(t, backend.Unsupported, SupportOf(curveID, name), name)
+		}
+	}
+}
+
+func TestPlonkProveAndVerifyOverOtherCurves(t *testing.T) {
+	for _, curveID := range []ecc.ID{ecc.BLS12_381, ecc.BLS12_377, ecc.BW6_761} {
+		circuit := testRangeACIR()
+		srs := testSRS(t, circuit, curveID)
+
+		proof, err := ProveWithMeta(circuit, feltsOf(curveID, 255, 2, 510), curveID, srs)
+		assert.NoError(t, err, curveID)
+
+		verifies, err := VerifyWithMeta(circuit, proof, feltsOf(curveID, 0, 0, 510), curveID, srs)
+		assert.NoError(t, err, curveID)
+		assert.True(t, verifies, curveID)
+
+		// The -1 of x⋅y - z == 0 is lifted to the -1 of the scalar field of
+		// curveID, so a wrong product does not verify.
+		verifies, err = VerifyWithMeta(circuit, proof, feltsOf(curveID, 0, 0, 511), curveID, srs)
+		assert.NoError(t, err, curveID)
+		assert.False(t, verifies, curveID)
+
+		size, err := CircuitSize(circuit, curveID)
+		assert.NoError(t, err)
+		bn254Size, err := CircuitSize(circuit, ecc.BN254)
+		assert.NoError(t, err)
+		assert.Equal(t, bn254Size, size, curveID)
+	}
+}
+
+func TestBuildSparseR1CSFailsWithValuesOfAnotherField(t *testing.T) {
+	_, _, err := BuildSparseR1CS(testACIR(), feltsOf(ecc.BN254, 2, 3, 6), ecc.BW6_761)
+
+	assert.Equal(t, common.InvalidValues, common.Code(err))
+}
@@ -0,0 +1,118 @@
+package plonk_backend
+
+import (
+	acir_opcode "gnark_backend_ffi/acir/opcode"
+)
+
+// SHA-256 as specified in FIPS 180-4.
+
+var sha256IV = [8]uint64{
+	0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+}
+
+var sha256K = [64]uint64{
+	0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+	0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+	0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+	0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+	0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+	0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+	0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+	0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+}
+
+// SHA256 constrains its 32 outputs to hold the bytes of the SHA-256 digest
+// of its inputs.
+func SHA256[T comparable, E element[T]](bbf *acir_opcode.BlackBoxFunction, b *builder[T, E]) error {
+	message, err := b.inputBytes(bbf.Inputs)
+	if err != nil {
+		return err
+	}
+	return b.assertBytes(bbf.Outputs, b.sha256(message))
+}
+
+// sha256 returns the bytes of the digest of message.
+func (b *builder[T, E]) sha256(message []word) []word {
+	// Padding: a one bit, zeros up to 56 bytes modulo 64 and the length of
+	// the message in bits as a big endian 64 bits integer.
+	length := uint64(len(message)) * 8
+	padded := append([]word{}, message...)
+	padded = append(padded, b.constantWord(0x80, 8))
+	for len(padded)%64 != 56 {
+		padded = append(padded, b.constantWord(0, 8))
+	}
+	for i := 7; i >= 0; i-- {
+		padded = append(padded, b.constantWord((length>>(8*i))&0xff, 8))
+	}
+
+	var state [8]word
+	for i, iv := range sha256IV {
+		state[i] = b.constantWord(iv, 32)
+	}
+
+	for block := 0; block < len(padded); block += 64 {
+		state = b.sha256Compress(state, padded[block:block+64])
+	}
+
+	digest := make([]word, 0, 32)
+	for _, w := range state {
+		digest = append(digest, w[24:32], w[16:24], w[8:16], w[0:8])
+	}
+	return digest
+}
+
+func (b *builder[T, E]) sha256Compress(state [8]word, block []word) [8]word {
+	// Message schedule.
+	var w [64]word
+	for t := 0; t < 16; t++ {
+		w[t] = bigEndianWord(block[4*t : 4*t+4]...)
+	}
+	for t := 16; t < 64; t++ {
+		s0 := b.xorWords(rotr(w[t-15], 7), rotr(w[t-15], 18), b.shr(w[t-15], 3))
+		s1 := b.xorWords(rotr(w[t-2], 17), rotr(w[t-2], 19), b.shr(w[t-2], 10))
+		w[t] = b.addWords(0, w[t-16], s0, w[t-7], s1)
+	}
+
+	v := state
+	for t := 0; t < 64; t++ {
+		s1 := b.xorWords(rotr(v[4], 6), rotr(v[4], 11), rotr(v[4], 25))
+		ch := b.sha256Ch(v[4], v[5], v[6])
+		s0 := b.xorWords(rotr(v[0], 2), rotr(v[0], 13), rotr(v[0], 22))
+		maj := b.sha256Maj(v[0], v[1], v[2])
+
+		// e = d + T1 and a = T1 + T2, where T1 = h + Σ1(e) + Ch(e, f, g) + K[t] + W[t]
+		// and T2 = Σ0(a) + Maj(a, b, c).
+		e := b.addWords(sha256K[t], v[3], v[7], s1, ch, w[t])
+		a := b.addWords(sha256K[t], v[7], s1, ch, w[t], s0, maj)
+		v = [8]word{a, v[0], v[1], v[2], e, v[4], v[5], v[6]}
+	}
+
+	for i := range state {
+		state[i] = b.addWords(0, state[i], v[i])
+	}
+	return state
+}
+
+// sha256Ch returns (e AND f) XOR (NOT e AND g) bitwise, computed as
+// g + e⋅(f - g).
+func (b *builder[T, E]) sha256Ch(e, f, g word) word {
+	result := make(word, len(e))
+	for i := range result {
+		difference := b.linearCombination([]T{b.one(), b.minusOne()}, []int{f[i], g[i]})
+		product := b.mul(e[i], difference)
+		result[i] = b.linearCombination([]T{b.one(), b.one()}, []int{product, g[i]})
+	}
+	return result
+}
+
+// sha256Maj returns (x AND y) XOR (x AND z) XOR (y AND z) bitwise, computed
+// as x⋅y + z⋅(x XOR y) since both terms can not be 1 at the same time.
+func (b *builder[T, E]) sha256Maj(x, y, z word) word {
+	result := make(word, len(x))
+	for i := range result {
+		product := b.and(x[i], y[i])
+		carry := b.and(z[i], b.xor(x[i], y[i]))
+		result[i] = b.linearCombination([]T{b.one(), b.one()}, []int{product, carry})
+	}
+	return result
+}
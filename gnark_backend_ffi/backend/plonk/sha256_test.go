@@ -0,0 +1,93 @@
+package plonk_backend
+
+import (
+	"encoding/hex"
+	"testing"
+
+	"gnark_backend_ffi/acir"
+	"gnark_backend_ffi/acir/opcode"
+	common "gnark_backend_ffi/internal"
+
+	"github.com/consensys/gnark-crypto/ecc"
+	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
+	"github.com/stretchr/testify/assert"
+)
+
+// testHashACIR calls the hash function on the bytes of the message, held by
+// the first witnesses, and returns the circuit together with the values of
+// its witnesses, which hold the digest after the message.
+func testHashACIR(name int, message []byte, digest []byte) (acir.ACIR, fr_bn254.Vector) {
+	var inputs opcode.FunctionInputs
+	var outputs common.Witnesses
+	var values fr_bn254.Vector
+	for i, b := range message {
+		inputs = append(inputs, opcode.FunctionInput{Witness: common.Witness(i + 1), NumBits: 8})
+		values = append(values, fr_bn254.NewElement(uint64(b)))
+	}
+	for i, b := range digest {
+		outputs = append(outputs, common.Witness(len(message)+i+1))
+		values = append(values, fr_bn254.NewElement(uint64(b)))
+	}
+
+	circuit := acir.ACIR{
+		CurrentWitness: common.Witness(len(values)),
+		Opcodes: []opcode.Opcode{
+			{Data: &opcode.BlackBoxFunction{Name: name, Inputs: inputs, Outputs: outputs}},
+		},
+		PublicInputs: outputs,
+	}
+
+	return circuit, values
+}
+
+// isSolved checks the values against the constraint system without proving,
+// which takes long for the hash gadgets.
+func isSolved(circuit acir.ACIR, values fr_bn254.Vector) error {
+	sparseR1CS, witness, err := BuildSparseR1CS(circuit, felts(values), ecc.BN254)
+	if err != nil {
+		return err
+	}
+	return sparseR1CS.IsSolved(witness)
+}
+
+func decodeHex(t *testing.T, s string) []byte {
+	decoded, err := hex.DecodeString(s)
+	assert.NoError(t, err)
+	return decoded
+}
+
+// Test vectors from FIPS 180-2, appendix B.
+func TestSHA256(t *testing.T) {
+	testCases := []struct {
+		message string
+		digest  string
+	}{
+		{"", "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"},
+		{"abc", "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"},
+		{"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq", "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"},
+	}
+
+	for _, testCase := range testCases {
+		digest := decodeHex(t, testCase.digest)
+
+		circuit, values := testHashACIR(opcode.SHA256, []byte(testCase.message), digest)
+		assert.NoError(t, isSolved(circuit, values), testCase.message)
+
+		digest[0] ^= 1
+		circuit, values = testHashACIR(opcode.SHA256, []byte(testCase.message), digest)
+		assert.Error(t, isSolved(circuit, values), testCase.message)
+	}
+}
+
+func TestPlonkProveAndVerifySHA256(t *testing.T) {
+	digest := decodeHex(t, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+	circuit, values := testHashACIR(opcode.SHA256, []byte("abc"), digest)
+	srs := testSRS(t, circuit, ecc.BN254)
+
+	proof, err := ProveWithMeta(circuit, felts(values), ecc.BN254, srs)
+	assert.NoError(t, err)
+
+	verifies, err := VerifyWithMeta(circuit, proof, felts(values), ecc.BN254, srs)
+	assert.NoError(t, err)
+	assert.True(t, verifies)
+}
@@ -1,151 +1,249 @@
 package plonk_backend
 
 import (
-	"fmt"
 	"gnark_backend_ffi/acir"
 	"gnark_backend_ffi/backend"
-	"log"
+	common "gnark_backend_ffi/internal"
+	backend_helpers "gnark_backend_ffi/internal/backend"
 
 	acir_opcode "gnark_backend_ffi/acir/opcode"
 
+	"github.com/consensys/gnark-crypto/ecc"
+	fr_bls12377 "github.com/consensys/gnark-crypto/ecc/bls12-377/fr"
+	fr_bls12381 "github.com/consensys/gnark-crypto/ecc/bls12-381/fr"
 	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
+	fr_bw6761 "github.com/consensys/gnark-crypto/ecc/bw6-761/fr"
+	"github.com/consensys/gnark/backend/witness"
 	"github.com/consensys/gnark/constraint"
+	cs_bls12377 "github.com/consensys/gnark/constraint/bls12-377"
+	cs_bls12381 "github.com/consensys/gnark/constraint/bls12-381"
 	cs_bn254 "github.com/consensys/gnark/constraint/bn254"
+	cs_bw6761 "github.com/consensys/gnark/constraint/bw6-761"
 )
 
 // TODO: Make this a method for acir.ACIR.
 // qL⋅xa + qR⋅xb + qO⋅xc + qM⋅(xa⋅xb) + qC == 0
-func BuildSparseR1CS(circuit acir.ACIR, values fr_bn254.Vector) (*cs_bn254.SparseR1CS, fr_bn254.Vector, fr_bn254.Vector) {
-	sparseR1CS := cs_bn254.NewSparseR1CS(int(circuit.CurrentWitness) - 1)
+//
+// The sparse R1CS is built over the scalar field of curveID and returned with
+// its witness. The values are elements of that field, and the coefficients of
+// the circuit are lifted to it from Noir's field.
+func BuildSparseR1CS(circuit acir.ACIR, values []common.Felt, curveID ecc.ID) (constraint.SparseR1CS, witness.Witness, error) {
+	err := backend_helpers.CheckFelts(values, curveID)
+	if err != nil {
+		return nil, nil, err
+	}
+	nbWires := int(circuit.CurrentWitness) - 1
+	switch curveID {
+	case ecc.BN254:
+		return buildSparseR1CS(circuit, values, curveID, cs_bn254.NewSparseR1CS(nbWires), bn254Gadgets)
+	case ecc.BLS12_381:
+		return buildSparseR1CS(circuit, values, curveID, cs_bls12381.NewSparseR1CS(nbWires), bls12381Gadgets)
+	case ecc.BLS12_377:
+		return buildSparseR1CS(circuit, values, curveID, cs_bls12377.NewSparseR1CS(nbWires), bls12377Gadgets)
+	case ecc.BW6_761:
+		return buildSparseR1CS(circuit, values, curveID, cs_bw6761.NewSparseR1CS(nbWires), bw6761Gadgets)
+	default:
+		return nil, nil, common.Errorf(common.InvalidCircuit, "circuits over %s are not supported", curveID)
+	}
+}
+
+func buildSparseR1CS[T comparable, E element[T]](circuit acir.ACIR, values []common.Felt, curveID ecc.ID, sparseR1CS constraint.SparseR1CS, gadgets map[int]gadget[T, E]) (constraint.SparseR1CS, witness.Witness, error) {
+	elements := make([]T, len(values))
+	for i, value := range values {
+		E(&elements[i]).SetBytes(value)
+	}
 
-	publicVariables, secretVariables, indexMap := backend.HandleValues(circuit, sparseR1CS, values)
-	handleOpcodes(circuit, sparseR1CS, indexMap)
+	publicVariables, secretVariables, indexMap := backend.HandleValues(circuit.PublicInputs, sparseR1CS, elements)
+	b := newBuilder[T, E](sparseR1CS, indexMap, publicVariables, secretVariables)
+	err := handleOpcodes(circuit, b, gadgets)
+	if err != nil {
+		return nil, nil, err
+	}
+
+	// The gadgets add secret variables of their own.
+	witness, err := backend.BuildWitnesses(curveID.ScalarField(), publicVariables, b.secretVariables(), sparseR1CS.GetNbPublicVariables(), sparseR1CS.GetNbSecretVariables())
+	if err != nil {
+		return nil, nil, err
+	}
 
-	return sparseR1CS, publicVariables, secretVariables
+	return sparseR1CS, witness, nil
 }
 
-func handleOpcodes(a acir.ACIR, sparseR1CS constraint.SparseR1CS, indexMap map[string]int) {
+func handleOpcodes[T comparable, E element[T]](a acir.ACIR, b *builder[T, E], gadgets map[int]gadget[T, E]) error {
 	for _, opcode := range a.Opcodes {
+		var err error
 		switch opcode := opcode.Data.(type) {
 		case *acir_opcode.ArithmeticOpcode:
-			handleArithmeticOpcode(opcode, sparseR1CS, indexMap)
-			break
+			err = handleArithmeticOpcode(opcode, b)
 		case *acir_opcode.BlackBoxFunction:
-			handleBlackBoxFunctionOpcode(opcode)
-			break
+			err = handleBlackBoxFunctionOpcode(opcode, b, gadgets)
 		case *acir_opcode.DirectiveOpcode:
-			break
 		default:
-			log.Fatal("unknown opcode type")
+			err = common.Errorf(common.InvalidCircuit, "unknown opcode type %T", opcode)
+		}
+		if err != nil {
+			return err
 		}
 	}
+	return nil
 }
 
-func handleArithmeticOpcode(a *acir_opcode.ArithmeticOpcode, sparseR1CS constraint.SparseR1CS, indexMap map[string]int) {
-	var xa, xb, xc int
-	var qL, qR, qO, qC, qM1, qM2 constraint.Coeff
+// handleArithmeticOpcode adds the opcode as a single sparse R1CS gate. The
+// Rust side splits wider opcodes beforehand, so the opcode has at most one
+// multiplication term and its simple terms involve at most three variables,
+// two of which are the operands of the multiplication when there is one.
+func handleArithmeticOpcode[T comparable, E element[T]](a *acir_opcode.ArithmeticOpcode, b *builder[T, E]) error {
+	if len(a.MulTerms) > 1 {
+		return common.Errorf(common.InvalidCircuit, "arithmetic opcode has %d multiplication terms, at most 1 is supported", len(a.MulTerms))
+	}
+
+	// The wires of the gate and their coefficients. xa and xb are the operands
+	// of the multiplication.
+	var wires [3]int
+	var coefficients [3]T
+	var used [3]bool
+	qC, err := b.element(a.QC)
+	if err != nil {
+		return err
+	}
+	g := gate[T]{qC: qC}
 
 	// Case qM⋅(xa⋅xb)
-	if len(a.MulTerms) != 0 {
+	if len(a.MulTerms) == 1 {
 		mulTerm := a.MulTerms[0]
-		qM1 = sparseR1CS.FromInterface(mulTerm.Coefficient)
-		qM2 = sparseR1CS.FromInterface(1)
-		xa = indexMap[fmt.Sprint(int(mulTerm.MultiplicandIndex))]
-		xb = indexMap[fmt.Sprint(int(mulTerm.MultiplierIndex))]
-	}
-
-	// Case qO⋅xc
-	if len(a.SimpleTerms) == 1 {
-		qOwOTerm := a.SimpleTerms[0]
-		qO = sparseR1CS.FromInterface(qOwOTerm.Coefficient)
-		xc = indexMap[fmt.Sprint(int(qOwOTerm.VariableIndex))]
-	}
-
-	// Case qL⋅xa + qR⋅xb
-	if len(a.SimpleTerms) == 2 {
-		// qL⋅xa
-		qLwLTerm := a.SimpleTerms[0]
-		qL = sparseR1CS.FromInterface(qLwLTerm.Coefficient)
-		xa = indexMap[fmt.Sprint(int(qLwLTerm.VariableIndex))]
-		// qR⋅xb
-		qRwRTerm := a.SimpleTerms[1]
-		qR = sparseR1CS.FromInterface(qRwRTerm.Coefficient)
-		xb = indexMap[fmt.Sprint(int(qRwRTerm.VariableIndex))]
-	}
-
-	// Case qL⋅xa + qR⋅xb + qO⋅xc
-	if len(a.SimpleTerms) == 3 {
-		// qL⋅xa
-		qLwLTerm := a.SimpleTerms[0]
-		qL = sparseR1CS.FromInterface(qLwLTerm.Coefficient)
-		xa = indexMap[fmt.Sprint(int(qLwLTerm.VariableIndex))]
-		// qR⋅xb
-		qRwRTerm := a.SimpleTerms[1]
-		qR = sparseR1CS.FromInterface(qRwRTerm.Coefficient)
-		xb = indexMap[fmt.Sprint(int(qRwRTerm.VariableIndex))]
-		// qO⋅xc
-		qOwOTerm := a.SimpleTerms[2]
-		qO = sparseR1CS.FromInterface(qOwOTerm.Coefficient)
-		xc = indexMap[fmt.Sprint(int(qOwOTerm.VariableIndex))]
-	}
-
-	// Add the qC term
-	qC = sparseR1CS.FromInterface(a.QC)
-
-	K := sparseR1CS.MakeTerm(&qC, 0)
-	K.MarkConstant()
-
-	constraint := constraint.SparseR1C{
-		L: sparseR1CS.MakeTerm(&qL, xa),
-		R: sparseR1CS.MakeTerm(&qR, xb),
-		O: sparseR1CS.MakeTerm(&qO, xc),
-		M: [2]constraint.Term{sparseR1CS.MakeTerm(&qM1, xa), sparseR1CS.MakeTerm(&qM2, xb)},
-		K: K.CoeffID(),
-	}
-
-	sparseR1CS.AddConstraint(constraint)
+		xa, err := b.variable(mulTerm.MultiplicandIndex)
+		if err != nil {
+			return err
+		}
+		xb, err := b.variable(mulTerm.MultiplierIndex)
+		if err != nil {
+			return err
+		}
+		g.qM, err = b.element(mulTerm.Coefficient)
+		if err != nil {
+			return err
+		}
+		wires[0], wires[1] = xa, xb
+		used[0], used[1] = true, true
+	}
+
+	// Cases qL⋅xa, qR⋅xb and qO⋅xc
+	for _, simpleTerm := range a.SimpleTerms {
+		variable, err := b.variable(simpleTerm.VariableIndex)
+		if err != nil {
+			return err
+		}
+		wire := -1
+		for i := range wires {
+			if used[i] && wires[i] == variable {
+				wire = i
+				break
+			}
+		}
+		if wire == -1 {
+			for i := range wires {
+				if !used[i] {
+					wire = i
+					break
+				}
+			}
+		}
+		if wire == -1 {
+			return common.Errorf(common.InvalidCircuit, "arithmetic opcode does not fit in a width 3 gate")
+		}
+		wires[wire], used[wire] = variable, true
+		coefficient, err := b.element(simpleTerm.Coefficient)
+		if err != nil {
+			return err
+		}
+		E(&coefficients[wire]).Add(&coefficients[wire], &coefficient)
+	}
+
+	g.xa, g.xb, g.xc = wires[0], wires[1], wires[2]
+	g.qL, g.qR, g.qO = coefficients[0], coefficients[1], coefficients[2]
+	b.addGate(g)
+
+	return nil
 }
 
-func handleBlackBoxFunctionOpcode(bbf *acir_opcode.BlackBoxFunction) {
-	switch bbf.Name {
-	case acir_opcode.AES:
-		AES()
-		break
-	case acir_opcode.AND:
-		AND()
-		break
-	case acir_opcode.XOR:
-		XOR()
-		break
-	case acir_opcode.RANGE:
-		Range()
-		break
-	case acir_opcode.SHA256:
-		SHA256()
-		break
-	case acir_opcode.Blake2s:
-		Blake2s()
-		break
-	case acir_opcode.MerkleMembership:
-		MerkleMembership()
-		break
-	case acir_opcode.SchnorrVerify:
-		SchnorrVerify()
-		break
-	case acir_opcode.Pedersen:
-		Pedersen()
-		break
-	case acir_opcode.HashToField128Security:
-		HashToField128Security()
-		break
-	case acir_opcode.EcdsaSecp256k1:
-		EcdsaSecp256k1()
-		break
-	case acir_opcode.FixedBaseScalarMul:
-		FixedBaseScalarMul()
-		break
-	case acir_opcode.Keccak256:
-		Keccak256()
-		break
+// A gadget constrains the outputs of a black box function call.
+type gadget[T comparable, E element[T]] func(*acir_opcode.BlackBoxFunction, *builder[T, E]) error
+
+// fieldGadgets returns the gadgets working over any scalar field.
+func fieldGadgets[T comparable, E element[T]]() map[int]gadget[T, E] {
+	return map[int]gadget[T, E]{
+		acir_opcode.AES:                    AES[T, E],
+		acir_opcode.AND:                    AND[T, E],
+		acir_opcode.XOR:                    XOR[T, E],
+		acir_opcode.RANGE:                  Range[T, E],
+		acir_opcode.SHA256:                 SHA256[T, E],
+		acir_opcode.Blake2s:                Blake2s[T, E],
+		acir_opcode.HashToField128Security: HashToField128Security[T, E],
+		acir_opcode.Keccak256:              Keccak256[T, E],
+	}
+}
+
+// bn254Gadgets, bls12381Gadgets, bls12377Gadgets and bw6761Gadgets map every
+// black box function to the gadget constraining it over the scalar field of
+// their curve. SupportOf is answered
+// from them too, so the Rust side is told exactly what the built circuits
+// constrain. The gadgets working with Grumpkin points only exist over BN254,
+// whose scalar field is the base field of Grumpkin, and so does the ECDSA one,
+// which emulates secp256k1 with limbs sized for it. Pedersen has no gadget,
+// nor do SchnorrVerify and MerkleMembership, which hash with it: the
+// generators and the hash of Noir's reference backend are not ported, and
+// hashing with other ones would constrain different outputs than its
+// circuits expect.
+var bn254Gadgets = func() map[int]gadget[fr_bn254.Element, *fr_bn254.Element] {
+	gadgets := fieldGadgets[fr_bn254.Element, *fr_bn254.Element]()
+	for name, g := range map[int]func(*acir_opcode.BlackBoxFunction, bn254Builder) error{
+		acir_opcode.EcdsaSecp256k1:     EcdsaSecp256k1,
+		acir_opcode.FixedBaseScalarMul: FixedBaseScalarMul,
+	} {
+		g := g
+		gadgets[name] = func(bbf *acir_opcode.BlackBoxFunction, b *builder[fr_bn254.Element, *fr_bn254.Element]) error {
+			return g(bbf, bn254Builder{b})
+		}
+	}
+	return gadgets
+}()
+
+var bls12381Gadgets = fieldGadgets[fr_bls12381.Element, *fr_bls12381.Element]()
+
+var bls12377Gadgets = fieldGadgets[fr_bls12377.Element, *fr_bls12377.Element]()
+
+var bw6761Gadgets = fieldGadgets[fr_bw6761.Element, *fr_bw6761.Element]()
+
+func handleBlackBoxFunctionOpcode[T comparable, E element[T]](bbf *acir_opcode.BlackBoxFunction, b *builder[T, E], gadgets map[int]gadget[T, E]) error {
+	gadget, ok := gadgets[bbf.Name]
+	if !ok {
+		return common.Errorf(common.InvalidCircuit, "black box function %d is not supported", bbf.Name)
+	}
+	return gadget(bbf, b)
+}
+
+// SupportOf tells how the circuits built by this backend over the scalar
+// field of curveID handle the calls to the black box function called name in
+// ACIR. Functions without a gadget are rejected by
+// handleBlackBoxFunctionOpcode.
+func SupportOf(curveID ecc.ID, name string) backend.BlackBoxFunctionSupport {
+	function, ok := acir_opcode.BlackBoxFunctionNamed(name)
+	if !ok {
+		return backend.Unsupported
+	}
+	var supported bool
+	switch curveID {
+	case ecc.BN254:
+		_, supported = bn254Gadgets[function]
+	case ecc.BLS12_381:
+		_, supported = bls12381Gadgets[function]
+	case ecc.BLS12_377:
+		_, supported = bls12377Gadgets[function]
+	case ecc.BW6_761:
+		_, supported = bw6761Gadgets[function]
+	}
+	if !supported {
+		return backend.Unsupported
 	}
+	return backend.Constrained
 }
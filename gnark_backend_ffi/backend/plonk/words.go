@@ -0,0 +1,154 @@
+package plonk_backend
+
+import (
+	"math/bits"
+
+	acir_opcode "gnark_backend_ffi/acir/opcode"
+	common "gnark_backend_ffi/internal"
+)
+
+// A word is the little endian binary decomposition of an unsigned integer,
+// with a boolean variable per bit. The hash gadgets work with bytes and 32 or
+// 64 bits words.
+type word = []int
+
+// constantWord returns the nbBits bits word holding value.
+func (b *builder[T, E]) constantWord(value uint64, nbBits int) word {
+	w := make(word, nbBits)
+	for i := range w {
+		w[i] = b.constant((value >> i) & 1)
+	}
+	return w
+}
+
+// rotr rotates w right by r bits. It does not add any constraint.
+func rotr(w word, r int) word {
+	rotated := make(word, len(w))
+	for i := range rotated {
+		rotated[i] = w[(i+r)%len(w)]
+	}
+	return rotated
+}
+
+// shr shifts w right by r bits.
+func (b *builder[T, E]) shr(w word, r int) word {
+	shifted := make(word, len(w))
+	for i := range shifted {
+		if i+r < len(w) {
+			shifted[i] = w[i+r]
+		} else {
+			shifted[i] = b.constant(0)
+		}
+	}
+	return shifted
+}
+
+// xorWords returns the bitwise XOR of words of the same length.
+func (b *builder[T, E]) xorWords(words ...word) word {
+	result := words[0]
+	for _, w := range words[1:] {
+		xored := make(word, len(w))
+		for i := range xored {
+			xored[i] = b.xor(result[i], w[i])
+		}
+		result = xored
+	}
+	return result
+}
+
+// xorConstant returns the bitwise XOR of w and a constant, negating the bits
+// of w where the constant has a one.
+func (b *builder[T, E]) xorConstant(w word, constant uint64) word {
+	result := make(word, len(w))
+	for i := range result {
+		if (constant>>i)&1 == 1 {
+			result[i] = b.not(w[i])
+		} else {
+			result[i] = w[i]
+		}
+	}
+	return result
+}
+
+// addWords returns the sum of words of the same length and a constant,
+// modulo 2 to the length of the words.
+func (b *builder[T, E]) addWords(constant uint64, words ...word) word {
+	nbBits := len(words[0])
+	powers := b.powersOfTwo(nbBits)
+	coefficients := make([]T, 0, nbBits*len(words))
+	variables := make([]int, 0, nbBits*len(words))
+	for _, w := range words {
+		coefficients = append(coefficients, powers...)
+		variables = append(variables, w...)
+	}
+
+	sum := b.linearCombination(coefficients, variables)
+	if constant != 0 {
+		sum = b.addConstant(sum, constant)
+	}
+
+	// The sum of n words and a constant word is smaller than (n + 1)⋅2^nbBits,
+	// the carry bits are dropped.
+	return b.toBits(sum, nbBits+bits.Len(uint(len(words))))[:nbBits]
+}
+
+// bigEndianWord concatenates bytes, the first one being the most
+// significant.
+func bigEndianWord(bytes ...word) word {
+	w := make(word, 0, 8*len(bytes))
+	for i := len(bytes) - 1; i >= 0; i-- {
+		w = append(w, bytes[i]...)
+	}
+	return w
+}
+
+// littleEndianWord concatenates bytes, the first one being the least
+// significant.
+func littleEndianWord(bytes ...word) word {
+	w := make(word, 0, 8*len(bytes))
+	for i := range bytes {
+		w = append(w, bytes[i]...)
+	}
+	return w
+}
+
+// inputBytes decomposes the inputs of a hash function into bytes. Like the
+// witness solver, it takes the ⌈NumBits/8⌉ least significant bytes of every
+// input, least significant first, and the input must fit in NumBits bits.
+func (b *builder[T, E]) inputBytes(inputs acir_opcode.FunctionInputs) ([]word, error) {
+	var bytes []word
+	for _, input := range inputs {
+		if int(input.NumBits) >= b.nbBits {
+			return nil, common.Errorf(common.InvalidCircuit, "hash inputs support up to %d bits, got %d", b.nbBits-1, input.NumBits)
+		}
+		x, err := b.variable(input.Witness)
+		if err != nil {
+			return nil, err
+		}
+
+		inputBits := b.toBits(x, int(input.NumBits))
+		for len(inputBits)%8 != 0 {
+			inputBits = append(inputBits, b.constant(0))
+		}
+		for i := 0; i < len(inputBits); i += 8 {
+			bytes = append(bytes, inputBits[i:i+8])
+		}
+	}
+	return bytes, nil
+}
+
+// assertBytes constrains every output to hold the value of the byte at the
+// same position.
+func (b *builder[T, E]) assertBytes(outputs common.Witnesses, bytes []word) error {
+	if len(outputs) != len(bytes) {
+		return common.Errorf(common.InvalidCircuit, "expected %d outputs, got %d", len(bytes), len(outputs))
+	}
+	for i, output := range outputs {
+		variable, err := b.variable(output)
+		if err != nil {
+			return err
+		}
+		b.assertEqual(b.fromBits(bytes[i]), variable)
+	}
+	return nil
+}
@@ -0,0 +1,16 @@
+package backend
+
+// BlackBoxFunctionSupport tells how a backend handles the calls to a black box
+// function. The values are mirrored by BlackBoxFunctionSupport in
+// src/gnark_backend_wrapper/report.rs, so both lists must be kept in sync.
+type BlackBoxFunctionSupport = int
+
+const (
+	// Building a circuit calling the function fails.
+	Unsupported BlackBoxFunctionSupport = iota
+	// The calls are left out of the circuit, so nothing constrains their
+	// outputs.
+	Unconstrained
+	// A gadget constrains the outputs of the calls.
+	Constrained
+)
@@ -2,116 +2,240 @@ package backend
 
 import (
 	"bytes"
+	"encoding/binary"
 	"encoding/hex"
-	"log"
+	"io"
+	"math/big"
+
+	common "gnark_backend_ffi/internal"
 
 	"github.com/consensys/gnark-crypto/ecc"
 	fr_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/fr"
+	"github.com/consensys/gnark-crypto/kzg"
+	"github.com/consensys/gnark/backend/groth16"
 	"github.com/consensys/gnark/backend/plonk"
 )
 
-func DeserializeFelt(encodedFelt string) (felt fr_bn254.Element) {
+// CurveID returns the gnark curve called name by ecc.ID's String, which is
+// how the Rust side names its curves (see CurveId). The constraint systems are
+// built over the scalar fields of BN254, BLS12-381, BLS12-377 and BW6-761.
+func CurveID(name string) (ecc.ID, error) {
+	for _, curveID := range []ecc.ID{ecc.BN254, ecc.BLS12_381, ecc.BLS12_377, ecc.BW6_761} {
+		if curveID.String() == name {
+			return curveID, nil
+		}
+	}
+	return ecc.UNKNOWN, common.Errorf(common.InvalidCircuit, "unknown curve %q, circuits are built over bn254, bls12_381, bls12_377 and bw6_761", name)
+}
+
+// FeltSize returns the number of bytes of the elements of the scalar field of
+// curveID, which is how wide the Rust side serializes them.
+func FeltSize(curveID ecc.ID) int {
+	return (curveID.ScalarField().BitLen() + 7) / 8
+}
+
+// ZeroFelts returns count zeros of the scalar field of curveID, as the Rust
+// side serializes them.
+func ZeroFelts(count int, curveID ecc.ID) []common.Felt {
+	felts := make([]common.Felt, count)
+	for i := range felts {
+		felts[i] = make(common.Felt, FeltSize(curveID))
+	}
+	return felts
+}
+
+// CheckFelts fails if the felts are not as wide as the elements of the scalar
+// field of curveID.
+func CheckFelts(felts []common.Felt, curveID ecc.ID) error {
+	for _, felt := range felts {
+		if len(felt) != FeltSize(curveID) {
+			return common.Errorf(common.InvalidValues, "felts over %s have %d bytes, got %d", curveID, FeltSize(curveID), len(felt))
+		}
+	}
+	return nil
+}
+
+// Lift returns the integer that felt, an element of Noir's field, stands for
+// in the scalar field of curveID. The elements above half the order of Noir's
+// field stand for negative integers, like the -1 coefficients of most
+// expressions, which must stay negative in the other fields. This fails if the
+// integer does not fit in the scalar field of curveID, whose elements above
+// half its order are negative too. The Rust side converts the values it sends
+// the same way, see from_felt.
+func Lift(felt common.Felt, curveID ecc.ID) (*big.Int, error) {
+	noirOrder := common.NoirCurve.ScalarField()
+	integer := felt.BigInt()
+	if integer.Cmp(noirOrder) >= 0 {
+		return nil, common.Errorf(common.InvalidCircuit, "%x is not an element of the scalar field of %s", integer, common.NoirCurve)
+	}
+	// The orders are odd, so half of them rounded down is (order - 1) / 2.
+	if integer.Cmp(new(big.Int).Rsh(noirOrder, 1)) > 0 {
+		integer.Sub(integer, noirOrder)
+	}
+	if new(big.Int).Abs(integer).Cmp(new(big.Int).Rsh(curveID.ScalarField(), 1)) > 0 {
+		return nil, common.Errorf(common.InvalidCircuit, "%d does not fit in the scalar field of %s", integer, curveID)
+	}
+	return integer, nil
+}
+
+func DeserializeFelt(encodedFelt string) (common.Felt, error) {
 	// Decode the received felt.
 	decodedFelt, err := hex.DecodeString(encodedFelt)
 	if err != nil {
-		log.Fatal(err)
+		return nil, common.NewError(common.InvalidValues, err)
 	}
-	// Deserialize the decoded felt.
-	felt.SetBytes(decodedFelt)
-	return
+	return decodedFelt, nil
 }
 
-func DeserializeFelts(encodedFelts string) (felts fr_bn254.Vector) {
+func DeserializeFelts(encodedFelts string) (felts []common.Felt, err error) {
 	// Decode the received felts.
 	decodedFelts, err := hex.DecodeString(encodedFelts)
 	if err != nil {
-		log.Fatal(err)
+		err = common.NewError(common.InvalidValues, err)
+		return
+	}
+	// Unpack the decoded felts, a big endian uint32 count followed by the
+	// felts, which are as wide as the scalar field they belong to.
+	if len(decodedFelts) < 4 {
+		err = common.Errorf(common.InvalidValues, "felts are missing their count")
+		return
+	}
+	count := binary.BigEndian.Uint32(decodedFelts)
+	decodedFelts = decodedFelts[4:]
+	if count == 0 {
+		if len(decodedFelts) != 0 {
+			err = common.Errorf(common.InvalidValues, "expected no felts, got %d bytes", len(decodedFelts))
+		}
+		return
+	}
+	if uint64(len(decodedFelts))%uint64(count) != 0 {
+		err = common.Errorf(common.InvalidValues, "expected %d felts, got %d bytes", count, len(decodedFelts))
+		return
+	}
+	feltSize := len(decodedFelts) / int(count)
+	felts = make([]common.Felt, count)
+	for i := range felts {
+		felts[i] = decodedFelts[i*feltSize : (i+1)*feltSize]
 	}
-	// Unpack and deserialize the decoded felts.
-	felts.UnmarshalBinary(decodedFelts)
 	return
 }
 
-func DeserializeProof(serializedProof string, curveID ecc.ID) (p plonk.Proof) {
+func DeserializeProof(serializedProof string, curveID ecc.ID) (p plonk.Proof, err error) {
 	// Deserialize proof.
 	p = plonk.NewProof(curveID)
-	decodedProof, err := hex.DecodeString(serializedProof)
-	if err != nil {
-		log.Fatal(err)
-	}
-	_, err = p.ReadFrom(bytes.NewReader(decodedProof))
-	if err != nil {
-		log.Fatal(err)
-	}
+	err = readFrom(p, serializedProof, common.InvalidProof)
 	return
 }
 
-func DeserializeProvingKey(encodedProvingKey string, curveID ecc.ID) (pk plonk.ProvingKey) {
+func DeserializeProvingKey(encodedProvingKey string, curveID ecc.ID) (pk plonk.ProvingKey, err error) {
 	pk = plonk.NewProvingKey(curveID)
-	decodedProvingKey, err := hex.DecodeString(encodedProvingKey)
-	if err != nil {
-		log.Fatal(err)
-	}
-	_, err = pk.ReadFrom(bytes.NewReader([]byte(decodedProvingKey)))
-	if err != nil {
-		log.Fatal(err)
-	}
+	err = readFrom(pk, encodedProvingKey, common.InvalidKey)
 	return
 }
 
-func DeserializeVerifyingKey(serializedVerifyingKey string, curveID ecc.ID) (vk plonk.VerifyingKey) {
+func DeserializeVerifyingKey(serializedVerifyingKey string, curveID ecc.ID) (vk plonk.VerifyingKey, err error) {
 	vk = plonk.NewVerifyingKey(curveID)
-	decodedVerifyingKey, err := hex.DecodeString(serializedVerifyingKey)
-	if err != nil {
-		log.Fatal(err)
-	}
-	_, err = vk.ReadFrom(bytes.NewReader(decodedVerifyingKey))
-	if err != nil {
-		log.Fatal(err)
-	}
+	err = readFrom(vk, serializedVerifyingKey, common.InvalidKey)
 	return
 }
 
-func SerializeProof(proof plonk.Proof) (p string) {
-	var serialized_proof bytes.Buffer
-	proof.WriteTo(&serialized_proof)
-	p = hex.EncodeToString(serialized_proof.Bytes())
+func SerializeProof(proof plonk.Proof) (string, error) {
+	return writeTo(proof)
+}
+
+func SerializeProvingKey(provingKey plonk.ProvingKey) (string, error) {
+	return writeTo(provingKey)
+}
+
+func SerializeVerifyingKey(verifyingKey plonk.VerifyingKey) (string, error) {
+	return writeTo(verifyingKey)
+}
+
+func DeserializeGroth16Proof(serializedProof string, curveID ecc.ID) (p groth16.Proof, err error) {
+	p = groth16.NewProof(curveID)
+	err = readFrom(p, serializedProof, common.InvalidProof)
+	return
+}
+
+func DeserializeGroth16ProvingKey(encodedProvingKey string, curveID ecc.ID) (pk groth16.ProvingKey, err error) {
+	pk = groth16.NewProvingKey(curveID)
+	err = readFrom(pk, encodedProvingKey, common.InvalidKey)
 	return
 }
 
-func SerializeProvingKey(provingKey plonk.ProvingKey) (pk string) {
-	var serializedProvingKey bytes.Buffer
-	provingKey.WriteTo(&serializedProvingKey)
-	pk = hex.EncodeToString(serializedProvingKey.Bytes())
+func DeserializeGroth16VerifyingKey(encodedVerifyingKey string, curveID ecc.ID) (vk groth16.VerifyingKey, err error) {
+	vk = groth16.NewVerifyingKey(curveID)
+	err = readFrom(vk, encodedVerifyingKey, common.InvalidKey)
 	return
 }
 
-func SerializeVerifyingKey(verifyingKey plonk.VerifyingKey) (vk string) {
-	var serializedProvingKey bytes.Buffer
-	verifyingKey.WriteTo(&serializedProvingKey)
-	vk = hex.EncodeToString(serializedProvingKey.Bytes())
+func SerializeGroth16Proof(proof groth16.Proof) (string, error) {
+	return writeTo(proof)
+}
+
+func SerializeGroth16ProvingKey(provingKey groth16.ProvingKey) (string, error) {
+	return writeTo(provingKey)
+}
+
+func SerializeGroth16VerifyingKey(verifyingKey groth16.VerifyingKey) (string, error) {
+	return writeTo(verifyingKey)
+}
+
+// DeserializeSRS reads an SRS in gnark's binary encoding, checking that its
+// points are in the right subgroups.
+func DeserializeSRS(encodedSRS string, curveID ecc.ID) (srs kzg.SRS, err error) {
+	srs = kzg.NewSRS(curveID)
+	err = readFrom(srs, encodedSRS, common.SRSError)
 	return
 }
 
+func SerializeSRS(srs kzg.SRS) (string, error) {
+	return writeTo(srs)
+}
+
+// readFrom hex decodes encoded into r, reporting failures with the given code.
+func readFrom(r io.ReaderFrom, encoded string, code common.ErrorCode) error {
+	decoded, err := hex.DecodeString(encoded)
+	if err != nil {
+		return common.NewError(code, err)
+	}
+	_, err = r.ReadFrom(bytes.NewReader(decoded))
+	if err != nil {
+		return common.NewError(code, err)
+	}
+	return nil
+}
+
+func writeTo(w io.WriterTo) (string, error) {
+	var serialized bytes.Buffer
+	_, err := w.WriteTo(&serialized)
+	if err != nil {
+		return "", common.NewError(common.InternalError, err)
+	}
+	return hex.EncodeToString(serialized.Bytes()), nil
+}
+
 // Samples a felt and returns the encoded felt and the non-encoded felt.
-func RandomEncodedFelt() (string, fr_bn254.Element) {
-	var felt fr_bn254.Element
-	felt.SetRandom()
+func RandomEncodedFelt() (string, common.Felt) {
+	var element fr_bn254.Element
+	element.SetRandom()
 
-	return hex.EncodeToString(felt.Marshal()), felt
+	bytes := element.Bytes()
+	return hex.EncodeToString(bytes[:]), bytes[:]
 }
 
 // Samples a felts vector and returns the encoded felts and the non-encoded felts vector.
-func RandomEncodedFelts() (string, fr_bn254.Vector) {
+func RandomEncodedFelts() (string, []common.Felt) {
 	var felt1 fr_bn254.Element
 	felt1.SetRandom()
 
 	var felt2 fr_bn254.Element
 	felt2.SetRandom()
 
-	felts := fr_bn254.Vector{felt1, felt2}
+	elements := fr_bn254.Vector{felt1, felt2}
 
-	binaryFelts, _ := felts.MarshalBinary()
+	binaryFelts, _ := elements.MarshalBinary()
 
-	return hex.EncodeToString(binaryFelts), felts
+	bytes1, bytes2 := felt1.Bytes(), felt2.Bytes()
+	return hex.EncodeToString(binaryFelts), []common.Felt{bytes1[:], bytes2[:]}
 }
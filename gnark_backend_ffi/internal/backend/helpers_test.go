@@ -0,0 +1,82 @@
+package backend
+
+import (
+	"math/big"
+	"testing"
+
+	common "gnark_backend_ffi/internal"
+
+	"github.com/consensys/gnark-crypto/ecc"
+	"github.com/stretchr/testify/assert"
+)
+
+func TestCurveID(t *testing.T) {
+	for name, expected := range map[string]ecc.ID{
+		"bn254":     ecc.BN254,
+		"bls12_381": ecc.BLS12_381,
+		"bls12_377": ecc.BLS12_377,
+		"bw6_761":   ecc.BW6_761,
+	} {
+		curveID, err := CurveID(name)
+		assert.NoError(t, err)
+		assert.Equal(t, expected, curveID)
+	}
+
+	_, err := CurveID("secp256k1")
+	assert.Equal(t, common.InvalidCircuit, common.Code(err))
+}
+
+func TestFeltSize(t *testing.T) {
+	assert.Equal(t, 32, FeltSize(ecc.BN254))
+	assert.Equal(t, 32, FeltSize(ecc.BLS12_381))
+	assert.Equal(t, 32, FeltSize(ecc.BLS12_377))
+	assert.Equal(t, 48, FeltSize(ecc.BW6_761))
+}
+
+func TestDeserializeFelts(t *testing.T) {
+	encodedFelts, felts := RandomEncodedFelts()
+	deserializedFelts, err := DeserializeFelts(encodedFelts)
+	assert.NoError(t, err)
+	assert.Equal(t, felts, deserializedFelts)
+	assert.NoError(t, CheckFelts(deserializedFelts, ecc.BN254))
+	assert.Equal(t, common.InvalidValues, common.Code(CheckFelts(deserializedFelts, ecc.BW6_761)))
+
+	// The count says 2 felts but a byte is missing.
+	_, err = DeserializeFelts(encodedFelts[:len(encodedFelts)-2])
+	assert.Equal(t, common.InvalidValues, common.Code(err))
+}
+
+func TestLiftKeepsNegativeFeltsNegative(t *testing.T) {
+	noirOrder := common.NoirCurve.ScalarField()
+	minusOne := common.Felt(new(big.Int).Sub(noirOrder, big.NewInt(1)).Bytes())
+
+	for _, curveID := range []ecc.ID{ecc.BN254, ecc.BLS12_381, ecc.BLS12_377, ecc.BW6_761} {
+		integer, err := Lift(common.Felt{0x12, 0x34}, curveID)
+		assert.NoError(t, err)
+		assert.Equal(t, big.NewInt(0x1234), integer)
+
+		integer, err = Lift(minusOne, curveID)
+		assert.NoError(t, err)
+		assert.Equal(t, big.NewInt(-1), integer)
+	}
+
+	// 2^252 is larger than half the order of BLS12-377's scalar field, but
+	// not of BW6-761's.
+	large := common.Felt(new(big.Int).Lsh(big.NewInt(1), 252).Bytes())
+	_, err := Lift(large, ecc.BLS12_377)
+	assert.Equal(t, common.InvalidCircuit, common.Code(err))
+	integer, err := Lift(large, ecc.BW6_761)
+	assert.NoError(t, err)
+	assert.Equal(t, large.BigInt(), integer)
+
+	_, err = Lift(common.Felt(noirOrder.Bytes()), ecc.BN254)
+	assert.Equal(t, common.InvalidCircuit, common.Code(err))
+}
+
+func TestDeserializeSRSFailsWithInvalidSRS(t *testing.T) {
+	_, err := DeserializeSRS("not hex", ecc.BN254)
+	assert.Equal(t, common.SRSError, common.Code(err))
+
+	_, err = DeserializeSRS("00", ecc.BN254)
+	assert.Equal(t, common.SRSError, common.Code(err))
+}
@@ -1,4 +1,17 @@
 package common
 
+import "math/big"
+
 type Witness = uint32
 type Witnesses = []Witness
+
+// Felt is a field element as the Rust side serializes it, big endian bytes.
+// The values and the coefficients of a RawR1CS belong to the scalar field the
+// constraint system is built over, and the values are as wide as it. The
+// coefficients of an ACIR belong to Noir's field, see NoirCurve, and are
+// lifted to the scalar field by backend.Lift.
+type Felt []byte
+
+func (f Felt) BigInt() *big.Int {
+	return new(big.Int).SetBytes(f)
+}
@@ -0,0 +1,58 @@
+package common
+
+import (
+	"errors"
+	"fmt"
+)
+
+// ErrorCode is the status code returned to the Rust side alongside the error
+// message. Every code maps to a GnarkBackendError variant in
+// src/gnark_backend_wrapper/errors.rs, so both lists must be kept in sync.
+type ErrorCode int32
+
+const (
+	Ok ErrorCode = iota
+	InvalidCircuit
+	InvalidValues
+	InvalidProof
+	InvalidKey
+	UnsatisfiedConstraint
+	KeyMismatch
+	SRSError
+	InternalError
+	KeysUnavailable
+)
+
+type Error struct {
+	Code ErrorCode
+	Err  error
+}
+
+func (e *Error) Error() string {
+	return e.Err.Error()
+}
+
+func (e *Error) Unwrap() error {
+	return e.Err
+}
+
+func NewError(code ErrorCode, err error) error {
+	return &Error{Code: code, Err: err}
+}
+
+func Errorf(code ErrorCode, format string, a ...any) error {
+	return NewError(code, fmt.Errorf(format, a...))
+}
+
+// Code returns the ErrorCode of the outermost Error wrapped by err. A nil error
+// is Ok and an error that does not wrap an Error is an InternalError.
+func Code(err error) ErrorCode {
+	if err == nil {
+		return Ok
+	}
+	var e *Error
+	if errors.As(err, &e) {
+		return e.Code
+	}
+	return InternalError
+}
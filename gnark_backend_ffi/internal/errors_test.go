@@ -0,0 +1,17 @@
+package common
+
+import (
+	"errors"
+	"fmt"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestCode(t *testing.T) {
+	assert.Equal(t, Ok, Code(nil))
+	assert.Equal(t, InternalError, Code(errors.New("plain error")))
+	assert.Equal(t, InvalidKey, Code(Errorf(InvalidKey, "bad key")))
+	assert.Equal(t, InvalidKey, Code(fmt.Errorf("context: %w", Errorf(InvalidKey, "bad key"))))
+	assert.Equal(t, InvalidCircuit, Code(NewError(InvalidCircuit, Errorf(InvalidValues, "bad felt"))))
+}
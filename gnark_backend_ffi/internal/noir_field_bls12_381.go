@@ -0,0 +1,9 @@
+//go:build bls12_381
+
+package common
+
+import "github.com/consensys/gnark-crypto/ecc"
+
+// NoirCurve is the curve whose scalar field Noir's field elements belong to,
+// see noir_field_bn254.go.
+const NoirCurve = ecc.BLS12_381
@@ -0,0 +1,10 @@
+//go:build !bls12_381
+
+package common
+
+import "github.com/consensys/gnark-crypto/ecc"
+
+// NoirCurve is the curve whose scalar field Noir's field elements belong to.
+// It follows the field feature of the Rust side, whose build script builds
+// this package with the bls12_381 tag for BLS12-381.
+const NoirCurve = ecc.BN254
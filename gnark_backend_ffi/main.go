@@ -1,15 +1,19 @@
 package main
 
+// #include <stdlib.h>
 import "C"
 import (
 	"crypto/rand"
 	"encoding/json"
 	"fmt"
 	"log"
+	"unsafe"
 
 	"gnark_backend_ffi/acir"
 	"gnark_backend_ffi/backend"
+	groth16_backend "gnark_backend_ffi/backend/groth16"
 	plonk_backend "gnark_backend_ffi/backend/plonk"
+	common "gnark_backend_ffi/internal"
 	backend_helpers "gnark_backend_ffi/internal/backend"
 
 	"github.com/consensys/gnark-crypto/ecc"
@@ -21,60 +25,565 @@ import (
 	cs_bn254 "github.com/consensys/gnark/constraint/bn254"
 )
 
+// Every exported function returns an error code and an error message after
+// its results. The error code is common.Ok and the message is nil on success;
+// otherwise the results are zero values and the message describes the error.
+//
+// The proving functions take the name of the curve whose scalar field the
+// Rust side works with first, see backend_helpers.CurveID. The PLONK ones
+// take last the handle of an SRS loaded with LoadSRS, which the Rust side
+// frees with FreeSRS once it drops the SRS.
+
 //export PlonkProveWithPK
-func PlonkProveWithPK(acirJSON string, encodedValues string, encodedProvingKey string) *C.char {
-	var circuit acir.ACIR
-	err := json.Unmarshal([]byte(acirJSON), &circuit)
+func PlonkProveWithPK(curve string, acirJSON string, encodedValues string, encodedProvingKey string, srsHandle C.ulonglong) (*C.char, C.int, *C.char) {
+	return stringResult(func() (string, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return "", err
+		}
+		circuit, err := deserializeACIR(acirJSON)
+		if err != nil {
+			return "", err
+		}
+		values, err := backend_helpers.DeserializeFelts(encodedValues)
+		if err != nil {
+			return "", err
+		}
+		provingKey, err := backend_helpers.DeserializeProvingKey(encodedProvingKey, curveID)
+		if err != nil {
+			return "", err
+		}
+		srs, err := backend.SRSOf(uint64(srsHandle), curveID)
+		if err != nil {
+			return "", err
+		}
+
+		proof, err := plonk_backend.ProveWithPK(circuit, provingKey, values, curveID, srs)
+		if err != nil {
+			return "", err
+		}
+
+		return backend_helpers.SerializeProof(proof)
+	})
+}
+
+//export PlonkProveWithMeta
+func PlonkProveWithMeta(curve string, acirJSON string, encodedValues string, srsHandle C.ulonglong) (*C.char, C.int, *C.char) {
+	return stringResult(func() (string, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return "", err
+		}
+		circuit, err := deserializeACIR(acirJSON)
+		if err != nil {
+			return "", err
+		}
+		values, err := backend_helpers.DeserializeFelts(encodedValues)
+		if err != nil {
+			return "", err
+		}
+		srs, err := backend.SRSOf(uint64(srsHandle), curveID)
+		if err != nil {
+			return "", err
+		}
+
+		proof, err := plonk_backend.ProveWithMeta(circuit, values, curveID, srs)
+		if err != nil {
+			return "", err
+		}
+
+		return backend_helpers.SerializeProof(proof)
+	})
+}
+
+//export PlonkVerifyWithMeta
+func PlonkVerifyWithMeta(curve string, acirJSON string, encodedValues string, encodedProof string, srsHandle C.ulonglong) (bool, C.int, *C.char) {
+	return boolResult(func() (bool, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return false, err
+		}
+		circuit, err := deserializeACIR(acirJSON)
+		if err != nil {
+			return false, err
+		}
+		values, err := backend_helpers.DeserializeFelts(encodedValues)
+		if err != nil {
+			return false, err
+		}
+		proof, err := backend_helpers.DeserializeProof(encodedProof, curveID)
+		if err != nil {
+			return false, err
+		}
+		srs, err := backend.SRSOf(uint64(srsHandle), curveID)
+		if err != nil {
+			return false, err
+		}
+
+		return plonk_backend.VerifyWithMeta(circuit, proof, values, curveID, srs)
+	})
+}
+
+//export PlonkVerifyWithVK
+func PlonkVerifyWithVK(curve string, acirJSON string, encodedProof string, encodedPublicInputs string, encodedVerifyingKey string, srsHandle C.ulonglong) (bool, C.int, *C.char) {
+	return boolResult(func() (bool, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return false, err
+		}
+		circuit, err := deserializeACIR(acirJSON)
+		if err != nil {
+			return false, err
+		}
+		proof, err := backend_helpers.DeserializeProof(encodedProof, curveID)
+		if err != nil {
+			return false, err
+		}
+		publicInputs, err := backend_helpers.DeserializeFelts(encodedPublicInputs)
+		if err != nil {
+			return false, err
+		}
+		verifyingKey, err := backend_helpers.DeserializeVerifyingKey(encodedVerifyingKey, curveID)
+		if err != nil {
+			return false, err
+		}
+		srs, err := backend.SRSOf(uint64(srsHandle), curveID)
+		if err != nil {
+			return false, err
+		}
+
+		return plonk_backend.VerifyWithVK(circuit, verifyingKey, proof, publicInputs, curveID, srs)
+	})
+}
+
+//export PlonkPreprocess
+func PlonkPreprocess(curve string, acirJSON string, encodedRandomValues string, srsHandle C.ulonglong) (*C.char, *C.char, C.int, *C.char) {
+	return keyPairResult(func() (string, string, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return "", "", err
+		}
+		circuit, err := deserializeACIR(acirJSON)
+		if err != nil {
+			return "", "", err
+		}
+		// TODO: Fix this in the Rust backend side. We should not receive a JSON.
+		// Decode values.
+		var valuesToDecode string
+		err = json.Unmarshal([]byte(encodedRandomValues), &valuesToDecode)
+		if err != nil {
+			return "", "", common.NewError(common.InvalidValues, err)
+		}
+		decodedRandomValues, err := backend_helpers.DeserializeFelts(valuesToDecode)
+		if err != nil {
+			return "", "", err
+		}
+		srs, err := backend.SRSOf(uint64(srsHandle), curveID)
+		if err != nil {
+			return "", "", err
+		}
+
+		provingKey, verifyingKey, err := plonk_backend.Preprocess(circuit, decodedRandomValues, curveID, srs)
+		if err != nil {
+			return "", "", err
+		}
+
+		serializedProvingKey, err := backend_helpers.SerializeProvingKey(provingKey)
+		if err != nil {
+			return "", "", err
+		}
+		serializedVerifyingKey, err := backend_helpers.SerializeVerifyingKey(verifyingKey)
+		if err != nil {
+			return "", "", err
+		}
+
+		return serializedProvingKey, serializedVerifyingKey, nil
+	})
+}
+
+//export PlonkGetExactCircuitSize
+func PlonkGetExactCircuitSize(curve string, acirJSON string) (C.uint, C.int, *C.char) {
+	return sizeResult(func() (int, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return 0, err
+		}
+		circuit, err := deserializeACIR(acirJSON)
+		if err != nil {
+			return 0, err
+		}
+
+		return plonk_backend.CircuitSize(circuit, curveID)
+	})
+}
+
+//export PlonkRequiredSRSSize
+func PlonkRequiredSRSSize(curve string, acirJSON string) (C.uint, C.int, *C.char) {
+	return sizeResult(func() (int, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return 0, err
+		}
+		circuit, err := deserializeACIR(acirJSON)
+		if err != nil {
+			return 0, err
+		}
+
+		return plonk_backend.RequiredSRSSize(circuit, curveID)
+	})
+}
+
+// GenerateInsecureSRS returns an SRS of size points made from local
+// randomness, see backend.NewInsecureSRS. It must only be used for tests.
+//
+//export GenerateInsecureSRS
+func GenerateInsecureSRS(curve string, size C.uint) (*C.char, C.int, *C.char) {
+	return stringResult(func() (string, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return "", err
+		}
+
+		srs, err := backend.NewInsecureSRS(curveID, int(size))
+		if err != nil {
+			return "", err
+		}
+
+		return backend_helpers.SerializeSRS(srs)
+	})
+}
+
+// LoadSRS checks that encodedSRS is a valid SRS over the curve and keeps it
+// for the PLONK functions, returning its handle and its number of G1 points.
+//
+//export LoadSRS
+func LoadSRS(curve string, encodedSRS string) (C.ulonglong, C.uint, C.int, *C.char) {
+	return srsHandleResult(func() (uint64, int, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return 0, 0, err
+		}
+		srs, err := backend_helpers.DeserializeSRS(encodedSRS, curveID)
+		if err != nil {
+			return 0, 0, err
+		}
+		size, err := backend.SRSSize(srs)
+		if err != nil {
+			return 0, 0, err
+		}
+
+		return backend.LoadSRS(srs), size, nil
+	})
+}
+
+// SerializeSRS returns the SRS loaded with srsHandle in gnark's binary
+// encoding, hex encoded, which LoadSRS reads back.
+//
+//export SerializeSRS
+func SerializeSRS(curve string, srsHandle C.ulonglong) (*C.char, C.int, *C.char) {
+	return stringResult(func() (string, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return "", err
+		}
+		srs, err := backend.SRSOf(uint64(srsHandle), curveID)
+		if err != nil {
+			return "", err
+		}
+
+		return backend_helpers.SerializeSRS(srs)
+	})
+}
+
+// FreeSRS releases the SRS loaded with srsHandle.
+//
+//export FreeSRS
+func FreeSRS(srsHandle C.ulonglong) {
+	backend.FreeSRS(uint64(srsHandle))
+}
+
+//export Groth16ProveWithMeta
+func Groth16ProveWithMeta(curve string, rawR1CSJSON string) (*C.char, C.int, *C.char) {
+	return stringResult(func() (string, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return "", err
+		}
+		r, err := deserializeRawR1CS(rawR1CSJSON)
+		if err != nil {
+			return "", err
+		}
+
+		proof, err := groth16_backend.ProveWithMeta(r, curveID)
+		if err != nil {
+			return "", err
+		}
+
+		return backend_helpers.SerializeGroth16Proof(proof)
+	})
+}
+
+//export Groth16ProveWithPK
+func Groth16ProveWithPK(curve string, rawR1CSJSON string, encodedProvingKey string) (*C.char, C.int, *C.char) {
+	return stringResult(func() (string, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return "", err
+		}
+		r, err := deserializeRawR1CS(rawR1CSJSON)
+		if err != nil {
+			return "", err
+		}
+		provingKey, err := backend_helpers.DeserializeGroth16ProvingKey(encodedProvingKey, curveID)
+		if err != nil {
+			return "", err
+		}
+
+		proof, err := groth16_backend.ProveWithPK(r, provingKey, curveID)
+		if err != nil {
+			return "", err
+		}
+
+		return backend_helpers.SerializeGroth16Proof(proof)
+	})
+}
+
+//export Groth16VerifyWithMeta
+func Groth16VerifyWithMeta(curve string, rawR1CSJSON string, encodedProof string) (bool, C.int, *C.char) {
+	return boolResult(func() (bool, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return false, err
+		}
+		r, err := deserializeRawR1CS(rawR1CSJSON)
+		if err != nil {
+			return false, err
+		}
+		proof, err := backend_helpers.DeserializeGroth16Proof(encodedProof, curveID)
+		if err != nil {
+			return false, err
+		}
+
+		return groth16_backend.VerifyWithMeta(r, proof, curveID)
+	})
+}
+
+//export Groth16VerifyWithVK
+func Groth16VerifyWithVK(curve string, rawR1CSJSON string, encodedProof string, encodedVerifyingKey string) (bool, C.int, *C.char) {
+	return boolResult(func() (bool, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return false, err
+		}
+		r, err := deserializeRawR1CS(rawR1CSJSON)
+		if err != nil {
+			return false, err
+		}
+		proof, err := backend_helpers.DeserializeGroth16Proof(encodedProof, curveID)
+		if err != nil {
+			return false, err
+		}
+		verifyingKey, err := backend_helpers.DeserializeGroth16VerifyingKey(encodedVerifyingKey, curveID)
+		if err != nil {
+			return false, err
+		}
+
+		return groth16_backend.VerifyWithVK(r, verifyingKey, proof, curveID)
+	})
+}
+
+//export Groth16Preprocess
+func Groth16Preprocess(curve string, rawR1CSJSON string) (*C.char, *C.char, C.int, *C.char) {
+	return keyPairResult(func() (string, string, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return "", "", err
+		}
+		r, err := deserializeRawR1CS(rawR1CSJSON)
+		if err != nil {
+			return "", "", err
+		}
+
+		provingKey, verifyingKey, err := groth16_backend.Preprocess(r, curveID)
+		if err != nil {
+			return "", "", err
+		}
+
+		serializedProvingKey, err := backend_helpers.SerializeGroth16ProvingKey(provingKey)
+		if err != nil {
+			return "", "", err
+		}
+		serializedVerifyingKey, err := backend_helpers.SerializeGroth16VerifyingKey(verifyingKey)
+		if err != nil {
+			return "", "", err
+		}
+
+		return serializedProvingKey, serializedVerifyingKey, nil
+	})
+}
+
+//export Groth16GetExactCircuitSize
+func Groth16GetExactCircuitSize(curve string, rawR1CSJSON string) (C.uint, C.int, *C.char) {
+	return sizeResult(func() (int, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return 0, err
+		}
+		r, err := deserializeRawR1CS(rawR1CSJSON)
+		if err != nil {
+			return 0, err
+		}
+
+		return groth16_backend.CircuitSize(r, curveID)
+	})
+}
+
+// BlackBoxFunctionSupport tells how the circuits built by the provingScheme
+// backend ("plonk" or "groth16") over the curve handle the calls to the black
+// box function called name in ACIR, see backend.BlackBoxFunctionSupport.
+//
+//export BlackBoxFunctionSupport
+func BlackBoxFunctionSupport(curve string, provingScheme string, name string) (C.uint, C.int, *C.char) {
+	return sizeResult(func() (int, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return 0, err
+		}
+		switch provingScheme {
+		case "plonk":
+			return plonk_backend.SupportOf(curveID, name), nil
+		case "groth16":
+			return groth16_backend.SupportOf(name), nil
+		}
+		return 0, common.Errorf(common.InternalError, "unknown proving scheme %q", provingScheme)
+	})
+}
+
+// BlackBoxFunctionGates returns the number of gates the circuits built by the
+// provingScheme backend over the curve spend on the black box function call
+// the circuit acirJSON holds alone. The raw R1CS of Groth16 leaves the calls
+// out, and the Plonk gadgets add the same gates whatever the values of the
+// witnesses, so the circuit is built with zeroed values.
+//
+//export BlackBoxFunctionGates
+func BlackBoxFunctionGates(curve string, provingScheme string, acirJSON string) (C.uint, C.int, *C.char) {
+	return sizeResult(func() (int, error) {
+		curveID, err := backend_helpers.CurveID(curve)
+		if err != nil {
+			return 0, err
+		}
+		circuit, err := deserializeACIR(acirJSON)
+		if err != nil {
+			return 0, err
+		}
+		switch provingScheme {
+		case "plonk":
+			return plonk_backend.CircuitSize(circuit, curveID)
+		case "groth16":
+			return 0, nil
+		}
+		return 0, common.Errorf(common.InternalError, "unknown proving scheme %q", provingScheme)
+	})
+}
+
+// FreeCString releases a string returned by any of the exported functions.
+// The Rust side calls it once it has copied the string.
+//
+//export FreeCString
+func FreeCString(ptr *C.char) {
+	C.free(unsafe.Pointer(ptr))
+}
+
+func deserializeACIR(acirJSON string) (circuit acir.ACIR, err error) {
+	err = json.Unmarshal([]byte(acirJSON), &circuit)
 	if err != nil {
-		log.Fatal(err)
+		err = common.NewError(common.InvalidCircuit, err)
 	}
-	values := backend_helpers.DeserializeFelts(encodedValues)
-	provingKey := backend_helpers.DeserializeProvingKey(encodedProvingKey, ecc.BN254)
+	return
+}
 
-	proof := plonk_backend.ProveWithPK(circuit, provingKey, values, ecc.BN254)
+func deserializeRawR1CS(rawR1CSJSON string) (r groth16_backend.RawR1CS, err error) {
+	err = json.Unmarshal([]byte(rawR1CSJSON), &r)
+	if err != nil {
+		err = common.NewError(common.InvalidCircuit, err)
+	}
+	return
+}
 
-	return C.CString(backend_helpers.SerializeProof(proof))
+// errorStatus turns err into the error code and message returned to Rust.
+func errorStatus(err error) (C.int, *C.char) {
+	if err == nil {
+		return C.int(common.Ok), nil
+	}
+	return C.int(common.Code(err)), C.CString(err.Error())
 }
 
-//export PlonkVerifyWithMeta
-func PlonkVerifyWithMeta(acirJSON string, encodedValues string, encodedProof string) bool {
-	return false
+// recoverStatus reports a panic as an InternalError instead of letting it
+// take down the process that embeds the library.
+func recoverStatus(errorCode *C.int, errorMessage **C.char) {
+	if r := recover(); r != nil {
+		*errorCode, *errorMessage = errorStatus(common.Errorf(common.InternalError, "%v", r))
+	}
 }
 
-//export PlonkVerifyWithVK
-func PlonkVerifyWithVK(acirJSON string, encodedProof string, encodedPublicInputs string, encodedVerifyingKey string) bool {
-	var circuit acir.ACIR
-	err := json.Unmarshal([]byte(acirJSON), &circuit)
+func stringResult(f func() (string, error)) (result *C.char, errorCode C.int, errorMessage *C.char) {
+	defer recoverStatus(&errorCode, &errorMessage)
+
+	s, err := f()
 	if err != nil {
-		log.Fatal(err)
+		errorCode, errorMessage = errorStatus(err)
+		return
 	}
-	proof := backend_helpers.DeserializeProof(encodedProof, ecc.BN254)
-	publicInputs := backend_helpers.DeserializeFelts(encodedPublicInputs)
-	verifyingKey := backend_helpers.DeserializeVerifyingKey(encodedVerifyingKey, ecc.BN254)
 
-	return plonk_backend.VerifyWithVK(circuit, verifyingKey, proof, publicInputs, ecc.BN254)
+	return C.CString(s), C.int(common.Ok), nil
 }
 
-//export PlonkPreprocess
-func PlonkPreprocess(acirJSON string, encodedRandomValues string) (*C.char, *C.char) {
-	// Deserialize ACIR.
-	var acir acir.ACIR
-	err := json.Unmarshal([]byte(acirJSON), &acir)
+func boolResult(f func() (bool, error)) (result bool, errorCode C.int, errorMessage *C.char) {
+	defer recoverStatus(&errorCode, &errorMessage)
+
+	b, err := f()
 	if err != nil {
-		log.Fatal(err)
+		errorCode, errorMessage = errorStatus(err)
+		return
 	}
-	// TODO: Fix this in the Rust backend side. We should not receive a JSON.
-	// Decode values.
-	var valuesToDecode string
-	err = json.Unmarshal([]byte(encodedRandomValues), &valuesToDecode)
+
+	return b, C.int(common.Ok), nil
+}
+
+func keyPairResult(f func() (string, string, error)) (provingKey *C.char, verifyingKey *C.char, errorCode C.int, errorMessage *C.char) {
+	defer recoverStatus(&errorCode, &errorMessage)
+
+	pk, vk, err := f()
 	if err != nil {
-		log.Fatal(err)
+		errorCode, errorMessage = errorStatus(err)
+		return
+	}
+
+	return C.CString(pk), C.CString(vk), C.int(common.Ok), nil
+}
+
+func sizeResult(f func() (int, error)) (size C.uint, errorCode C.int, errorMessage *C.char) {
+	defer recoverStatus(&errorCode, &errorMessage)
+
+	n, err := f()
+	if err != nil {
+		errorCode, errorMessage = errorStatus(err)
+		return
 	}
-	decodedRandomValues := backend_helpers.DeserializeFelts(valuesToDecode)
 
-	provingKey, verifyingKey := plonk_backend.Preprocess(acir, decodedRandomValues)
+	return C.uint(n), C.int(common.Ok), nil
+}
+
+func srsHandleResult(f func() (uint64, int, error)) (handle C.ulonglong, size C.uint, errorCode C.int, errorMessage *C.char) {
+	defer recoverStatus(&errorCode, &errorMessage)
+
+	h, n, err := f()
+	if err != nil {
+		errorCode, errorMessage = errorStatus(err)
+		return
+	}
 
-	return C.CString(backend_helpers.SerializeProvingKey(provingKey)), C.CString(backend_helpers.SerializeVerifyingKey(verifyingKey))
+	return C.ulonglong(h), C.uint(n), C.int(common.Ok), nil
 }
 
 func ExampleSimpleCircuit() {
@@ -126,7 +635,7 @@ func ExampleSimpleCircuit() {
 
 	fmt.Println("Proving...")
 
-	witness := backend.BuildWitnesses(r1cs.CurveID().ScalarField(), publicVariables, secretVariables, r1cs.GetNbPublicVariables()-1, r1cs.GetNbSecretVariables())
+	witness, _ := backend.BuildWitnesses(r1cs.CurveID().ScalarField(), publicVariables, secretVariables, r1cs.GetNbPublicVariables()-1, r1cs.GetNbSecretVariables())
 
 	p, _ := groth16.Prove(r1cs, pk, witness)
 
@@ -154,7 +663,15 @@ func PlonkExample(acirJSON string, values fr_bn254.Vector) {
 	fmt.Println()
 
 	fmt.Println("Building Sparse R1CS...")
-	sparseR1CS, publicVariables, secretVariables := plonk_backend.BuildSparseR1CS(a, values)
+	felts := make([]common.Felt, len(values))
+	for i := range values {
+		bytes := values[i].Bytes()
+		felts[i] = bytes[:]
+	}
+	sparseR1CS, witness, err := plonk_backend.BuildSparseR1CS(a, felts, ecc.BN254)
+	if err != nil {
+		log.Fatal(err)
+	}
 	fmt.Println("Sparse R1CS built.")
 	fmt.Println("Constraints:")
 	constraints, res := sparseR1CS.GetConstraints()
@@ -163,11 +680,6 @@ func PlonkExample(acirJSON string, values fr_bn254.Vector) {
 	}
 	fmt.Println()
 
-	fmt.Println("Building witness...")
-	witness := backend.BuildWitnesses(sparseR1CS.CurveID().ScalarField(), publicVariables, secretVariables, sparseR1CS.GetNbPublicVariables(), sparseR1CS.GetNbSecretVariables())
-	fmt.Println("Witness built.")
-	fmt.Println()
-
 	fmt.Println("Setting up...")
 	alpha, err := rand.Int(rand.Reader, sparseR1CS.CurveID().ScalarField())
 	if err != nil {
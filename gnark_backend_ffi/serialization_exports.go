@@ -0,0 +1,110 @@
+package main
+
+import "C"
+import (
+	"encoding/binary"
+	"encoding/hex"
+	"encoding/json"
+	"log"
+
+	groth16_backend "gnark_backend_ffi/backend/groth16"
+	backend_helpers "gnark_backend_ffi/internal/backend"
+)
+
+// The functions in this file deserialize what the Rust side sends and
+// serialize it back, so that tests/serialization_tests.rs can check that both
+// sides agree on the encoding of the RawR1CS structures.
+
+//export IntegrationTestFeltSerialization
+func IntegrationTestFeltSerialization(encodedFelt string) *C.char {
+	deserializedFelt, err := backend_helpers.DeserializeFelt(encodedFelt)
+	if err != nil {
+		log.Fatal(err)
+	}
+
+	// Encode the felt, which is already serialized.
+	serializedFeltString := hex.EncodeToString(deserializedFelt)
+
+	return C.CString(serializedFeltString)
+}
+
+//export IntegrationTestFeltsSerialization
+func IntegrationTestFeltsSerialization(encodedFelts string) *C.char {
+	deserializedFelts, err := backend_helpers.DeserializeFelts(encodedFelts)
+	if err != nil {
+		log.Fatal(err)
+	}
+
+	// Serialize the felts, a big endian uint32 count followed by the felts.
+	serializedFelts := make([]byte, 4)
+	binary.BigEndian.PutUint32(serializedFelts, uint32(len(deserializedFelts)))
+	for _, felt := range deserializedFelts {
+		serializedFelts = append(serializedFelts, felt...)
+	}
+
+	// Encode the serialized felts.
+	serializedFeltsString := hex.EncodeToString(serializedFelts)
+
+	return C.CString(serializedFeltsString)
+}
+
+//export IntegrationTestU64Serialization
+func IntegrationTestU64Serialization(number uint64) uint64 {
+	return number
+}
+
+//export IntegrationTestMulTermSerialization
+func IntegrationTestMulTermSerialization(mulTermJSON string) *C.char {
+	var deserializedMulTerm groth16_backend.RawMulTerm
+	return pingPong(mulTermJSON, &deserializedMulTerm)
+}
+
+//export IntegrationTestMulTermsSerialization
+func IntegrationTestMulTermsSerialization(mulTermsJSON string) *C.char {
+	var deserializedMulTerms []groth16_backend.RawMulTerm
+	return pingPong(mulTermsJSON, &deserializedMulTerms)
+}
+
+//export IntegrationTestAddTermSerialization
+func IntegrationTestAddTermSerialization(addTermJSON string) *C.char {
+	var deserializedAddTerm groth16_backend.RawAddTerm
+	return pingPong(addTermJSON, &deserializedAddTerm)
+}
+
+//export IntegrationTestAddTermsSerialization
+func IntegrationTestAddTermsSerialization(addTermsJSON string) *C.char {
+	var deserializedAddTerms []groth16_backend.RawAddTerm
+	return pingPong(addTermsJSON, &deserializedAddTerms)
+}
+
+//export IntegrationTestRawGateSerialization
+func IntegrationTestRawGateSerialization(rawGateJSON string) *C.char {
+	var deserializedRawGate groth16_backend.RawGate
+	return pingPong(rawGateJSON, &deserializedRawGate)
+}
+
+//export IntegrationTestRawGatesSerialization
+func IntegrationTestRawGatesSerialization(rawGatesJSON string) *C.char {
+	var deserializedRawGates []groth16_backend.RawGate
+	return pingPong(rawGatesJSON, &deserializedRawGates)
+}
+
+//export IntegrationTestRawR1CSSerialization
+func IntegrationTestRawR1CSSerialization(rawR1CSJSON string) *C.char {
+	var deserializedRawR1CS groth16_backend.RawR1CS
+	return pingPong(rawR1CSJSON, &deserializedRawR1CS)
+}
+
+func pingPong(serialized string, deserialized interface{}) *C.char {
+	err := json.Unmarshal([]byte(serialized), deserialized)
+	if err != nil {
+		log.Fatal(err)
+	}
+
+	reserialized, err := json.Marshal(deserialized)
+	if err != nil {
+		log.Fatal(err)
+	}
+
+	return C.CString(string(reserialized))
+}
@@ -1,8 +1,8 @@
 pub use acvm::{
     acir::{
-        circuit::{Circuit, Opcode, PublicInputs},
+        circuit::{opcodes::BlackBoxFuncCall, Circuit, Opcode, PublicInputs},
         native_types::{Expression, Witness},
-        FieldElement,
+        BlackBoxFunc, FieldElement,
     },
     pwg::witness_to_value,
     PartialWitnessGenerator,
@@ -1,23 +1,58 @@
-// Allow unwrap because the Backend traits don't support Result.
-#![allow(clippy::unwrap_used)]
-
 use acvm::acir::{
-    circuit::opcodes::BlackBoxFuncCall, circuit::Circuit, native_types::Witness, BlackBoxFunc,
+    circuit::opcodes::{BlackBoxFuncCall, FunctionInput},
+    circuit::Circuit,
+    native_types::Witness,
+    BlackBoxFunc,
 };
 use acvm::pwg::hash::{blake2s, sha256};
 use acvm::pwg::logic::solve_logic_opcode;
 use acvm::pwg::range::solve_range_opcode;
-use acvm::pwg::signature::ecdsa::secp256k1_prehashed;
 use acvm::pwg::witness_to_value;
 use acvm::{
     FieldElement, Language, OpcodeResolutionError, PartialWitnessGenerator, ProofSystemCompiler,
     SmartContract,
 };
 use std::collections::BTreeMap;
+use std::sync::Arc;
+
+mod aes;
+mod ecdsa;
+mod grumpkin;
+mod keccak;
 
 use crate::gnark_backend_wrapper as gnark_backend;
+use crate::gnark_backend_wrapper::{
+    BlackBoxFunctionSupport, CurveId, GnarkBackendError, ProvingScheme, Srs,
+};
 
-pub struct Gnark;
+/// The gnark backend.
+///
+/// Circuits are preprocessed, proven and verified with its proving scheme, the
+/// default one being picked by the `groth16` and `plonk` features, over its
+/// curve, see [`Gnark::with_curve`].
+///
+/// By default circuits are proven even if the proving scheme leaves some of
+/// their opcodes unconstrained, the proofs saying nothing about those. In
+/// strict mode, see [`Gnark::strict`], preprocessing and proving fail instead.
+///
+/// PLONK needs an SRS, which the backend never loads nor generates by itself,
+/// see [`Gnark::with_srs`].
+///
+/// `Gnark` used to be a unit struct: [`Gnark::default()`], or
+/// [`Gnark::DEFAULT`] in constants, is the backend that `Gnark` was.
+#[derive(Debug, Clone)]
+pub struct Gnark {
+    proving_scheme: ProvingScheme,
+    curve: CurveId,
+    strict: bool,
+    srs: Option<Arc<Srs>>,
+}
+
+impl Default for Gnark {
+    fn default() -> Self {
+        Gnark::DEFAULT
+    }
+}
 
 impl acvm::Backend for Gnark {}
 
@@ -32,37 +67,233 @@ fn get_values_from_witness_tree(
         .collect()
 }
 
+/// Fallible counterparts of the `ProofSystemCompiler` methods.
+///
+/// The acvm traits can not return errors, so their implementations for `Gnark`
+/// panic on any failure. Library users that need to handle errors should call
+/// these methods instead.
+impl Gnark {
+    /// The backend with the proving scheme and the curve picked by the
+    /// features, not strict and without an SRS.
+    pub const DEFAULT: Gnark = Gnark::new(ProvingScheme::DEFAULT);
+
+    pub const fn new(proving_scheme: ProvingScheme) -> Self {
+        Self {
+            proving_scheme,
+            curve: CurveId::DEFAULT,
+            strict: false,
+            srs: None,
+        }
+    }
+
+    /// Returns the same backend refusing to preprocess or prove circuits with
+    /// unconstrained opcodes.
+    pub fn strict(self) -> Self {
+        Self {
+            strict: true,
+            ..self
+        }
+    }
+
+    /// Returns the same backend building circuits over `curve`. The default
+    /// curve is the one of the field feature, keys and proofs made over
+    /// another curve are rejected.
+    pub fn with_curve(self, curve: CurveId) -> Self {
+        Self { curve, ..self }
+    }
+
+    /// Returns the same backend preprocessing, proving and verifying PLONK
+    /// circuits with `srs`, which must be over the backend's curve.
+    pub fn with_srs(self, srs: Srs) -> Self {
+        Self {
+            srs: Some(Arc::new(srs)),
+            ..self
+        }
+    }
+
+    pub fn proving_scheme(&self) -> ProvingScheme {
+        self.proving_scheme
+    }
+
+    pub fn curve(&self) -> CurveId {
+        self.curve
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    pub fn srs(&self) -> Option<&Srs> {
+        self.srs.as_deref()
+    }
+
+    /// Generates the proving and verifying keys of `circuit`.
+    pub fn try_preprocess(
+        &self,
+        circuit: &Circuit,
+    ) -> Result<(Vec<u8>, Vec<u8>), GnarkBackendError> {
+        self.check_circuit_supported(circuit)?;
+        self.check_srs(circuit)?;
+        gnark_backend::preprocess(self.proving_scheme, self.curve, circuit, self.srs())
+    }
+
+    /// Proves that `witness_values` satisfy `circuit` using a proving key
+    /// returned by [`Gnark::try_preprocess`].
+    pub fn try_prove_with_pk(
+        &self,
+        circuit: &Circuit,
+        witness_values: BTreeMap<Witness, FieldElement>,
+        proving_key: &[u8],
+    ) -> Result<Vec<u8>, GnarkBackendError> {
+        self.check_circuit_supported(circuit)?;
+        // TODO: modify gnark serializer to accept the BTreeMap
+        let values = get_values_from_witness_tree(circuit.num_vars(), witness_values);
+        gnark_backend::prove_with_pk(
+            self.proving_scheme,
+            self.curve,
+            circuit,
+            values,
+            proving_key,
+            self.srs(),
+        )
+    }
+
+    /// Verifies a proof of `circuit` using a verifying key returned by
+    /// [`Gnark::try_preprocess`].
+    pub fn try_verify_with_vk(
+        &self,
+        proof: &[u8],
+        public_inputs: BTreeMap<Witness, FieldElement>,
+        circuit: &Circuit,
+        verification_key: &[u8],
+    ) -> Result<bool, GnarkBackendError> {
+        let public = get_values_from_witness_tree(circuit.num_vars(), public_inputs);
+        gnark_backend::verify_with_vk(
+            self.proving_scheme,
+            self.curve,
+            circuit,
+            proof,
+            &public,
+            verification_key,
+            self.srs(),
+        )
+    }
+
+    /// Proves that `witness_values` satisfy `circuit` without a proving key.
+    /// The proof can only be checked with [`Gnark::try_verify_from_cs`].
+    pub fn try_prove_with_meta(
+        &self,
+        circuit: Circuit,
+        witness_values: BTreeMap<Witness, FieldElement>,
+    ) -> Result<Vec<u8>, GnarkBackendError> {
+        self.check_circuit_supported(&circuit)?;
+        // TODO: modify gnark serializer to accept the BTreeMap
+        let values = get_values_from_witness_tree(circuit.num_vars(), witness_values);
+        gnark_backend::prove_with_meta(self.proving_scheme, self.curve, circuit, values, self.srs())
+    }
+
+    /// Verifies a proof returned by [`Gnark::try_prove_with_meta`].
+    /// `public_inputs` holds the values of the circuit's public inputs in
+    /// ascending witness order.
+    ///
+    /// Groth16 proofs only verify in the process that made them, as long as
+    /// it still holds the keys they were made with. Otherwise this fails with
+    /// [`GnarkBackendError::KeysUnavailableError`], and the proof has to be made
+    /// with [`Gnark::try_prove_with_pk`] and checked with
+    /// [`Gnark::try_verify_with_vk`] instead, see
+    /// [`gnark_backend::verify_with_meta`].
+    pub fn try_verify_from_cs(
+        &self,
+        proof: &[u8],
+        public_inputs: Vec<FieldElement>,
+        circuit: Circuit,
+    ) -> Result<bool, GnarkBackendError> {
+        gnark_backend::verify_with_meta(
+            self.proving_scheme,
+            self.curve,
+            circuit,
+            proof,
+            &public_inputs,
+            self.srs(),
+        )
+    }
+
+    /// Returns the number of constraints of the constraint system built for
+    /// `circuit`.
+    pub fn try_get_exact_circuit_size(&self, circuit: &Circuit) -> Result<u32, GnarkBackendError> {
+        gnark_backend::get_exact_circuit_size(self.proving_scheme, self.curve, circuit)
+    }
+
+    /// Returns the number of points the SRS needs to have for `circuit`, or
+    /// `None` if the proving scheme does not use one.
+    pub fn try_required_srs_size(
+        &self,
+        circuit: &Circuit,
+    ) -> Result<Option<u32>, GnarkBackendError> {
+        gnark_backend::required_srs_size(self.proving_scheme, self.curve, circuit)
+    }
+
+    /// Fails with [`GnarkBackendError::SRSError`] if the proving scheme needs
+    /// an SRS for `circuit` and the backend has none, one over another curve
+    /// or one that is too small.
+    pub fn check_srs(&self, circuit: &Circuit) -> Result<(), GnarkBackendError> {
+        gnark_backend::check_srs(self.proving_scheme, self.curve, circuit, self.srs())
+    }
+
+    /// Lists the opcodes of `circuit` left unconstrained by the proving
+    /// scheme, that is the calls to black box functions it has no gadget for.
+    pub fn unconstrained_opcodes(
+        &self,
+        circuit: &Circuit,
+    ) -> Result<Vec<String>, GnarkBackendError> {
+        let report = gnark_backend::circuit_report(self.proving_scheme, self.curve, circuit)?;
+        Ok(report
+            .issues
+            .iter()
+            .map(|(index, issue)| format!("#{index} {}", issue.black_box_function()))
+            .collect())
+    }
+
+    /// In strict mode, fails with
+    /// [`GnarkBackendError::UnconstrainedOpcodesError`] listing every opcode
+    /// of `circuit` that the proving scheme would leave unconstrained.
+    pub fn check_circuit_supported(&self, circuit: &Circuit) -> Result<(), GnarkBackendError> {
+        if !self.strict {
+            return Ok(());
+        }
+        let unconstrained = self.unconstrained_opcodes(circuit)?;
+        if !unconstrained.is_empty() {
+            return Err(GnarkBackendError::UnconstrainedOpcodesError(unconstrained));
+        }
+        Ok(())
+    }
+}
+
+#[allow(
+    clippy::unwrap_used,
+    reason = "ProofSystemCompiler can not return errors, see the fallible API above"
+)]
 impl ProofSystemCompiler for Gnark {
     fn np_language(&self) -> Language {
-        Language::PLONKCSat { width: 3 }
+        match self.proving_scheme {
+            ProvingScheme::Plonk => Language::PLONKCSat { width: 3 },
+            ProvingScheme::Groth16 => Language::R1CS,
+        }
     }
 
+    // Only the functions the proving scheme constrains are supported, so
+    // that acvm falls back to arithmetic opcodes where it can.
     fn black_box_function_supported(&self, opcode: &BlackBoxFunc) -> bool {
-        match opcode {
-            BlackBoxFunc::AES => false,
-            BlackBoxFunc::AND => true,
-            BlackBoxFunc::XOR => true,
-            BlackBoxFunc::RANGE => true,
-            BlackBoxFunc::SHA256 => true,
-            BlackBoxFunc::Blake2s => true,
-            BlackBoxFunc::MerkleMembership => false,
-            BlackBoxFunc::SchnorrVerify => false,
-            BlackBoxFunc::Pedersen => false,
-            BlackBoxFunc::HashToField128Security => true,
-            BlackBoxFunc::EcdsaSecp256k1 => true,
-            BlackBoxFunc::FixedBaseScalarMul => false,
-            BlackBoxFunc::Keccak256 => false,
-        }
+        gnark_backend::black_box_function_support(self.proving_scheme, self.curve, opcode).unwrap()
+            == BlackBoxFunctionSupport::Constrained
     }
 
     fn prove_with_meta(
         &self,
         circuit: Circuit,
-        witness_values: std::collections::BTreeMap<Witness, FieldElement>,
+        witness_values: BTreeMap<Witness, FieldElement>,
     ) -> Vec<u8> {
-        // TODO: modify gnark serializer to accept the BTreeMap
-        let values = get_values_from_witness_tree(circuit.num_vars(), witness_values);
-        gnark_backend::prove_with_meta(circuit, values).unwrap()
+        self.try_prove_with_meta(circuit, witness_values).unwrap()
     }
 
     fn verify_from_cs(
@@ -71,26 +302,26 @@ impl ProofSystemCompiler for Gnark {
         public_inputs: Vec<FieldElement>,
         circuit: Circuit,
     ) -> bool {
-        gnark_backend::verify_with_meta(circuit, proof, &public_inputs).unwrap()
+        self.try_verify_from_cs(proof, public_inputs, circuit)
+            .unwrap()
     }
 
     fn get_exact_circuit_size(&self, circuit: &Circuit) -> u32 {
-        gnark_backend::get_exact_circuit_size(circuit).unwrap()
+        self.try_get_exact_circuit_size(circuit).unwrap()
     }
 
     fn preprocess(&self, circuit: &Circuit) -> (Vec<u8>, Vec<u8>) {
-        gnark_backend::preprocess(circuit).unwrap()
+        self.try_preprocess(circuit).unwrap()
     }
 
     fn prove_with_pk(
         &self,
         circuit: &Circuit,
-        witness_values: std::collections::BTreeMap<Witness, FieldElement>,
+        witness_values: BTreeMap<Witness, FieldElement>,
         proving_key: &[u8],
     ) -> Vec<u8> {
-        // TODO: modify gnark serializer to accept the BTreeMap
-        let values = get_values_from_witness_tree(circuit.num_vars(), witness_values);
-        gnark_backend::prove_with_pk(circuit, values, proving_key).unwrap()
+        self.try_prove_with_pk(circuit, witness_values, proving_key)
+            .unwrap()
     }
 
     fn verify_with_vk(
@@ -100,21 +331,66 @@ impl ProofSystemCompiler for Gnark {
         circuit: &Circuit,
         verification_key: &[u8],
     ) -> bool {
-        let public: Vec<FieldElement> =
-            get_values_from_witness_tree(circuit.num_vars(), public_inputs);
-        gnark_backend::verify_with_vk(circuit, proof, &public, verification_key).unwrap()
+        self.try_verify_with_vk(proof, public_inputs, circuit, verification_key)
+            .unwrap()
     }
 }
 
+// The bytes of the values of `inputs`, each one taking as many bytes as its
+// number of bits needs.
+fn input_bytes(
+    initial_witness: &BTreeMap<Witness, FieldElement>,
+    inputs: &[FunctionInput],
+    func_name: BlackBoxFunc,
+) -> Result<Vec<u8>, OpcodeResolutionError> {
+    let mut bytes = Vec::new();
+    for input in inputs {
+        let assignment = witness_to_value(initial_witness, input.witness)?;
+        let Some(num_bits) = usize::try_from(input.num_bits)
+            .ok()
+            .filter(|_| input.num_bits <= FieldElement::max_num_bits())
+        else {
+            return Err(OpcodeResolutionError::UnexpectedOpcode(
+                "inputs of at most the field's number of bits",
+                func_name,
+            ));
+        };
+        bytes.extend(assignment.fetch_nearest_bytes(num_bits));
+    }
+    Ok(bytes)
+}
+
 impl PartialWitnessGenerator for Gnark {
     fn solve_black_box_function_call(
         initial_witness: &mut BTreeMap<Witness, FieldElement>,
         func_call: &BlackBoxFuncCall,
     ) -> Result<(), OpcodeResolutionError> {
         match func_call.name {
-            BlackBoxFunc::AES => Err(OpcodeResolutionError::UnsupportedBlackBoxFunc(
-                func_call.name,
-            )),
+            BlackBoxFunc::AES => {
+                // The inputs are the bytes of the key followed by the ones of
+                // the plaintext, whose blocks are encrypted independently.
+                let bytes = input_bytes(initial_witness, &func_call.inputs, func_call.name)?;
+                let Some((key, plaintext)) =
+                    bytes.split_first_chunk::<16>().filter(|(_, plaintext)| {
+                        plaintext.len() % 16 == 0 && plaintext.len() == func_call.outputs.len()
+                    })
+                else {
+                    return Err(OpcodeResolutionError::IncorrectNumFunctionArguments(
+                        func_call.outputs.len() + 16,
+                        func_call.name,
+                        func_call.inputs.len(),
+                    ));
+                };
+
+                let (blocks, _) = plaintext.as_chunks::<16>();
+                let ciphertext = blocks
+                    .iter()
+                    .flat_map(|block| aes::aes128_encrypt(key, block));
+                for (output, byte) in func_call.outputs.iter().zip(ciphertext) {
+                    initial_witness.insert(*output, FieldElement::from(u128::from(byte)));
+                }
+                Ok(())
+            }
             BlackBoxFunc::AND | BlackBoxFunc::XOR => solve_logic_opcode(initial_witness, func_call),
             BlackBoxFunc::RANGE => solve_range_opcode(initial_witness, func_call),
             BlackBoxFunc::SHA256 => {
@@ -125,46 +401,96 @@ impl PartialWitnessGenerator for Gnark {
                 blake2s(initial_witness, func_call);
                 Ok(())
             }
+            // The inner nodes are Pedersen hashes, see below.
             BlackBoxFunc::MerkleMembership => Err(OpcodeResolutionError::UnsupportedBlackBoxFunc(
                 func_call.name,
             )),
+            // The challenge is a Pedersen hash, see below.
             BlackBoxFunc::SchnorrVerify => Err(OpcodeResolutionError::UnsupportedBlackBoxFunc(
                 func_call.name,
             )),
+            // The generators and the hash of Noir's reference backend are not
+            // ported, and solving with other ones would not match the
+            // circuits compiled for it.
             BlackBoxFunc::Pedersen => Err(OpcodeResolutionError::UnsupportedBlackBoxFunc(
                 func_call.name,
             )),
             BlackBoxFunc::HashToField128Security => {
                 // Deal with Blake2s -- XXX: It's not possible for pwg to know that it is Blake2s
                 // We need to get this method from the backend
-                let mut hasher = <blake2::Blake2s as blake2::Digest>::new();
-
-                // 0. For each input in the vector of inputs, check if we have their witness assignments (Can do this outside of match, since they all have inputs)
-                for input_index in func_call.inputs.iter() {
-                    let witness = &input_index.witness;
-                    let num_bits = input_index.num_bits;
+                let [output] = func_call.outputs.as_slice() else {
+                    return Err(OpcodeResolutionError::IncorrectNumFunctionArguments(
+                        1,
+                        func_call.name,
+                        func_call.outputs.len(),
+                    ));
+                };
+                let bytes = input_bytes(initial_witness, &func_call.inputs, func_call.name)?;
+                let result = <blake2::Blake2s as blake2::Digest>::digest(&bytes);
 
-                    let assignment = witness_to_value(initial_witness, *witness)?;
+                let reduced_res = FieldElement::from_be_bytes_reduce(&result);
+                initial_witness.insert(*output, reduced_res);
+                Ok(())
+            }
+            BlackBoxFunc::EcdsaSecp256k1 => {
+                // The inputs are the bytes of the coordinates of the public
+                // key, of the signature and of the hashed message.
+                let bytes = input_bytes(initial_witness, &func_call.inputs, func_call.name)?;
+                let split = || {
+                    let (public_key_x, rest) = bytes.split_first_chunk::<32>()?;
+                    let (public_key_y, rest) = rest.split_first_chunk::<32>()?;
+                    let (signature, hashed_message) = rest.split_first_chunk::<64>()?;
+                    let hashed_message = <&[u8; 32]>::try_from(hashed_message).ok()?;
+                    Some((public_key_x, public_key_y, signature, hashed_message))
+                };
+                let (Some((public_key_x, public_key_y, signature, hashed_message)), [output]) =
+                    (split(), func_call.outputs.as_slice())
+                else {
+                    return Err(OpcodeResolutionError::IncorrectNumFunctionArguments(
+                        160,
+                        func_call.name,
+                        func_call.inputs.len(),
+                    ));
+                };
 
-                    let bytes = assignment.fetch_nearest_bytes(num_bits.try_into().unwrap());
+                let verified =
+                    ecdsa::verify_prehashed(public_key_x, public_key_y, signature, hashed_message);
+                initial_witness.insert(*output, FieldElement::from(u128::from(verified)));
+                Ok(())
+            }
+            BlackBoxFunc::FixedBaseScalarMul => {
+                let ([scalar], [x_witness, y_witness]) =
+                    (func_call.inputs.as_slice(), func_call.outputs.as_slice())
+                else {
+                    return Err(OpcodeResolutionError::IncorrectNumFunctionArguments(
+                        1,
+                        func_call.name,
+                        func_call.inputs.len(),
+                    ));
+                };
+                let scalar = witness_to_value(initial_witness, scalar.witness)?;
 
-                    blake2::Digest::update(&mut hasher, bytes);
+                let (x, y) = grumpkin::Point::one().mul(*scalar).coordinates();
+                initial_witness.insert(*x_witness, x);
+                initial_witness.insert(*y_witness, y);
+                Ok(())
+            }
+            BlackBoxFunc::Keccak256 => {
+                if func_call.outputs.len() != 32 {
+                    return Err(OpcodeResolutionError::IncorrectNumFunctionArguments(
+                        32,
+                        func_call.name,
+                        func_call.outputs.len(),
+                    ));
                 }
-                let result = blake2::Digest::finalize(hasher);
-
-                let reduced_res = FieldElement::from_be_bytes_reduce(&result);
-                assert_eq!(func_call.outputs.len(), 1);
+                let message = input_bytes(initial_witness, &func_call.inputs, func_call.name)?;
 
-                initial_witness.insert(func_call.outputs[0], reduced_res);
+                let digest = keccak::keccak256(&message);
+                for (output, byte) in func_call.outputs.iter().zip(digest) {
+                    initial_witness.insert(*output, FieldElement::from(u128::from(byte)));
+                }
                 Ok(())
             }
-            BlackBoxFunc::EcdsaSecp256k1 => secp256k1_prehashed(initial_witness, func_call),
-            BlackBoxFunc::FixedBaseScalarMul => Err(
-                OpcodeResolutionError::UnsupportedBlackBoxFunc(func_call.name),
-            ),
-            BlackBoxFunc::Keccak256 => Err(OpcodeResolutionError::UnsupportedBlackBoxFunc(
-                func_call.name,
-            )),
         }
     }
 }
@@ -189,3 +515,608 @@ impl SmartContract for Gnark {
         unimplemented!("gnark does not implement an ETH contract")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use acvm::acir::circuit::Opcode;
+
+    // Solves a call to a hash function on the bytes of `message` and returns
+    // the values of its outputs.
+    fn solve_outputs(name: BlackBoxFunc, message: &[u8], num_outputs: u32) -> Vec<FieldElement> {
+        let message_len: u32 = message.len().try_into().unwrap();
+        let mut initial_witness = BTreeMap::new();
+        let mut inputs = Vec::new();
+        for (index, byte) in (1..).zip(message) {
+            initial_witness.insert(Witness(index), FieldElement::from(u128::from(*byte)));
+            inputs.push(FunctionInput {
+                witness: Witness(index),
+                num_bits: 8,
+            });
+        }
+        let outputs: Vec<Witness> = (1..=num_outputs)
+            .map(|index| Witness(message_len + index))
+            .collect();
+        let func_call = BlackBoxFuncCall {
+            name,
+            inputs,
+            outputs: outputs.clone(),
+        };
+
+        Gnark::solve_black_box_function_call(&mut initial_witness, &func_call).unwrap();
+
+        outputs
+            .iter()
+            .map(|output| *initial_witness.get(output).unwrap())
+            .collect()
+    }
+
+    // Solves a call to a hash function on the bytes of `message` and returns
+    // the bytes of the digest.
+    fn solve_hash(name: BlackBoxFunc, message: &[u8], digest_len: u32) -> Vec<u8> {
+        solve_outputs(name, message, digest_len)
+            .iter()
+            .map(|output| u8::try_from(output.to_u128()).unwrap())
+            .collect()
+    }
+
+    // The Go gadgets are tested against the same vectors, see
+    // gnark_backend_ffi/backend/plonk/sha256_test.go.
+    #[test]
+    fn test_solve_sha256() {
+        let test_vectors = [
+            (
+                "",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                "abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ];
+
+        for (message, digest) in test_vectors {
+            let solved_digest = solve_hash(BlackBoxFunc::SHA256, message.as_bytes(), 32);
+            assert_eq!(hex::encode(solved_digest), digest, "{message:?}");
+        }
+    }
+
+    // The Go gadgets are tested against the same vectors, see
+    // gnark_backend_ffi/backend/plonk/blake2s_test.go.
+    const BLAKE2S_TEST_VECTORS: [(&[u8], &str, &str); 2] = [
+        (
+            b"",
+            "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9",
+            "0858dd4ab72d4041707096633f3299c1ceede5b7392ec3fb936122d53ed0eef7",
+        ),
+        (
+            b"abc",
+            "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982",
+            "20281019514a74b92956e5eccd69ecd20f11a2d8251cc99809b7a5b896675981",
+        ),
+    ];
+
+    #[test]
+    fn test_solve_blake2s() {
+        for (message, digest, _) in BLAKE2S_TEST_VECTORS {
+            let solved_digest = solve_hash(BlackBoxFunc::Blake2s, message, 32);
+            assert_eq!(hex::encode(solved_digest), digest, "{message:?}");
+        }
+    }
+
+    #[test]
+    fn test_solve_hash_to_field_128_security() {
+        for (message, _, field) in BLAKE2S_TEST_VECTORS {
+            let solved = solve_outputs(BlackBoxFunc::HashToField128Security, message, 1);
+            assert_eq!(
+                solved,
+                vec![FieldElement::from_hex(field).unwrap()],
+                "{message:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_solve_hash_to_field_128_security_fails_without_one_output() {
+        let func_call = BlackBoxFuncCall {
+            name: BlackBoxFunc::HashToField128Security,
+            inputs: vec![],
+            outputs: vec![Witness(1), Witness(2)],
+        };
+
+        assert!(matches!(
+            Gnark::solve_black_box_function_call(&mut BTreeMap::new(), &func_call),
+            Err(OpcodeResolutionError::IncorrectNumFunctionArguments(
+                1,
+                _,
+                2
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_solve_fails_on_inputs_wider_than_the_field() {
+        let mut initial_witness = BTreeMap::from([(Witness(1), FieldElement::one())]);
+        let func_call = BlackBoxFuncCall {
+            name: BlackBoxFunc::Keccak256,
+            inputs: vec![FunctionInput {
+                witness: Witness(1),
+                num_bits: 512,
+            }],
+            outputs: (2..34).map(Witness).collect(),
+        };
+
+        assert!(matches!(
+            Gnark::solve_black_box_function_call(&mut initial_witness, &func_call),
+            Err(OpcodeResolutionError::UnexpectedOpcode(
+                _,
+                BlackBoxFunc::Keccak256
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_solve_keccak256() {
+        let solved_digest = solve_hash(BlackBoxFunc::Keccak256, b"abc", 32);
+        assert_eq!(
+            hex::encode(solved_digest),
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+        );
+    }
+
+    #[test]
+    fn test_solve_keccak256_fails_without_32_outputs() {
+        for num_outputs in [31_usize, 33] {
+            let mut initial_witness = BTreeMap::from([(Witness(1), FieldElement::from(0x61_u128))]);
+            let func_call = BlackBoxFuncCall {
+                name: BlackBoxFunc::Keccak256,
+                inputs: vec![FunctionInput {
+                    witness: Witness(1),
+                    num_bits: 8,
+                }],
+                outputs: (2..).take(num_outputs).map(Witness).collect(),
+            };
+
+            assert!(matches!(
+                Gnark::solve_black_box_function_call(&mut initial_witness, &func_call),
+                Err(OpcodeResolutionError::IncorrectNumFunctionArguments(
+                    32,
+                    BlackBoxFunc::Keccak256,
+                    actual
+                )) if actual == num_outputs
+            ));
+            assert_eq!(initial_witness.len(), 1);
+        }
+    }
+
+    // FIPS-197, appendices B and C.1, encrypted as two blocks with the same
+    // key. The Go gadget is tested against the same vectors, see
+    // gnark_backend_ffi/backend/plonk/aes_test.go.
+    #[test]
+    fn test_solve_aes() {
+        let key_and_plaintext = hex_literal::hex!(
+            "000102030405060708090a0b0c0d0e0f"
+            "00112233445566778899aabbccddeeff"
+            "00112233445566778899aabbccddeeff"
+        );
+        let solved_ciphertext = solve_hash(BlackBoxFunc::AES, &key_and_plaintext, 32);
+        assert_eq!(
+            hex::encode(solved_ciphertext),
+            "69c4e0d86a7b0430d8cdb78070b4c55a69c4e0d86a7b0430d8cdb78070b4c55a"
+        );
+
+        let key_and_plaintext = hex_literal::hex!(
+            "2b7e151628aed2a6abf7158809cf4f3c"
+            "3243f6a8885a308d313198a2e0370734"
+        );
+        let solved_ciphertext = solve_hash(BlackBoxFunc::AES, &key_and_plaintext, 16);
+        assert_eq!(
+            hex::encode(solved_ciphertext),
+            "3925841d02dc09fbdc118597196a0b32"
+        );
+    }
+
+    #[test]
+    fn test_solve_aes_fails_on_partial_blocks() {
+        let mut initial_witness = BTreeMap::new();
+        let mut inputs = Vec::new();
+        for index in 1..=20 {
+            initial_witness.insert(Witness(index), FieldElement::zero());
+            inputs.push(FunctionInput {
+                witness: Witness(index),
+                num_bits: 8,
+            });
+        }
+        let func_call = BlackBoxFuncCall {
+            name: BlackBoxFunc::AES,
+            inputs,
+            outputs: (21..25).map(Witness).collect(),
+        };
+
+        assert!(Gnark::solve_black_box_function_call(&mut initial_witness, &func_call).is_err());
+    }
+
+    #[test]
+    fn test_solve_aes_fails_without_a_key() {
+        let func_call = BlackBoxFuncCall {
+            name: BlackBoxFunc::AES,
+            inputs: vec![],
+            outputs: vec![],
+        };
+
+        assert!(Gnark::solve_black_box_function_call(&mut BTreeMap::new(), &func_call).is_err());
+    }
+
+    #[test]
+    fn test_solve_pedersen_is_unsupported() {
+        let func_call = BlackBoxFuncCall {
+            name: BlackBoxFunc::Pedersen,
+            inputs: vec![],
+            outputs: vec![Witness(1), Witness(2)],
+        };
+
+        let result = Gnark::solve_black_box_function_call(&mut BTreeMap::new(), &func_call);
+
+        assert!(matches!(
+            result,
+            Err(OpcodeResolutionError::UnsupportedBlackBoxFunc(
+                BlackBoxFunc::Pedersen
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_solve_merkle_membership_is_unsupported() {
+        let func_call = BlackBoxFuncCall {
+            name: BlackBoxFunc::MerkleMembership,
+            inputs: vec![],
+            outputs: vec![Witness(1)],
+        };
+
+        let result = Gnark::solve_black_box_function_call(&mut BTreeMap::new(), &func_call);
+
+        assert!(matches!(
+            result,
+            Err(OpcodeResolutionError::UnsupportedBlackBoxFunc(
+                BlackBoxFunc::MerkleMembership
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_solve_schnorr_verify_is_unsupported() {
+        let func_call = BlackBoxFuncCall {
+            name: BlackBoxFunc::SchnorrVerify,
+            inputs: vec![],
+            outputs: vec![Witness(1)],
+        };
+
+        let result = Gnark::solve_black_box_function_call(&mut BTreeMap::new(), &func_call);
+
+        assert!(matches!(
+            result,
+            Err(OpcodeResolutionError::UnsupportedBlackBoxFunc(
+                BlackBoxFunc::SchnorrVerify
+            ))
+        ));
+    }
+
+    // The Go gadget is tested against the same vectors, see
+    // gnark_backend_ffi/backend/plonk/fixed_base_scalar_mul_test.go.
+    #[test]
+    fn test_solve_fixed_base_scalar_mul() {
+        let test_vectors = [
+            (
+                FieldElement::one(),
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "0000000000000002cf135e7506a45d632d270d45f1181294833fc48d823f272c",
+            ),
+            (
+                FieldElement::from(2_u128),
+                "06ce1b0827aafa85ddeb49cdaa36306d19a74caa311e13d46d8bc688cdbffffe",
+                "1c122f81a3a14964909ede0ba2a6855fc93faf6fa1a788bf467be7e7a43f80ac",
+            ),
+            (
+                FieldElement::from(42_u128),
+                "1043dd984b697ba6c4861b6e33bd8bad627385a2598c7f669879663d8b521add",
+                "127804e7e5bfe71f1bcee05d237daa28340c90e1bd439dc978fe2b84cc127d8b",
+            ),
+            (
+                -FieldElement::one(),
+                "2b9d4f8d4925e90f1e8b70d68e9cbe55b2f19d785cd2def25827f1d638cdb59d",
+                "0cd340c3cdf8f2bed9c2298fea4719f023e3c98962d9185e4d4f92e6ca0987a3",
+            ),
+            (
+                FieldElement::zero(),
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+            ),
+        ];
+
+        for (scalar, x, y) in test_vectors {
+            let mut initial_witness = BTreeMap::from([(Witness(1), scalar)]);
+            let func_call = BlackBoxFuncCall {
+                name: BlackBoxFunc::FixedBaseScalarMul,
+                inputs: vec![FunctionInput {
+                    witness: Witness(1),
+                    num_bits: FieldElement::max_num_bits(),
+                }],
+                outputs: vec![Witness(2), Witness(3)],
+            };
+
+            Gnark::solve_black_box_function_call(&mut initial_witness, &func_call).unwrap();
+
+            let output = |index| initial_witness.get(&Witness(index)).unwrap().to_hex();
+            assert_eq!((output(2), output(3)), (x.to_owned(), y.to_owned()));
+        }
+    }
+
+    fn aes_circuit() -> Circuit {
+        Circuit {
+            opcodes: vec![
+                Opcode::Arithmetic(acvm::acir::native_types::Expression::default()),
+                Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+                    name: BlackBoxFunc::AES,
+                    inputs: vec![],
+                    outputs: vec![],
+                }),
+            ],
+            ..Circuit::default()
+        }
+    }
+
+    // Unconstrained opcodes are only rejected in strict mode.
+    #[test]
+    fn test_check_circuit_supported_accepts_black_box_functions() {
+        assert!(!Gnark::default().is_strict());
+        assert!(Gnark::default()
+            .check_circuit_supported(&aes_circuit())
+            .is_ok());
+    }
+
+    #[test]
+    fn test_check_circuit_supported_accepts_arithmetic_circuits() {
+        let circuit = Circuit {
+            opcodes: vec![Opcode::Arithmetic(
+                acvm::acir::native_types::Expression::default(),
+            )],
+            ..Circuit::default()
+        };
+
+        assert!(Gnark::default().check_circuit_supported(&circuit).is_ok());
+        for proving_scheme in [ProvingScheme::Plonk, ProvingScheme::Groth16] {
+            let gnark = Gnark::new(proving_scheme).strict();
+            assert!(gnark.check_circuit_supported(&circuit).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_plonk_needs_a_large_enough_srs() {
+        let circuit = Circuit {
+            opcodes: vec![Opcode::Arithmetic(
+                acvm::acir::native_types::Expression::default(),
+            )],
+            ..Circuit::default()
+        };
+
+        let gnark = Gnark::new(ProvingScheme::Plonk);
+        assert!(matches!(
+            gnark.try_preprocess(&circuit),
+            Err(GnarkBackendError::SRSError(_))
+        ));
+        let required_size = gnark.try_required_srs_size(&circuit).unwrap().unwrap();
+
+        let srs = Srs::insecure_test_srs(CurveId::DEFAULT, required_size - 1).unwrap();
+        assert!(gnark.clone().with_srs(srs).check_srs(&circuit).is_err());
+
+        let srs = Srs::insecure_test_srs(CurveId::DEFAULT, required_size).unwrap();
+        let gnark = gnark.with_srs(srs.clone());
+        assert_eq!(gnark.srs(), Some(&srs));
+        assert!(gnark.check_srs(&circuit).is_ok());
+
+        let groth16 = Gnark::new(ProvingScheme::Groth16);
+        assert_eq!(groth16.try_required_srs_size(&circuit).unwrap(), None);
+        assert!(groth16.check_srs(&circuit).is_ok());
+    }
+
+    // x * x = y with y public, over the scalar field of every curve.
+    #[test]
+    fn test_prove_and_verify_over_every_curve() {
+        use acvm::acir::circuit::PublicInputs;
+        use acvm::acir::native_types::Expression;
+        use std::collections::BTreeSet;
+
+        let circuit = Circuit {
+            current_witness_index: 2,
+            opcodes: vec![Opcode::Arithmetic(Expression {
+                mul_terms: vec![(FieldElement::one(), Witness(1), Witness(1))],
+                linear_combinations: vec![(-FieldElement::one(), Witness(2))],
+                q_c: FieldElement::zero(),
+            })],
+            public_inputs: PublicInputs(BTreeSet::from([Witness(2)])),
+        };
+        // A negative witness, which must stay negative in the other fields.
+        let x = -FieldElement::from(3_u128);
+        let witness_values = BTreeMap::from([(Witness(1), x), (Witness(2), x * x)]);
+
+        let curves = [
+            CurveId::Bn254,
+            CurveId::Bls12_381,
+            CurveId::Bls12_377,
+            CurveId::Bw6_761,
+        ];
+        for (proving_scheme, curve) in [ProvingScheme::Plonk, ProvingScheme::Groth16]
+            .into_iter()
+            .flat_map(|proving_scheme| curves.map(|curve| (proving_scheme, curve)))
+        {
+            let mut gnark = Gnark::new(proving_scheme).with_curve(curve);
+            if let Some(size) = gnark.try_required_srs_size(&circuit).unwrap() {
+                gnark = gnark.with_srs(Srs::insecure_test_srs(curve, size).unwrap());
+            }
+
+            let (proving_key, verifying_key) = gnark.try_preprocess(&circuit).unwrap();
+            let proof = gnark
+                .try_prove_with_pk(&circuit, witness_values.clone(), &proving_key)
+                .unwrap();
+            let public_inputs = BTreeMap::from([(Witness(2), x * x)]);
+            assert!(gnark
+                .try_verify_with_vk(&proof, public_inputs, &circuit, &verifying_key)
+                .unwrap());
+        }
+    }
+
+    #[test]
+    fn test_gnark_configuration() {
+        let gnark = Gnark::new(ProvingScheme::Groth16);
+        assert_eq!(gnark.proving_scheme(), ProvingScheme::Groth16);
+        assert!(!gnark.is_strict());
+
+        let gnark = gnark.strict();
+        assert_eq!(gnark.proving_scheme(), ProvingScheme::Groth16);
+        assert!(gnark.is_strict());
+
+        let gnark = gnark.with_curve(CurveId::Bw6_761);
+        assert_eq!(gnark.curve(), CurveId::Bw6_761);
+        assert_eq!(gnark.proving_scheme(), ProvingScheme::Groth16);
+        assert!(gnark.is_strict());
+
+        assert_eq!(Gnark::default().curve(), CurveId::default());
+        assert_eq!(
+            Gnark::default().proving_scheme(),
+            if cfg!(feature = "groth16") {
+                ProvingScheme::Groth16
+            } else {
+                ProvingScheme::Plonk
+            }
+        );
+    }
+
+    #[test]
+    fn test_default_backend_is_usable_in_constants() {
+        const BACKEND: Gnark = Gnark::DEFAULT;
+
+        assert_eq!(BACKEND.proving_scheme(), Gnark::default().proving_scheme());
+        assert_eq!(BACKEND.curve(), Gnark::default().curve());
+        assert!(!BACKEND.is_strict());
+        assert!(BACKEND.srs().is_none());
+    }
+
+    #[test]
+    fn test_np_language_matches_the_proving_scheme() {
+        assert!(matches!(
+            Gnark::new(ProvingScheme::Plonk).np_language(),
+            Language::PLONKCSat { width: 3 }
+        ));
+        assert!(matches!(
+            Gnark::new(ProvingScheme::Groth16).np_language(),
+            Language::R1CS
+        ));
+    }
+
+    // The PLONK backend has a gadget for every black box function while the
+    // Groth16 one only keeps the arithmetic opcodes.
+    #[test]
+    fn test_black_box_function_supported_matches_the_proving_scheme() {
+        for func in [
+            BlackBoxFunc::AES,
+            BlackBoxFunc::RANGE,
+            BlackBoxFunc::SHA256,
+            BlackBoxFunc::EcdsaSecp256k1,
+            BlackBoxFunc::Keccak256,
+        ] {
+            assert!(Gnark::new(ProvingScheme::Plonk).black_box_function_supported(&func));
+            assert!(!Gnark::new(ProvingScheme::Groth16).black_box_function_supported(&func));
+        }
+    }
+
+    #[test]
+    fn test_strict_mode_lists_every_unconstrained_opcode() {
+        let mut circuit = aes_circuit();
+        circuit
+            .opcodes
+            .push(Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+                name: BlackBoxFunc::SHA256,
+                inputs: vec![],
+                outputs: vec![],
+            }));
+
+        let plonk = Gnark::new(ProvingScheme::Plonk).strict();
+        assert!(plonk.check_circuit_supported(&circuit).is_ok());
+
+        let groth16 = Gnark::new(ProvingScheme::Groth16).strict();
+        assert!(matches!(
+            groth16.check_circuit_supported(&circuit),
+            Err(GnarkBackendError::UnconstrainedOpcodesError(opcodes))
+                if opcodes == ["#1 AES", "#2 SHA256"]
+        ));
+    }
+
+    fn solve_ecdsa_secp256k1(bytes: &[u8]) -> Result<FieldElement, OpcodeResolutionError> {
+        let mut initial_witness = BTreeMap::new();
+        let mut inputs = Vec::new();
+        for (index, byte) in (1..).zip(bytes) {
+            initial_witness.insert(Witness(index), FieldElement::from(u128::from(*byte)));
+            inputs.push(FunctionInput {
+                witness: Witness(index),
+                num_bits: 8,
+            });
+        }
+        let output = Witness(1000);
+        let func_call = BlackBoxFuncCall {
+            name: BlackBoxFunc::EcdsaSecp256k1,
+            inputs,
+            outputs: vec![output],
+        };
+
+        Gnark::solve_black_box_function_call(&mut initial_witness, &func_call)?;
+
+        Ok(*initial_witness.get(&output).unwrap())
+    }
+
+    // See the vector of src/backend/ecdsa.rs, the message being "hello".
+    const ECDSA_SECP256K1_INPUTS: [u8; 160] = hex_literal::hex!(
+        "c2dd8b4d1e91daadcf305153a4cb4caf471f2cfe0c49bad5f912e6330a7fac09"
+        "c06bb3a4a5745826bc149fe131e41092f7fd367fa5e497311a38c150136c61bf"
+        "4f064b69736960bad78e428f71f8276a63dda86a573eae887ffd48a985afc967"
+        "2e447ec0526c8f9eb8165f911efa8386d545de4efa02d56e44af5826d9410846"
+        "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+    );
+
+    #[test]
+    fn test_solve_ecdsa_secp256k1() {
+        type Patch = fn(&mut [u8; 160]);
+        let solve = |patch: Patch| {
+            let mut inputs = ECDSA_SECP256K1_INPUTS;
+            patch(&mut inputs);
+            solve_ecdsa_secp256k1(&inputs).unwrap()
+        };
+        assert_eq!(solve(|_| ()), FieldElement::one());
+
+        let rejected: [(&str, Patch); 6] = [
+            ("tampered message", |inputs| inputs[159] ^= 1),
+            ("tampered signature", |inputs| inputs[100] ^= 1),
+            // acvm would accept it, as only the parity of y is the same.
+            ("public key off the curve", |inputs| inputs[63] ^= 2),
+            ("r = 0", |inputs| inputs[64..96].fill(0)),
+            ("s >= n", |inputs| inputs[96..128].fill(0xff)),
+            // n - s is a valid signature which isn't normalized.
+            ("high s", |inputs| {
+                inputs[96..128].copy_from_slice(&hex_literal::hex!(
+                    "d1bb813fad93706147e9a06ee1057c77e568fe97b545cacd7b230665f6f538fb"
+                ))
+            }),
+        ];
+        for (name, patch) in rejected {
+            assert_eq!(solve(patch), FieldElement::zero(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_solve_ecdsa_secp256k1_fails_without_a_32_bytes_hash() {
+        assert!(solve_ecdsa_secp256k1(&ECDSA_SECP256K1_INPUTS[..159]).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+// AES-128 encryption of 16 bytes blocks, as specified by FIPS-197. acvm 0.5
+// doesn't solve it, so the backend does.
+
+// The state and the round keys are held by columns of 4 bytes: byte i of a
+// block is in column i / 4 and row i % 4.
+type Block = [[u8; 4]; 4];
+
+const ROUNDS: usize = 10;
+
+const SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+pub fn aes128_encrypt(key: &[u8; 16], plaintext: &[u8; 16]) -> [u8; 16] {
+    let round_keys = expand_key(key);
+    let mut round_keys = round_keys.iter();
+    let mut state = to_block(plaintext);
+    if let Some(round_key) = round_keys.next() {
+        state = add_round_key(state, round_key);
+    }
+    for (round, round_key) in (1..=ROUNDS).zip(round_keys) {
+        state = shift_rows(state.map(sub_word));
+        if round < ROUNDS {
+            state = state.map(mix_column);
+        }
+        state = add_round_key(state, round_key);
+    }
+
+    let mut ciphertext = [0_u8; 16];
+    for (bytes, column) in ciphertext.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&column);
+    }
+    ciphertext
+}
+
+fn to_block(bytes: &[u8; 16]) -> Block {
+    let mut block = Block::default();
+    for (column, chunk) in block.iter_mut().zip(bytes.chunks_exact(4)) {
+        column.copy_from_slice(chunk);
+    }
+    block
+}
+
+// The round keys: the first one is the key, and every column of the next
+// ones is the XOR of the previous column and of the same column of the
+// previous round key, the first column of a round key XORing the previous
+// one rotated, substituted and XORed with the round constant.
+fn expand_key(key: &[u8; 16]) -> Vec<Block> {
+    let mut round_keys = vec![to_block(key)];
+    let mut round_constant = 1_u8;
+    for _ in 0..ROUNDS {
+        let Some(&[c0, c1, c2, c3]) = round_keys.last() else {
+            unreachable!("there is at least the key")
+        };
+        let [a, b, c, d] = c3;
+        let mut previous = sub_word([b, c, d, a]);
+        previous[0] ^= round_constant;
+        let mut round_key = Block::default();
+        for (column, previous_round_column) in round_key.iter_mut().zip([c0, c1, c2, c3]) {
+            previous = xor_words(previous, previous_round_column);
+            *column = previous;
+        }
+        round_keys.push(round_key);
+        round_constant = xtime(round_constant);
+    }
+    round_keys
+}
+
+fn sub_word(word: [u8; 4]) -> [u8; 4] {
+    word.map(|byte| SBOX.get(usize::from(byte)).copied().unwrap_or_default())
+}
+
+// Row r is rotated left by r bytes.
+fn shift_rows(state: Block) -> Block {
+    let mut shifted = Block::default();
+    for (c, column) in shifted.iter_mut().enumerate() {
+        for (r, byte) in column.iter_mut().enumerate() {
+            if let Some(source) = state.get((c + r) % 4).and_then(|source| source.get(r)) {
+                *byte = *source;
+            }
+        }
+    }
+    shifted
+}
+
+// The column is multiplied by the polynomial 3⋅x³ + x² + x + 2 over GF(2⁸).
+fn mix_column([a0, a1, a2, a3]: [u8; 4]) -> [u8; 4] {
+    let all = a0 ^ a1 ^ a2 ^ a3;
+    [
+        a0 ^ all ^ xtime(a0 ^ a1),
+        a1 ^ all ^ xtime(a1 ^ a2),
+        a2 ^ all ^ xtime(a2 ^ a3),
+        a3 ^ all ^ xtime(a3 ^ a0),
+    ]
+}
+
+// Multiplication by x in GF(2⁸) = GF(2)[x] / (x⁸ + x⁴ + x³ + x + 1).
+fn xtime(byte: u8) -> u8 {
+    (byte << 1) ^ if byte & 0x80 == 0 { 0 } else { 0x1b }
+}
+
+fn add_round_key(state: Block, round_key: &Block) -> Block {
+    let mut result = state;
+    for (column, key_column) in result.iter_mut().zip(round_key) {
+        *column = xor_words(*column, *key_column);
+    }
+    result
+}
+
+fn xor_words(a: [u8; 4], b: [u8; 4]) -> [u8; 4] {
+    let mut result = a;
+    for (x, y) in result.iter_mut().zip(b) {
+        *x ^= y;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // FIPS-197, appendices B and C.1. The Go gadget is tested against the
+    // same vectors, see gnark_backend_ffi/backend/plonk/aes_test.go.
+    #[test]
+    fn test_aes128_encrypt() {
+        let test_vectors = [
+            (
+                hex_literal::hex!("2b7e151628aed2a6abf7158809cf4f3c"),
+                hex_literal::hex!("3243f6a8885a308d313198a2e0370734"),
+                "3925841d02dc09fbdc118597196a0b32",
+            ),
+            (
+                hex_literal::hex!("000102030405060708090a0b0c0d0e0f"),
+                hex_literal::hex!("00112233445566778899aabbccddeeff"),
+                "69c4e0d86a7b0430d8cdb78070b4c55a",
+            ),
+        ];
+
+        for (key, plaintext, ciphertext) in test_vectors {
+            assert_eq!(hex::encode(aes128_encrypt(&key, &plaintext)), ciphertext);
+        }
+    }
+
+    // FIPS-197, appendix A.1.
+    #[test]
+    fn test_expand_key() {
+        let round_keys = expand_key(&hex_literal::hex!("2b7e151628aed2a6abf7158809cf4f3c"));
+        let last = round_keys.last().unwrap().concat();
+        assert_eq!(round_keys.len(), 11);
+        assert_eq!(hex::encode(last), "d014f9a8c9ee2589e13f0cc8b6630ca6");
+    }
+}
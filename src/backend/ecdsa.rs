@@ -0,0 +1,119 @@
+// ECDSA signatures on secp256k1 of a message hashed beforehand. acvm 0.5
+// solves them by decompressing the public key from x and the parity of y and
+// panics on malformed signatures, whereas the gadget of
+// gnark_backend_ffi/backend/plonk/ecdsa.go checks that (x, y) is on the curve
+// and rejects malformed inputs, so the backend solves them the same way.
+
+use k256::ecdsa::Signature;
+use k256::elliptic_curve::sec1::{Coordinates, ToEncodedPoint};
+use k256::{AffinePoint, EncodedPoint, ProjectivePoint, PublicKey, Scalar};
+
+// The signature is (r, s), two 32 bytes big endian integers. It is valid if
+// 0 < r < n and 0 < s <= n / 2, as in BIP 62, and if the abscissa of
+// (z⋅G + r⋅P) / s is r modulo n, where z is the hashed message reduced modulo
+// n.
+pub fn verify_prehashed(
+    public_key_x: &[u8; 32],
+    public_key_y: &[u8; 32],
+    signature: &[u8; 64],
+    hashed_message: &[u8; 32],
+) -> bool {
+    let point = EncodedPoint::from_affine_coordinates(
+        &(*public_key_x).into(),
+        &(*public_key_y).into(),
+        false,
+    );
+    let (Ok(public_key), Ok(signature)) = (
+        PublicKey::try_from(point),
+        Signature::try_from(signature.as_slice()),
+    ) else {
+        return false;
+    };
+    let (r, s) = (signature.r(), signature.s());
+    if bool::from(s.is_high()) {
+        return false;
+    }
+    let Some(s_inverse) = Option::<Scalar>::from(s.invert()) else {
+        return false;
+    };
+
+    let z = Scalar::from_bytes_reduced(&(*hashed_message).into());
+    let big_r: AffinePoint = (ProjectivePoint::generator() * (z * s_inverse)
+        + ProjectivePoint::from(*public_key.as_affine()) * (*r * s_inverse))
+        .to_affine();
+
+    match big_r.to_encoded_point(false).coordinates() {
+        Coordinates::Uncompressed { x, .. } => Scalar::from_bytes_reduced(x) == *r,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The Go gadget is tested against the same vector, see
+    // gnark_backend_ffi/backend/plonk/ecdsa_test.go.
+    const PUBLIC_KEY_X: [u8; 32] =
+        hex_literal::hex!("c2dd8b4d1e91daadcf305153a4cb4caf471f2cfe0c49bad5f912e6330a7fac09");
+    const PUBLIC_KEY_Y: [u8; 32] =
+        hex_literal::hex!("c06bb3a4a5745826bc149fe131e41092f7fd367fa5e497311a38c150136c61bf");
+    const SIGNATURE: [u8; 64] = hex_literal::hex!(
+        "4f064b69736960bad78e428f71f8276a63dda86a573eae887ffd48a985afc967"
+        "2e447ec0526c8f9eb8165f911efa8386d545de4efa02d56e44af5826d9410846"
+    );
+    // SHA-256("hello").
+    const HASHED_MESSAGE: [u8; 32] =
+        hex_literal::hex!("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824");
+
+    #[test]
+    fn test_verify_prehashed() {
+        assert!(verify_prehashed(
+            &PUBLIC_KEY_X,
+            &PUBLIC_KEY_Y,
+            &SIGNATURE,
+            &HASHED_MESSAGE
+        ));
+
+        // Signed by the private keys 1 and n - 1, the public keys being G and
+        // -G, see gnark_backend_ffi/backend/plonk/ecdsa_test.go.
+        let generator_x =
+            hex_literal::hex!("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798");
+        for (public_key_y, signature) in [
+            (
+                hex_literal::hex!(
+                    "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"
+                ),
+                hex_literal::hex!(
+                    "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+                    "594f4bc6a672a1458377623f6bbf12599cfcc1af61d33503f2dba9cf25b29185"
+                ),
+            ),
+            (
+                hex_literal::hex!(
+                    "b7c52588d95c3b9aa25b0403f1eef75702e84bb7597aabe663b82f6f04ef2777"
+                ),
+                hex_literal::hex!(
+                    "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+                    "4ccc18c49a2c189e2eb8276b08cd2868e785de7f0e26e67ae6ee4df8836c7f74"
+                ),
+            ),
+        ] {
+            assert!(verify_prehashed(
+                &generator_x,
+                &public_key_y,
+                &signature,
+                &HASHED_MESSAGE
+            ));
+        }
+
+        let mut hashed_message = HASHED_MESSAGE;
+        hashed_message[31] ^= 1;
+        assert!(!verify_prehashed(
+            &PUBLIC_KEY_X,
+            &PUBLIC_KEY_Y,
+            &SIGNATURE,
+            &hashed_message
+        ));
+    }
+}
@@ -0,0 +1,139 @@
+// Grumpkin, the curve y² = x³ - 17 defined over the scalar field of BN254.
+// Its points are pairs of field elements, so its arithmetic is native to the
+// circuits, and its order is the base field modulus of BN254, which is larger
+// than the field, so every field element is a valid scalar.
+
+use crate::acvm::FieldElement;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Point {
+    Infinity,
+    Affine { x: FieldElement, y: FieldElement },
+}
+
+impl Point {
+    // The generator of barretenberg, (1, √-16).
+    pub fn one() -> Point {
+        Point::Affine {
+            x: FieldElement::one(),
+            y: FieldElement::from_be_bytes_reduce(&hex_literal::hex!(
+                "0000000000000002cf135e7506a45d632d270d45f1181294833fc48d823f272c"
+            )),
+        }
+    }
+
+    #[cfg(test)]
+    fn is_on_curve(self) -> bool {
+        match self {
+            Point::Infinity => true,
+            Point::Affine { x, y } => y * y == x * x * x - FieldElement::from(17_u128),
+        }
+    }
+
+    pub fn add(self, other: Point) -> Point {
+        match (self, other) {
+            (Point::Infinity, point) | (point, Point::Infinity) => point,
+            (Point::Affine { x: x1, y: y1 }, Point::Affine { x: x2, y: y2 }) => {
+                if x1 == x2 {
+                    if (y1 + y2).is_zero() {
+                        return Point::Infinity;
+                    }
+                    return self.double();
+                }
+                let lambda = (y2 - y1) / (x2 - x1);
+                Point::from_lambda(lambda, x1, y1, x2)
+            }
+        }
+    }
+
+    pub fn double(self) -> Point {
+        match self {
+            Point::Infinity => Point::Infinity,
+            Point::Affine { y, .. } if y.is_zero() => Point::Infinity,
+            Point::Affine { x, y } => {
+                let lambda = FieldElement::from(3_u128) * x * x / (y + y);
+                Point::from_lambda(lambda, x, y, x)
+            }
+        }
+    }
+
+    // The sum of (x1, y1) and a point of abscissa x2 on the line of slope
+    // lambda going through both.
+    fn from_lambda(
+        lambda: FieldElement,
+        x1: FieldElement,
+        y1: FieldElement,
+        x2: FieldElement,
+    ) -> Point {
+        let x3 = lambda * lambda - x1 - x2;
+        let y3 = lambda * (x1 - x3) - y1;
+        Point::Affine { x: x3, y: y3 }
+    }
+
+    pub fn mul(self, scalar: FieldElement) -> Point {
+        self.mul_bytes(&scalar.to_be_bytes())
+    }
+
+    // Multiplies by a big endian integer, which may be larger than the field
+    // modulus.
+    pub fn mul_bytes(self, scalar: &[u8]) -> Point {
+        // Double and add, most significant bit first.
+        let bits = scalar
+            .iter()
+            .flat_map(|byte| (0..8_u8).rev().map(move |i| (byte >> i) & 1_u8 == 1));
+        bits.fold(Point::Infinity, |result, bit| {
+            let doubled = result.double();
+            if bit {
+                doubled.add(self)
+            } else {
+                doubled
+            }
+        })
+    }
+
+    // The coordinates of the point, (0, 0) standing for the point at infinity
+    // which is not on the curve.
+    pub fn coordinates(self) -> (FieldElement, FieldElement) {
+        match self {
+            Point::Infinity => (FieldElement::zero(), FieldElement::zero()),
+            Point::Affine { x, y } => (x, y),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multiples_of_one_are_on_the_curve() {
+        let one = Point::one();
+        assert!(one.is_on_curve());
+        assert!(one.double().is_on_curve());
+        assert!(one.add(one.double()).is_on_curve());
+        assert!(one.mul(FieldElement::from(1234_u128)).is_on_curve());
+    }
+
+    // 2^256 - 1 = 0x0101...01 * 0xff is larger than both the field modulus
+    // and the order of the curve.
+    #[test]
+    fn test_mul_bytes_does_not_reduce_modulo_the_field() {
+        let generator = Point::one();
+        let scalar = [0xff; 32];
+        let expected = generator.mul_bytes(&[0x01; 32]).mul_bytes(&[0xff]);
+        assert_eq!(generator.mul_bytes(&scalar), expected);
+    }
+
+    #[test]
+    fn test_mul_is_consistent_with_add() {
+        let generator = Point::one();
+        let three_times = generator.add(generator).add(generator);
+        let (x, y) = generator.coordinates();
+        let minus_generator = Point::Affine { x, y: -y };
+        assert_eq!(generator.mul(FieldElement::from(3_u128)), three_times);
+        assert_eq!(generator.add(minus_generator), Point::Infinity);
+        assert_eq!(generator.mul(FieldElement::zero()), Point::Infinity);
+        // -1 is r - 1, and r is not the order of the curve.
+        assert_ne!(generator.mul(-FieldElement::one()), minus_generator);
+    }
+}
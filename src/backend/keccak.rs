@@ -0,0 +1,169 @@
+// Keccak-256, the hash function used by Ethereum, which is the original
+// Keccak submission with a 0x01 padding byte rather than the 0x06 of SHA3-256.
+// acvm 0.5 doesn't solve it, so the backend does.
+
+// The lanes of the state, indexed by row and then column: lane (x, y) is
+// state[y][x].
+type State = [[u64; 5]; 5];
+
+// The rate of Keccak-256 in bytes, the capacity being 512 bits.
+const RATE: usize = 136;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000_0000_0000_0001,
+    0x0000_0000_0000_8082,
+    0x8000_0000_0000_808a,
+    0x8000_0000_8000_8000,
+    0x0000_0000_0000_808b,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8009,
+    0x0000_0000_0000_008a,
+    0x0000_0000_0000_0088,
+    0x0000_0000_8000_8009,
+    0x0000_0000_8000_000a,
+    0x0000_0000_8000_808b,
+    0x8000_0000_0000_008b,
+    0x8000_0000_0000_8089,
+    0x8000_0000_0000_8003,
+    0x8000_0000_0000_8002,
+    0x8000_0000_0000_0080,
+    0x0000_0000_0000_800a,
+    0x8000_0000_8000_000a,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8080,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8008,
+];
+
+// The rotation of every lane in the ρ step, indexed like the state.
+const ROTATIONS: [[u32; 5]; 5] = [
+    [0, 1, 62, 28, 27],
+    [36, 44, 6, 55, 20],
+    [3, 10, 43, 25, 39],
+    [41, 45, 15, 21, 8],
+    [18, 2, 61, 56, 14],
+];
+
+pub fn keccak256(message: &[u8]) -> [u8; 32] {
+    // Padding: a 0x01 byte, zeros up to a multiple of the rate and the last
+    // bit set.
+    let mut padded = message.to_vec();
+    padded.push(0x01);
+    padded.resize(padded.len().next_multiple_of(RATE), 0);
+    if let Some(last) = padded.last_mut() {
+        *last |= 0x80;
+    }
+
+    let mut state = State::default();
+    for block in padded.chunks_exact(RATE) {
+        for (lane, bytes) in state.iter_mut().flatten().zip(block.chunks_exact(8)) {
+            let mut lane_bytes = [0_u8; 8];
+            lane_bytes.copy_from_slice(bytes);
+            *lane ^= u64::from_le_bytes(lane_bytes);
+        }
+        keccak_f(&mut state);
+    }
+
+    let mut digest = [0_u8; 32];
+    for (bytes, lane) in digest.chunks_exact_mut(8).zip(state.iter().flatten()) {
+        bytes.copy_from_slice(&lane.to_le_bytes());
+    }
+    digest
+}
+
+// The Keccak-f[1600] permutation.
+fn keccak_f(state: &mut State) {
+    for round_constant in ROUND_CONSTANTS {
+        // θ: every lane is XORed with the parities of the columns on its left
+        // and, rotated by one bit, on its right.
+        let parities = state
+            .iter()
+            .fold([0_u64; 5], |parities, row| xor_rows(parities, *row));
+        let (mut left, mut right) = (parities, parities);
+        left.rotate_right(1);
+        right.rotate_left(1);
+        let mut theta = [0_u64; 5];
+        for (t, (l, r)) in theta.iter_mut().zip(left.iter().zip(right)) {
+            *t = l ^ r.rotate_left(1);
+        }
+        for row in state.iter_mut() {
+            *row = xor_rows(*row, theta);
+        }
+
+        // ρ and π: lane (x, y) is rotated and moved to (y, 2x + 3y).
+        let mut permuted = State::default();
+        for (y, (row, rotations)) in state.iter().zip(ROTATIONS).enumerate() {
+            for (x, (lane, rotation)) in row.iter().zip(rotations).enumerate() {
+                if let Some(target) = permuted
+                    .get_mut((2 * x + 3 * y) % 5)
+                    .and_then(|target_row| target_row.get_mut(y))
+                {
+                    *target = lane.rotate_left(rotation);
+                }
+            }
+        }
+
+        // χ: a[x] ^= !a[x + 1] & a[x + 2] on every row.
+        for (row, permuted_row) in state.iter_mut().zip(permuted) {
+            let (mut next, mut next_but_one) = (permuted_row, permuted_row);
+            next.rotate_left(1);
+            next_but_one.rotate_left(2);
+            for (lane, ((a, b), c)) in row
+                .iter_mut()
+                .zip(permuted_row.iter().zip(next).zip(next_but_one))
+            {
+                *lane = a ^ (!b & c);
+            }
+        }
+
+        // ι
+        state[0][0] ^= round_constant;
+    }
+}
+
+fn xor_rows(mut row: [u64; 5], other: [u64; 5]) -> [u64; 5] {
+    for (lane, other_lane) in row.iter_mut().zip(other) {
+        *lane ^= other_lane;
+    }
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The Go gadget is tested against the same vectors, see
+    // gnark_backend_ffi/backend/plonk/keccak_test.go.
+    #[test]
+    fn test_keccak256() {
+        let test_vectors: [(&[u8], &str); 3] = [
+            (
+                b"",
+                "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            ),
+            (
+                b"abc",
+                "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+            ),
+            (
+                b"The quick brown fox jumps over the lazy dog",
+                "4d741b6f1eb29cb2a9b9911c82f56fa8d73b04959d3d9d222895df6c0b28aa15",
+            ),
+        ];
+
+        for (message, digest) in test_vectors {
+            assert_eq!(hex::encode(keccak256(message)), digest, "{message:?}");
+        }
+    }
+
+    // A message longer than the rate is absorbed in two blocks.
+    #[test]
+    fn test_keccak256_absorbs_several_blocks() {
+        let message: Vec<u8> = (0..200_u8).collect();
+        assert_eq!(
+            hex::encode(keccak256(&message)),
+            "bfb0aa97863e797943cf7c33bb7e880bb4543f3d2703c0923c6901c2af57b890"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use crate::gnark_backend_wrapper::GnarkBackendError;
-use std::ffi::CString;
-use std::os::raw::c_char;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_uchar, c_uint, c_ulonglong};
+use std::str::Utf8Error;
 
 #[derive(Debug)]
 #[repr(C)]
@@ -19,8 +20,238 @@ impl TryFrom<&CString> for GoString {
     }
 }
 
+// The exported Go functions return their results followed by an error code
+// and an error message, which cgo packs into a struct in that order.
+
 #[repr(C)]
 pub struct KeyPair {
     pub proving_key: *const c_char,
     pub verifying_key: *const c_char,
+    pub error_code: c_int,
+    pub error_message: *const c_char,
+}
+
+#[repr(C)]
+pub struct ProofResult {
+    pub proof: *const c_char,
+    pub error_code: c_int,
+    pub error_message: *const c_char,
+}
+
+#[repr(C)]
+pub struct SRSResult {
+    pub srs: *const c_char,
+    pub error_code: c_int,
+    pub error_message: *const c_char,
+}
+
+#[repr(C)]
+pub struct SRSHandleResult {
+    pub handle: c_ulonglong,
+    pub size: c_uint,
+    pub error_code: c_int,
+    pub error_message: *const c_char,
+}
+
+#[repr(C)]
+pub struct VerifyResult {
+    pub verifies: c_uchar,
+    pub error_code: c_int,
+    pub error_message: *const c_char,
+}
+
+#[repr(C)]
+pub struct SizeResult {
+    pub size: c_uint,
+    pub error_code: c_int,
+    pub error_message: *const c_char,
+}
+
+impl KeyPair {
+    /// Takes ownership of the strings allocated by Go and returns a copy of
+    /// the hex encoded proving and verifying keys.
+    pub fn into_keys(self) -> Result<(String, String), GnarkBackendError> {
+        let (proving_key, verifying_key, error_message) = unsafe {
+            (
+                GoCString::from_raw(self.proving_key),
+                GoCString::from_raw(self.verifying_key),
+                GoCString::from_raw(self.error_message),
+            )
+        };
+        go_status(self.error_code, &error_message.to_string_lossy())?;
+        let proving_key = proving_key
+            .to_str()
+            .map_err(|e| GnarkBackendError::DeserializeKeyError(e.to_string()))?;
+        let verifying_key = verifying_key
+            .to_str()
+            .map_err(|e| GnarkBackendError::DeserializeKeyError(e.to_string()))?;
+        Ok((proving_key.to_owned(), verifying_key.to_owned()))
+    }
+}
+
+impl ProofResult {
+    /// Takes ownership of the strings allocated by Go and returns a copy of
+    /// the hex encoded proof.
+    pub fn into_proof(self) -> Result<String, GnarkBackendError> {
+        let (proof, error_message) = unsafe {
+            (
+                GoCString::from_raw(self.proof),
+                GoCString::from_raw(self.error_message),
+            )
+        };
+        go_status(self.error_code, &error_message.to_string_lossy())?;
+        let proof = proof
+            .to_str()
+            .map_err(|e| GnarkBackendError::DeserializeProofError(e.to_string()))?;
+        Ok(proof.to_owned())
+    }
+}
+
+impl SRSResult {
+    /// Takes ownership of the strings allocated by Go and returns a copy of
+    /// the hex encoded SRS.
+    pub fn into_srs(self) -> Result<String, GnarkBackendError> {
+        let (srs, error_message) = unsafe {
+            (
+                GoCString::from_raw(self.srs),
+                GoCString::from_raw(self.error_message),
+            )
+        };
+        go_status(self.error_code, &error_message.to_string_lossy())?;
+        let srs = srs
+            .to_str()
+            .map_err(|e| GnarkBackendError::SRSError(e.to_string()))?;
+        Ok(srs.to_owned())
+    }
+}
+
+impl SRSHandleResult {
+    /// Takes ownership of the error message allocated by Go and returns the
+    /// handle of the loaded SRS and its number of G1 points.
+    pub fn into_handle(self) -> Result<(c_ulonglong, u32), GnarkBackendError> {
+        let error_message = unsafe { GoCString::from_raw(self.error_message) };
+        go_status(self.error_code, &error_message.to_string_lossy())?;
+        Ok((self.handle, self.size))
+    }
+}
+
+impl VerifyResult {
+    /// Takes ownership of the error message allocated by Go and returns
+    /// whether the proof verifies.
+    pub fn into_verifies(self) -> Result<bool, GnarkBackendError> {
+        let error_message = unsafe { GoCString::from_raw(self.error_message) };
+        go_status(self.error_code, &error_message.to_string_lossy())?;
+        match self.verifies {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(GnarkBackendError::VerifyInvalidBoolError),
+        }
+    }
+}
+
+impl SizeResult {
+    /// Takes ownership of the error message allocated by Go and returns the
+    /// number of constraints.
+    pub fn into_size(self) -> Result<u32, GnarkBackendError> {
+        let error_message = unsafe { GoCString::from_raw(self.error_message) };
+        go_status(self.error_code, &error_message.to_string_lossy())?;
+        Ok(self.size)
+    }
+}
+
+/// Owns a string allocated by the Go side with `C.CString` and frees it when
+/// dropped.
+pub struct GoCString(*const c_char);
+
+impl GoCString {
+    /// # Safety
+    ///
+    /// `ptr` must be null or point to a string allocated by the Go side that
+    /// is not freed anywhere else.
+    pub unsafe fn from_raw(ptr: *const c_char) -> Self {
+        Self(ptr)
+    }
+
+    /// Borrows the string, which is empty if the pointer is null.
+    pub fn to_str(&self) -> Result<&str, Utf8Error> {
+        if self.0.is_null() {
+            return Ok("");
+        }
+        unsafe { CStr::from_ptr(self.0) }.to_str()
+    }
+
+    pub fn to_string_lossy(&self) -> String {
+        if self.0.is_null() {
+            return String::new();
+        }
+        unsafe { CStr::from_ptr(self.0) }
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+impl Drop for GoCString {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { FreeCString(self.0) }
+        }
+    }
+}
+
+extern "C" {
+    fn FreeCString(ptr: *const c_char);
+}
+
+fn go_status(error_code: c_int, error_message: &str) -> Result<(), GnarkBackendError> {
+    if error_code == GO_OK {
+        return Ok(());
+    }
+    Err(GnarkBackendError::from_go_error(
+        error_code,
+        error_message.to_owned(),
+    ))
+}
+
+// Error codes returned by the Go side, see gnark_backend_ffi/internal/errors.go.
+pub const GO_OK: c_int = 0;
+pub const GO_INVALID_CIRCUIT: c_int = 1;
+pub const GO_INVALID_VALUES: c_int = 2;
+pub const GO_INVALID_PROOF: c_int = 3;
+pub const GO_INVALID_KEY: c_int = 4;
+pub const GO_UNSATISFIED_CONSTRAINT: c_int = 5;
+pub const GO_KEY_MISMATCH: c_int = 6;
+pub const GO_SRS_ERROR: c_int = 7;
+pub const GO_KEYS_UNAVAILABLE: c_int = 9;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_go_status_is_ok_with_ok_code() {
+        assert!(go_status(GO_OK, "").is_ok());
+    }
+
+    #[test]
+    fn test_go_status_maps_error_codes() {
+        let message = "constraint #0 is not satisfied";
+
+        let error = go_status(GO_UNSATISFIED_CONSTRAINT, message).unwrap_err();
+        assert!(matches!(
+            error,
+            GnarkBackendError::UnsatisfiedConstraintError(m) if m == "constraint #0 is not satisfied"
+        ));
+
+        let error = go_status(GO_KEY_MISMATCH, message).unwrap_err();
+        assert!(matches!(error, GnarkBackendError::KeyMismatchError(_)));
+
+        let error = go_status(GO_SRS_ERROR, message).unwrap_err();
+        assert!(matches!(error, GnarkBackendError::SRSError(_)));
+
+        let error = go_status(GO_KEYS_UNAVAILABLE, message).unwrap_err();
+        assert!(matches!(error, GnarkBackendError::KeysUnavailableError(_)));
+
+        let error = go_status(1000, "").unwrap_err();
+        assert!(matches!(error, GnarkBackendError::Error(m) if m.is_empty()));
+    }
 }
@@ -1,4 +1,9 @@
+use super::c_go_structures::{
+    GO_INVALID_CIRCUIT, GO_INVALID_KEY, GO_INVALID_PROOF, GO_INVALID_VALUES, GO_KEYS_UNAVAILABLE,
+    GO_KEY_MISMATCH, GO_SRS_ERROR, GO_UNSATISFIED_CONSTRAINT,
+};
 use acvm::OpcodeResolutionError;
+use std::os::raw::c_int;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -24,6 +29,9 @@ pub enum GnarkBackendError {
     #[error("currently we do not support this opcode: {0}")]
     UnsupportedOpcodeError(String),
 
+    #[error("these opcodes would be left unconstrained: {}", .0.join(", "))]
+    UnconstrainedOpcodesError(Vec<String>),
+
     #[error("Verify did not return a valid bool")]
     VerifyInvalidBoolError,
 
@@ -33,6 +41,41 @@ pub enum GnarkBackendError {
     #[error("an error occurred while serializing felt: {0}")]
     SerializeFeltError(String),
 
+    #[error("the circuit is invalid: {0}")]
+    InvalidCircuitError(String),
+
+    #[error("an error occurred while deserializing felts: {0}")]
+    DeserializeFeltsError(String),
+
+    #[error("the witness does not satisfy the circuit: {0}")]
+    UnsatisfiedConstraintError(String),
+
+    #[error("the key does not match the circuit: {0}")]
+    KeyMismatchError(String),
+
+    #[error("an error occurred while loading the SRS: {0}")]
+    SRSError(String),
+
+    #[error("the keys the proof was made with are not available: {0}")]
+    KeysUnavailableError(String),
+
     #[error("an error occurred: {0}")]
     Error(String),
 }
+
+impl GnarkBackendError {
+    /// Builds the error matching the error code returned by the Go side.
+    pub(crate) fn from_go_error(error_code: c_int, message: String) -> Self {
+        match error_code {
+            GO_INVALID_CIRCUIT => Self::InvalidCircuitError(message),
+            GO_INVALID_VALUES => Self::DeserializeFeltsError(message),
+            GO_INVALID_PROOF => Self::DeserializeProofError(message),
+            GO_INVALID_KEY => Self::DeserializeKeyError(message),
+            GO_UNSATISFIED_CONSTRAINT => Self::UnsatisfiedConstraintError(message),
+            GO_KEY_MISMATCH => Self::KeyMismatchError(message),
+            GO_SRS_ERROR => Self::SRSError(message),
+            GO_KEYS_UNAVAILABLE => Self::KeysUnavailableError(message),
+            _ => Self::Error(message),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+// The scalar fields of BLS12-377 and BW6-761, which the arkworks crates we
+// depend on do not define, with the moduli and generators of ark-bls12-377
+// and ark-bw6-761.
+#![allow(
+    unexpected_cfgs,
+    non_local_definitions,
+    reason = "MontConfig derives code checking for an `asm` feature and implementing the trait inside a constant"
+)]
+
+use ark_ff::fields::{Fp256, Fp384, MontBackend, MontConfig};
+
+#[derive(MontConfig)]
+#[modulus = "8444461749428370424248824938781546531375899335154063827935233455917409239041"]
+#[generator = "22"]
+pub struct Bls12_377FrConfig;
+pub type Bls12_377Fr = Fp256<MontBackend<Bls12_377FrConfig, 4>>;
+
+// The base field of BLS12-377.
+#[derive(MontConfig)]
+#[modulus = "258664426012969094010652733694893533536393512754914660539884262666720468348340822774968888139573360124440321458177"]
+#[generator = "15"]
+pub struct Bw6_761FrConfig;
+pub type Bw6_761Fr = Fp384<MontBackend<Bw6_761FrConfig, 6>>;
@@ -1,10 +1,11 @@
 use super::super::{from_felt, Fr};
 use crate::acvm;
-use crate::gnark_backend_wrapper::groth16::serialize::{
-    deserialize_felt, deserialize_felts, serialize_felt, serialize_felts,
-};
 use crate::gnark_backend_wrapper::groth16::GnarkBackendError;
 use crate::gnark_backend_wrapper::num_constraints;
+use crate::gnark_backend_wrapper::serialize::{
+    deserialize_felt, deserialize_felts, serialize_felt, serialize_felts,
+};
+use ark_ff::PrimeField;
 use std::num::TryFromIntError;
 
 // AcirCircuit and AcirArithGate are R1CS-friendly structs.
@@ -14,52 +15,59 @@ use std::num::TryFromIntError;
 // - These structures only support arithmetic gates, while the compiler has other
 // gate types. These can be added later once the backend knows how to deal with things like XOR
 // or once ACIR is taught how to do convert these black box functions to Arithmetic gates.
+//
+// Their felts belong to the scalar field `F` of the curve the R1CS is built
+// over, Noir's field by default.
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
-pub struct RawR1CS {
-    pub gates: Vec<RawGate>,
+#[serde(bound = "")]
+pub struct RawR1CS<F: PrimeField = Fr> {
+    pub gates: Vec<RawGate<F>>,
     pub public_inputs: Vec<acvm::Witness>,
     #[serde(
         serialize_with = "serialize_felts",
         deserialize_with = "deserialize_felts"
     )]
-    pub values: Vec<Fr>,
+    pub values: Vec<F>,
     pub num_variables: u64,
     pub num_constraints: u64,
 }
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
-pub struct RawGate {
-    pub mul_terms: Vec<MulTerm>,
-    pub add_terms: Vec<AddTerm>,
+#[serde(bound = "")]
+pub struct RawGate<F: PrimeField = Fr> {
+    pub mul_terms: Vec<MulTerm<F>>,
+    pub add_terms: Vec<AddTerm<F>>,
     #[serde(
         serialize_with = "serialize_felt",
         deserialize_with = "deserialize_felt"
     )]
-    pub constant_term: Fr,
+    pub constant_term: F,
 }
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
-pub struct MulTerm {
+#[serde(bound = "")]
+pub struct MulTerm<F: PrimeField = Fr> {
     #[serde(
         serialize_with = "serialize_felt",
         deserialize_with = "deserialize_felt"
     )]
-    pub coefficient: Fr,
+    pub coefficient: F,
     pub multiplicand: acvm::Witness,
     pub multiplier: acvm::Witness,
 }
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
-pub struct AddTerm {
+#[serde(bound = "")]
+pub struct AddTerm<F: PrimeField = Fr> {
     #[serde(
         serialize_with = "serialize_felt",
         deserialize_with = "deserialize_felt"
     )]
-    pub coefficient: Fr,
+    pub coefficient: F,
     pub sum: acvm::Witness,
 }
 
-impl RawR1CS {
+impl<F: PrimeField> RawR1CS<F> {
     pub fn new(
         acir: acvm::Circuit,
         values: Vec<acvm::FieldElement>,
@@ -77,11 +85,14 @@ impl RawR1CS {
                 let expression = opcode.clone().arithmetic().ok_or(
                     GnarkBackendError::UnsupportedOpcodeError(opcode.to_string()),
                 )?;
-                gates.push(RawGate::new(expression));
+                gates.push(RawGate::new(expression)?);
                 Ok::<_, GnarkBackendError>(())
             })?;
 
-        let values: Vec<Fr> = values.into_iter().map(from_felt).collect();
+        let values = values
+            .into_iter()
+            .map(from_felt)
+            .collect::<Result<_, _>>()?;
 
         Ok(Self {
             gates,
@@ -93,65 +104,69 @@ impl RawR1CS {
     }
 }
 
-impl RawGate {
-    pub fn new(arithmetic_gate: acvm::Expression) -> Self {
-        let converted_mul_terms: Vec<MulTerm> = arithmetic_gate
+impl<F: PrimeField> RawGate<F> {
+    pub fn new(arithmetic_gate: acvm::Expression) -> Result<Self, GnarkBackendError> {
+        let converted_mul_terms: Vec<MulTerm<F>> = arithmetic_gate
             .mul_terms
             .into_iter()
-            .map(|(coefficient, multiplicand, multiplier)| MulTerm {
-                coefficient: from_felt(coefficient),
-                multiplicand,
-                multiplier,
+            .map(|(coefficient, multiplicand, multiplier)| {
+                Ok(MulTerm {
+                    coefficient: from_felt(coefficient)?,
+                    multiplicand,
+                    multiplier,
+                })
             })
-            .collect();
+            .collect::<Result<_, GnarkBackendError>>()?;
 
-        let converted_linear_combinations: Vec<_> = arithmetic_gate
+        let converted_linear_combinations: Vec<AddTerm<F>> = arithmetic_gate
             .linear_combinations
             .into_iter()
-            .map(|(coefficient, sum)| AddTerm {
-                coefficient: from_felt(coefficient),
-                sum,
+            .map(|(coefficient, sum)| {
+                Ok(AddTerm {
+                    coefficient: from_felt(coefficient)?,
+                    sum,
+                })
             })
-            .collect();
+            .collect::<Result<_, GnarkBackendError>>()?;
 
-        Self {
+        Ok(Self {
             mul_terms: converted_mul_terms,
             add_terms: converted_linear_combinations,
-            constant_term: from_felt(arithmetic_gate.q_c),
-        }
+            constant_term: from_felt(arithmetic_gate.q_c)?,
+        })
     }
 }
 
-impl Copy for MulTerm {}
-impl Copy for AddTerm {}
+impl<F: PrimeField> Copy for MulTerm<F> {}
+impl<F: PrimeField> Copy for AddTerm<F> {}
 
-impl std::fmt::Debug for MulTerm {
+impl<F: PrimeField> std::fmt::Debug for MulTerm<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Coefficient: {:?}", self.coefficient.0 .0)?;
+        writeln!(f, "Coefficient: {}", self.coefficient)?;
         writeln!(f, "Multiplicand: {:?}", self.multiplicand.0)?;
         writeln!(f, "Multiplier: {:?}", self.multiplier.0)?;
         writeln!(f)
     }
 }
 
-impl std::fmt::Debug for AddTerm {
+impl<F: PrimeField> std::fmt::Debug for AddTerm<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Coefficient: {:?}", self.coefficient.0 .0)?;
+        writeln!(f, "Coefficient: {}", self.coefficient)?;
         writeln!(f, "Sum: {:?}", self.sum.0)?;
         writeln!(f)
     }
 }
 
-impl std::fmt::Debug for RawGate {
+impl<F: PrimeField> std::fmt::Debug for RawGate<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.mul_terms.fmt(f)?;
         self.add_terms.fmt(f)?;
-        writeln!(f, "Constant term: {:?}", self.constant_term.0 .0)?;
+        writeln!(f, "Constant term: {}", self.constant_term)?;
         writeln!(f)
     }
 }
 
-impl std::fmt::Debug for RawR1CS {
+impl<F: PrimeField> std::fmt::Debug for RawR1CS<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.gates.fmt(f)?;
         writeln!(
@@ -167,7 +182,7 @@ impl std::fmt::Debug for RawR1CS {
             "Values: {:?}",
             self.values
                 .iter()
-                .map(|value| value.0 .0)
+                .map(ToString::to_string)
                 .collect::<Vec<_>>()
         )?;
         writeln!(f, "Number of variables: {}", self.num_variables)?;
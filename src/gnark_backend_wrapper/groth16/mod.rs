@@ -1,57 +1,88 @@
 use crate::acvm;
-use std::ffi::{CStr, CString};
+use ark_ff::PrimeField;
+use std::ffi::CString;
 use std::num::TryFromIntError;
-use std::os::raw::{c_char, c_uchar};
 
 mod acir_to_r1cs;
 
-use crate::gnark_backend_wrapper::c_go_structures::{GoString, KeyPair};
+use crate::gnark_backend_wrapper::c_go_structures::{
+    GoString, KeyPair, ProofResult, SizeResult, VerifyResult,
+};
 use crate::gnark_backend_wrapper::errors::GnarkBackendError;
 pub use crate::gnark_backend_wrapper::groth16::acir_to_r1cs::{AddTerm, MulTerm, RawGate, RawR1CS};
-use crate::gnark_backend_wrapper::num_constraints;
-use crate::Gnark;
+use crate::gnark_backend_wrapper::public_inputs_to_values;
+use crate::gnark_backend_wrapper::{curve_c_str, fields, CurveId};
 
 extern "C" {
-    fn VerifyWithMeta(rawr1cs: GoString, proof: GoString) -> c_uchar;
-    fn ProveWithMeta(rawr1cs: GoString) -> *const c_char;
-    fn VerifyWithVK(rawr1cs: GoString, proof: GoString, verifying_key: GoString) -> c_uchar;
-    fn ProveWithPK(rawr1cs: GoString, proving_key: GoString) -> *const c_char;
-    fn Preprocess(circuit: GoString) -> KeyPair;
+    fn Groth16VerifyWithMeta(curve: GoString, rawr1cs: GoString, proof: GoString) -> VerifyResult;
+    fn Groth16ProveWithMeta(curve: GoString, rawr1cs: GoString) -> ProofResult;
+    fn Groth16VerifyWithVK(
+        curve: GoString,
+        rawr1cs: GoString,
+        proof: GoString,
+        verifying_key: GoString,
+    ) -> VerifyResult;
+    fn Groth16ProveWithPK(curve: GoString, rawr1cs: GoString, proving_key: GoString)
+        -> ProofResult;
+    fn Groth16GetExactCircuitSize(curve: GoString, rawr1cs: GoString) -> SizeResult;
+    fn Groth16Preprocess(curve: GoString, rawr1cs: GoString) -> KeyPair;
+}
+
+// The RawR1CS of the circuit over the scalar field of `curve`, as JSON.
+fn serialize_rawr1cs(
+    curve: CurveId,
+    circuit: acvm::Circuit,
+    values: Vec<acvm::FieldElement>,
+) -> Result<String, GnarkBackendError> {
+    fn to_json<F: PrimeField>(
+        circuit: acvm::Circuit,
+        values: Vec<acvm::FieldElement>,
+    ) -> Result<String, GnarkBackendError> {
+        serde_json::to_string(&RawR1CS::<F>::new(circuit, values)?)
+            .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))
+    }
+
+    match curve {
+        CurveId::Bn254 => to_json::<ark_bn254::Fr>(circuit, values),
+        CurveId::Bls12_381 => to_json::<ark_bls12_381::Fr>(circuit, values),
+        CurveId::Bls12_377 => to_json::<fields::Bls12_377Fr>(circuit, values),
+        CurveId::Bw6_761 => to_json::<fields::Bw6_761Fr>(circuit, values),
+    }
 }
 
 pub fn prove_with_meta(
+    curve: CurveId,
     circuit: acvm::Circuit,
     values: Vec<acvm::FieldElement>,
 ) -> Result<Vec<u8>, GnarkBackendError> {
-    let rawr1cs = RawR1CS::new(circuit, values)?;
+    let curve_c_str = curve_c_str(curve)?;
+    let curve_go_string = GoString::try_from(&curve_c_str)?;
 
     // Serialize to json and then convert to GoString
-    let serialized_rawr1cs = serde_json::to_string(&rawr1cs)
-        .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
+    let serialized_rawr1cs = serialize_rawr1cs(curve, circuit, values)?;
     let c_str = CString::new(serialized_rawr1cs)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let go_string_rawr1cs = GoString::try_from(&c_str)?;
 
-    let result: *const c_char = unsafe { ProveWithMeta(go_string_rawr1cs) };
-    let c_str = unsafe { CStr::from_ptr(result) };
-    let bytes = c_str
-        .to_str()
-        .map_err(|e| GnarkBackendError::DeserializeProofError(e.to_string()))?
-        .as_bytes();
+    let result: ProofResult = unsafe { Groth16ProveWithMeta(curve_go_string, go_string_rawr1cs) };
+    let proof_str = result.into_proof()?;
+    let decoded_proof = hex::decode(proof_str)
+        .map_err(|e| GnarkBackendError::DeserializeProofError(e.to_string()))?;
 
-    Ok(bytes.to_vec())
+    Ok(decoded_proof)
 }
 
 pub fn prove_with_pk(
+    curve: CurveId,
     circuit: &acvm::Circuit,
     values: Vec<acvm::FieldElement>,
     proving_key: &[u8],
 ) -> Result<Vec<u8>, GnarkBackendError> {
-    let rawr1cs = RawR1CS::new(circuit.clone(), values)?;
+    let curve_c_str = curve_c_str(curve)?;
+    let curve_go_string = GoString::try_from(&curve_c_str)?;
 
     // Serialize to json and then convert to GoString
-    let rawr1cs_json = serde_json::to_string(&rawr1cs)
-        .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
+    let rawr1cs_json = serialize_rawr1cs(curve, circuit.clone(), values)?;
     let rawr1cs_c_str = CString::new(rawr1cs_json)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let rawr1cs_go_string = GoString::try_from(&rawr1cs_c_str)?;
@@ -62,56 +93,55 @@ pub fn prove_with_pk(
     let proving_key_go_string = GoString::try_from(&proving_key_c_str)
         .map_err(|e| GnarkBackendError::SerializeKeyError(e.to_string()))?;
 
-    let proof: *const c_char = unsafe { ProveWithPK(rawr1cs_go_string, proving_key_go_string) };
-    let proof_c_str = unsafe { CStr::from_ptr(proof) };
-    let proof_str = proof_c_str
-        .to_str()
-        .map_err(|e| GnarkBackendError::DeserializeProofError(e.to_string()))?;
+    let result: ProofResult =
+        unsafe { Groth16ProveWithPK(curve_go_string, rawr1cs_go_string, proving_key_go_string) };
+    let proof_str = result.into_proof()?;
     let decoded_proof = hex::decode(proof_str)
         .map_err(|e| GnarkBackendError::DeserializeProofError(e.to_string()))?;
 
     Ok(decoded_proof)
 }
 
+/// Verifies a proof returned by [`prove_with_meta`] in the same process, see
+/// [`super::verify_with_meta`].
 pub fn verify_with_meta(
+    curve: CurveId,
     circuit: acvm::Circuit,
     proof: &[u8],
     public_inputs: &[acvm::FieldElement],
 ) -> Result<bool, GnarkBackendError> {
-    let rawr1cs = RawR1CS::new(circuit, public_inputs.to_vec())?;
+    let curve_c_str = curve_c_str(curve)?;
+    let curve_go_string = GoString::try_from(&curve_c_str)?;
+    let values = public_inputs_to_values(&circuit, public_inputs)?;
 
     // Serialize to json and then convert to GoString
-    let rawr1cs_json = serde_json::to_string(&rawr1cs)
-        .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
+    let rawr1cs_json = serialize_rawr1cs(curve, circuit, values)?;
     let c_str = CString::new(rawr1cs_json)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let go_string_rawr1cs = GoString::try_from(&c_str)?;
 
-    let serialized_proof = String::from_utf8(proof.to_vec())
-        .map_err(|e| GnarkBackendError::SerializeProofError(e.to_string()))?;
-    let c_str = CString::new(serialized_proof)
+    let proof_serialized = hex::encode(proof);
+    let proof_c_str = CString::new(proof_serialized)
         .map_err(|e| GnarkBackendError::SerializeProofError(e.to_string()))?;
-    let go_string_proof = GoString::try_from(&c_str)?;
+    let go_string_proof = GoString::try_from(&proof_c_str)?;
 
-    let result = unsafe { VerifyWithMeta(go_string_rawr1cs, go_string_proof) };
-    match result {
-        0 => Ok(false),
-        1 => Ok(true),
-        _ => Err(GnarkBackendError::VerifyInvalidBoolError),
-    }
+    let result: VerifyResult =
+        unsafe { Groth16VerifyWithMeta(curve_go_string, go_string_rawr1cs, go_string_proof) };
+    result.into_verifies()
 }
 
 pub fn verify_with_vk(
+    curve: CurveId,
     circuit: &acvm::Circuit,
     proof: &[u8],
     public_inputs: &[acvm::FieldElement],
     verifying_key: &[u8],
 ) -> Result<bool, GnarkBackendError> {
-    let rawr1cs = RawR1CS::new(circuit.clone(), public_inputs.to_vec())?;
+    let curve_c_str = curve_c_str(curve)?;
+    let curve_go_string = GoString::try_from(&curve_c_str)?;
 
     // Serialize to json and then convert to GoString
-    let rawr1cs_json = serde_json::to_string(&rawr1cs)
-        .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
+    let rawr1cs_json = serialize_rawr1cs(curve, circuit.clone(), public_inputs.to_vec())?;
     let rawr1cs_c_str = CString::new(rawr1cs_json)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let rawr1cs_go_string = GoString::try_from(&rawr1cs_c_str)?;
@@ -126,51 +156,75 @@ pub fn verify_with_vk(
         .map_err(|e| GnarkBackendError::SerializeKeyError(e.to_string()))?;
     let verifying_key_go_string = GoString::try_from(&verifying_key_c_str)?;
 
-    let verifies =
-        unsafe { VerifyWithVK(rawr1cs_go_string, proof_go_string, verifying_key_go_string) };
-    match verifies {
-        0 => Ok(false),
-        1 => Ok(true),
-        _ => Err(GnarkBackendError::VerifyInvalidBoolError),
+    let result: VerifyResult = unsafe {
+        Groth16VerifyWithVK(
+            curve_go_string,
+            rawr1cs_go_string,
+            proof_go_string,
+            verifying_key_go_string,
+        )
+    };
+    result.into_verifies()
+}
+
+// The number of constraints handleRawGate adds for an arithmetic opcode: a
+// single product is constrained with the linear terms as its output, otherwise
+// every product gets its own constraint and the linear combination one more.
+pub fn num_gates(expression: &acvm::Expression) -> usize {
+    match expression.num_mul_terms() {
+        1 => 1,
+        num_mul_terms => num_mul_terms + 1,
     }
 }
 
-pub fn get_exact_circuit_size(circuit: &acvm::Circuit) -> Result<u32, GnarkBackendError> {
-    let size: u32 = num_constraints(circuit)?
+// The size is the number of constraints of the R1CS that the Go side builds
+// when proving, which does not depend on the values.
+pub fn get_exact_circuit_size(
+    curve: CurveId,
+    circuit: &acvm::Circuit,
+) -> Result<u32, GnarkBackendError> {
+    let curve_c_str = curve_c_str(curve)?;
+    let curve_go_string = GoString::try_from(&curve_c_str)?;
+    let num_witnesses: usize = circuit
+        .current_witness_index
         .try_into()
         .map_err(|e: TryFromIntError| GnarkBackendError::Error(e.to_string()))?;
-    Ok(size)
+    let values = vec![acvm::FieldElement::zero(); num_witnesses];
+
+    // Serialize to json and then convert to GoString
+    let serialized_rawr1cs = serialize_rawr1cs(curve, circuit.clone(), values)?;
+    let c_str = CString::new(serialized_rawr1cs)
+        .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
+    let go_string_rawr1cs = GoString::try_from(&c_str)?;
+
+    let result: SizeResult =
+        unsafe { Groth16GetExactCircuitSize(curve_go_string, go_string_rawr1cs) };
+    result.into_size()
 }
 
-pub fn preprocess(circuit: &acvm::Circuit) -> Result<(Vec<u8>, Vec<u8>), GnarkBackendError> {
+pub fn preprocess(
+    curve: CurveId,
+    circuit: &acvm::Circuit,
+) -> Result<(Vec<u8>, Vec<u8>), GnarkBackendError> {
+    let curve_c_str = curve_c_str(curve)?;
+    let curve_go_string = GoString::try_from(&curve_c_str)?;
     let num_witnesses: usize = circuit
         .num_vars()
         .try_into()
         .map_err(|e: TryFromIntError| GnarkBackendError::Error(e.to_string()))?;
     let values = vec![acvm::FieldElement::from(rand::random::<u128>()); num_witnesses - 1];
 
-    let rawr1cs = RawR1CS::new(circuit.clone(), values)?;
-
     // Serialize to json and then convert to GoString
-    let rawr1cs_json = serde_json::to_string(&rawr1cs)
-        .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
+    let rawr1cs_json = serialize_rawr1cs(curve, circuit.clone(), values)?;
     let rawr1cs_c_str = CString::new(rawr1cs_json)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let rawr1cs_go_string = GoString::try_from(&rawr1cs_c_str)?;
 
-    let key_pair: KeyPair = unsafe { Preprocess(rawr1cs_go_string) };
+    let key_pair: KeyPair = unsafe { Groth16Preprocess(curve_go_string, rawr1cs_go_string) };
+    let (proving_key_str, verifying_key_str) = key_pair.into_keys()?;
 
-    let proving_key_c_str = unsafe { CStr::from_ptr(key_pair.proving_key) };
-    let proving_key_str = proving_key_c_str
-        .to_str()
-        .map_err(|e| GnarkBackendError::DeserializeKeyError(e.to_string()))?;
     let decoded_proving_key = hex::decode(proving_key_str)
         .map_err(|e| GnarkBackendError::DeserializeKeyError(e.to_string()))?;
-
-    let verifying_key_c_str = unsafe { CStr::from_ptr(key_pair.verifying_key) };
-    let verifying_key_str = verifying_key_c_str
-        .to_str()
-        .map_err(|e| GnarkBackendError::DeserializeKeyError(e.to_string()))?;
     let decoded_verifying_key = hex::decode(verifying_key_str)
         .map_err(|e| GnarkBackendError::DeserializeKeyError(e.to_string()))?;
 
@@ -180,6 +234,7 @@ pub fn preprocess(circuit: &acvm::Circuit) -> Result<(Vec<u8>, Vec<u8>), GnarkBa
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::ffi::CStr;
 
     #[test]
     fn test_go_string_from_cstring() {
@@ -191,9 +246,29 @@ mod tests {
         assert_eq!(string, deserialized_string);
     }
 
+    #[test]
+    fn test_num_gates() {
+        let expression = |num_mul_terms| acvm::Expression {
+            mul_terms: vec![
+                (
+                    acvm::FieldElement::one(),
+                    acvm::Witness(1),
+                    acvm::Witness(2)
+                );
+                num_mul_terms
+            ],
+            linear_combinations: vec![(acvm::FieldElement::one(), acvm::Witness(3))],
+            q_c: acvm::FieldElement::zero(),
+        };
+
+        assert_eq!(num_gates(&expression(0)), 1);
+        assert_eq!(num_gates(&expression(1)), 1);
+        assert_eq!(num_gates(&expression(3)), 4);
+    }
+
     #[test]
     fn get_exact_circuit_size_should_return_zero_with_an_empty_circuit() {
-        let size = get_exact_circuit_size(&acvm::Circuit::default()).unwrap();
+        let size = get_exact_circuit_size(CurveId::Bn254, &acvm::Circuit::default()).unwrap();
         assert_eq!(size, 0);
     }
 }
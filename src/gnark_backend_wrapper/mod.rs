@@ -1,73 +1,589 @@
 use crate::acvm;
+use ark_ff::{BigInteger, PrimeField};
+use std::ffi::CString;
+use std::num::TryFromIntError;
 
 mod errors;
 pub use errors::GnarkBackendError;
 
 mod c_go_structures;
+use c_go_structures::SizeResult;
 pub use c_go_structures::{GoString, KeyPair};
 
+mod report;
+pub use report::{BlackBoxFunctionSupport, CircuitReport, OpcodeIssue, WidestExpression};
+
+mod fields;
+
 mod serialize;
 
-// Arkworks's types are generic for `Field` but Noir's types are concrete and
-// its value depends on the feature flag.
+mod srs;
+pub use srs::Srs;
+
+// Noir's field elements are concrete and their type depends on the feature
+// flag, the circuits being built over the scalar field of its curve by default.
 cfg_if::cfg_if! {
     if #[cfg(feature = "bn254")] {
         pub use ark_bn254::{Bn254 as Curve, Fr};
-
-        // Converts a FieldElement to a Fr
-        // noir_field uses arkworks for bn254
-        pub fn from_felt(felt: acvm::FieldElement) -> Fr {
-            felt.into_repr()
-        }
     } else if #[cfg(feature = "bls12_381")] {
         pub use ark_bls12_381::{Bls12_381 as Curve, Fr};
+    } else {
+        compile_error!("please specify a field to compile with");
+    }
+}
 
-        // Converts a FieldElement to a Fr
-        // noir_field uses arkworks for bls12_381
-        pub fn from_felt(felt: FieldElement) -> Fr {
-            felt.into_repr()
+// Converts a FieldElement to an element of the scalar field `F`. Noir's field
+// elements above half its order stand for negative integers, like the -1
+// coefficients of most expressions, so they are taken to the negative elements
+// of `F`, and the other elements to the elements of `F` holding the same
+// integer. This fails if the integer does not fit in `F`, whose elements above
+// half its order are negative too.
+pub fn from_felt<F: PrimeField>(felt: acvm::FieldElement) -> Result<F, GnarkBackendError> {
+    let negative = felt.into_repr().into_bigint() > Fr::MODULUS_MINUS_ONE_DIV_TWO;
+    let magnitude = if negative { -felt } else { felt }.to_be_bytes();
+
+    let element = F::from_be_bytes_mod_order(&magnitude);
+    // The integer was reduced if its bytes changed.
+    let reduced = !element
+        .into_bigint()
+        .to_bytes_be()
+        .iter()
+        .skip_while(|byte| **byte == 0)
+        .eq(magnitude.iter().skip_while(|byte| **byte == 0));
+    if reduced || element.into_bigint() > F::MODULUS_MINUS_ONE_DIV_TWO {
+        return Err(GnarkBackendError::SerializeFeltError(format!(
+            "{} does not fit in a field of {} bits",
+            felt.to_hex(),
+            F::MODULUS_BIT_SIZE
+        )));
+    }
+    Ok(if negative { -element } else { element })
+}
+
+// Encodes felts for the Go side as elements of the scalar field of `curve`,
+// which are as wide as it.
+fn encode_felts(
+    curve: CurveId,
+    felts: Vec<acvm::FieldElement>,
+) -> Result<String, GnarkBackendError> {
+    fn encode<F: PrimeField>(felts: Vec<acvm::FieldElement>) -> Result<String, GnarkBackendError> {
+        let felts = felts
+            .into_iter()
+            .map(from_felt)
+            .collect::<Result<Vec<F>, _>>()?;
+        serialize::encode_felts(&felts)
+    }
+
+    match curve {
+        CurveId::Bn254 => encode::<ark_bn254::Fr>(felts),
+        CurveId::Bls12_381 => encode::<ark_bls12_381::Fr>(felts),
+        CurveId::Bls12_377 => encode::<fields::Bls12_377Fr>(felts),
+        CurveId::Bw6_761 => encode::<fields::Bw6_761Fr>(felts),
+    }
+}
+
+// Every proving function of the Go side takes the curve first.
+fn curve_c_str(curve: CurveId) -> Result<CString, GnarkBackendError> {
+    CString::new(curve.name()).map_err(|e| GnarkBackendError::Error(e.to_string()))
+}
+
+mod groth16;
+pub use groth16::{AddTerm, MulTerm, RawGate, RawR1CS};
+
+mod plonk;
+
+/// The gnark proving scheme circuits are preprocessed, proven and verified
+/// with. Both are always available, the `groth16` and `plonk` features only
+/// pick the default one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProvingScheme {
+    Plonk,
+    Groth16,
+}
+
+impl ProvingScheme {
+    /// The scheme picked by the `groth16` and `plonk` features.
+    pub const DEFAULT: ProvingScheme = if cfg!(feature = "groth16") {
+        ProvingScheme::Groth16
+    } else {
+        ProvingScheme::Plonk
+    };
+
+    /// The name of the scheme on the Go side, see BlackBoxFunctionSupport.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProvingScheme::Plonk => "plonk",
+            ProvingScheme::Groth16 => "groth16",
         }
+    }
+}
+
+impl Default for ProvingScheme {
+    fn default() -> Self {
+        ProvingScheme::DEFAULT
+    }
+}
+
+/// The curve whose scalar field circuits are built over, embedded in the keys
+/// and proofs so they are not used with another curve. BLS12-377 and BW6-761
+/// form the two-chain gnark uses for one-level recursion. Over another curve
+/// than the one of the field feature, the witnesses and coefficients are taken
+/// to its scalar field by [`from_felt`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveId {
+    Bn254,
+    Bls12_381,
+    Bls12_377,
+    Bw6_761,
+}
+
+impl CurveId {
+    /// The curve of the field feature.
+    pub const DEFAULT: CurveId = if cfg!(feature = "bn254") {
+        CurveId::Bn254
     } else {
-        compile_error!("please specify a field to compile with");
+        CurveId::Bls12_381
+    };
+
+    /// The name of the curve on the Go side, as given by gnark's ecc.ID.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CurveId::Bn254 => "bn254",
+            CurveId::Bls12_381 => "bls12_381",
+            CurveId::Bls12_377 => "bls12_377",
+            CurveId::Bw6_761 => "bw6_761",
+        }
+    }
+
+    // The byte keys and proofs start with.
+    fn tag(&self) -> u8 {
+        match self {
+            CurveId::Bn254 => 1,
+            CurveId::Bls12_381 => 2,
+            CurveId::Bls12_377 => 3,
+            CurveId::Bw6_761 => 4,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        [
+            CurveId::Bn254,
+            CurveId::Bls12_381,
+            CurveId::Bls12_377,
+            CurveId::Bw6_761,
+        ]
+        .into_iter()
+        .find(|curve| curve.tag() == tag)
+    }
+
+    // Prefixes a key or a proof made by the Go side with the curve.
+    fn embed(&self, mut bytes: Vec<u8>) -> Vec<u8> {
+        bytes.insert(0, self.tag());
+        bytes
+    }
+
+    // Returns the key or proof made by the Go side out of `bytes`, failing
+    // with `error` if it was not made over this curve.
+    fn strip<'bytes>(
+        &self,
+        bytes: &'bytes [u8],
+        error: fn(String) -> GnarkBackendError,
+    ) -> Result<&'bytes [u8], GnarkBackendError> {
+        let (tag, rest) = bytes
+            .split_first()
+            .ok_or_else(|| error("missing curve".to_owned()))?;
+        match CurveId::from_tag(*tag) {
+            Some(curve) if curve == *self => Ok(rest),
+            Some(curve) => Err(error(format!(
+                "made over {} but expected {}",
+                curve.name(),
+                self.name()
+            ))),
+            None => Err(error(format!("unknown curve {tag}"))),
+        }
     }
 }
 
-cfg_if::cfg_if! {
-    if #[cfg(feature = "groth16")] {
-        mod groth16;
-        pub use groth16::{AddTerm, Fr, GoString, MulTerm, RawGate, RawR1CS};
-        pub use groth16::verify_with_meta;
-        pub use groth16::prove_with_meta;
-        pub use groth16::verify_with_vk;
-        pub use groth16::prove_with_pk;
-        pub use groth16::get_exact_circuit_size;
-        pub use groth16::preprocess;
-    } else if #[cfg(feature = "plonk")] {
-        mod plonk;
-        pub use plonk::verify_with_meta;
-        pub use plonk::prove_with_meta;
-        pub use plonk::verify_with_vk;
-        pub use plonk::prove_with_pk;
-        pub use plonk::get_exact_circuit_size;
-        pub use plonk::preprocess;
+impl Default for CurveId {
+    fn default() -> Self {
+        CurveId::DEFAULT
     }
 }
 
+pub fn prove_with_meta(
+    proving_scheme: ProvingScheme,
+    curve: CurveId,
+    circuit: acvm::Circuit,
+    values: Vec<acvm::FieldElement>,
+    srs: Option<&Srs>,
+) -> Result<Vec<u8>, GnarkBackendError> {
+    let proof = match proving_scheme {
+        ProvingScheme::Plonk => {
+            plonk::prove_with_meta(curve, circuit, values, srs::required_srs(curve, srs)?)
+        }
+        ProvingScheme::Groth16 => groth16::prove_with_meta(curve, circuit, values),
+    }?;
+    Ok(curve.embed(proof))
+}
+
+pub fn prove_with_pk(
+    proving_scheme: ProvingScheme,
+    curve: CurveId,
+    circuit: &acvm::Circuit,
+    values: Vec<acvm::FieldElement>,
+    proving_key: &[u8],
+    srs: Option<&Srs>,
+) -> Result<Vec<u8>, GnarkBackendError> {
+    let proving_key = curve.strip(proving_key, GnarkBackendError::DeserializeKeyError)?;
+    let proof = match proving_scheme {
+        ProvingScheme::Plonk => plonk::prove_with_pk(
+            curve,
+            circuit,
+            values,
+            proving_key,
+            srs::required_srs(curve, srs)?,
+        ),
+        ProvingScheme::Groth16 => groth16::prove_with_pk(curve, circuit, values, proving_key),
+    }?;
+    Ok(curve.embed(proof))
+}
+
+/// Verifies a proof returned by [`prove_with_meta`]. A Groth16 proof can not
+/// be verified in another process than the one that made it: the keys it was
+/// made with are sampled then and only kept, for the last 16 circuits, by the
+/// Go side of that process. Without them this fails with
+/// [`GnarkBackendError::KeysUnavailableError`] rather than returning `false`,
+/// and the proof must be made with [`prove_with_pk`] and checked with
+/// [`verify_with_vk`] instead.
+pub fn verify_with_meta(
+    proving_scheme: ProvingScheme,
+    curve: CurveId,
+    circuit: acvm::Circuit,
+    proof: &[u8],
+    public_inputs: &[acvm::FieldElement],
+    srs: Option<&Srs>,
+) -> Result<bool, GnarkBackendError> {
+    let proof = curve.strip(proof, GnarkBackendError::DeserializeProofError)?;
+    match proving_scheme {
+        ProvingScheme::Plonk => plonk::verify_with_meta(
+            curve,
+            circuit,
+            proof,
+            public_inputs,
+            srs::required_srs(curve, srs)?,
+        ),
+        ProvingScheme::Groth16 => groth16::verify_with_meta(curve, circuit, proof, public_inputs),
+    }
+}
+
+pub fn verify_with_vk(
+    proving_scheme: ProvingScheme,
+    curve: CurveId,
+    circuit: &acvm::Circuit,
+    proof: &[u8],
+    public_inputs: &[acvm::FieldElement],
+    verifying_key: &[u8],
+    srs: Option<&Srs>,
+) -> Result<bool, GnarkBackendError> {
+    let proof = curve.strip(proof, GnarkBackendError::DeserializeProofError)?;
+    let verifying_key = curve.strip(verifying_key, GnarkBackendError::DeserializeKeyError)?;
+    match proving_scheme {
+        ProvingScheme::Plonk => plonk::verify_with_vk(
+            curve,
+            circuit,
+            proof,
+            public_inputs,
+            verifying_key,
+            srs::required_srs(curve, srs)?,
+        ),
+        ProvingScheme::Groth16 => {
+            groth16::verify_with_vk(curve, circuit, proof, public_inputs, verifying_key)
+        }
+    }
+}
+
+pub fn get_exact_circuit_size(
+    proving_scheme: ProvingScheme,
+    curve: CurveId,
+    circuit: &acvm::Circuit,
+) -> Result<u32, GnarkBackendError> {
+    match proving_scheme {
+        ProvingScheme::Plonk => plonk::get_exact_circuit_size(curve, circuit),
+        ProvingScheme::Groth16 => groth16::get_exact_circuit_size(curve, circuit),
+    }
+}
+
+// The number of points the SRS needs to have for the circuit, `None` for
+// Groth16 which does not use one.
+pub fn required_srs_size(
+    proving_scheme: ProvingScheme,
+    curve: CurveId,
+    circuit: &acvm::Circuit,
+) -> Result<Option<u32>, GnarkBackendError> {
+    match proving_scheme {
+        ProvingScheme::Plonk => plonk::required_srs_size(curve, circuit).map(Some),
+        ProvingScheme::Groth16 => Ok(None),
+    }
+}
+
+// Fails if the proving scheme needs an SRS for the circuit and `srs` is
+// missing, over another curve or too small.
+pub fn check_srs(
+    proving_scheme: ProvingScheme,
+    curve: CurveId,
+    circuit: &acvm::Circuit,
+    srs: Option<&Srs>,
+) -> Result<(), GnarkBackendError> {
+    match required_srs_size(proving_scheme, curve, circuit)? {
+        Some(required_size) => srs::required_srs(curve, srs)?.check_size(required_size),
+        None => Ok(()),
+    }
+}
+
+pub fn preprocess(
+    proving_scheme: ProvingScheme,
+    curve: CurveId,
+    circuit: &acvm::Circuit,
+    srs: Option<&Srs>,
+) -> Result<(Vec<u8>, Vec<u8>), GnarkBackendError> {
+    let (proving_key, verifying_key) = match proving_scheme {
+        ProvingScheme::Plonk => plonk::preprocess(curve, circuit, srs::required_srs(curve, srs)?),
+        ProvingScheme::Groth16 => groth16::preprocess(curve, circuit),
+    }?;
+    Ok((curve.embed(proving_key), curve.embed(verifying_key)))
+}
+
+extern "C" {
+    fn BlackBoxFunctionSupport(
+        curve: GoString,
+        proving_scheme: GoString,
+        name: GoString,
+    ) -> SizeResult;
+    fn BlackBoxFunctionGates(
+        curve: GoString,
+        proving_scheme: GoString,
+        acir: GoString,
+    ) -> SizeResult;
+}
+
+// Asks the Go side how the circuits it builds for `proving_scheme` over `curve`
+// handle the calls to `func`, the gadgets of each scheme being listed over
+// there.
+pub fn black_box_function_support(
+    proving_scheme: ProvingScheme,
+    curve: CurveId,
+    func: &acvm::BlackBoxFunc,
+) -> Result<BlackBoxFunctionSupport, GnarkBackendError> {
+    let curve_c_str = curve_c_str(curve)?;
+    let curve_go_string = GoString::try_from(&curve_c_str)?;
+    let proving_scheme_c_str =
+        CString::new(proving_scheme.name()).map_err(|e| GnarkBackendError::Error(e.to_string()))?;
+    let proving_scheme_go_string = GoString::try_from(&proving_scheme_c_str)?;
+    let name_c_str = CString::new(report::acir_name(func)?)
+        .map_err(|e| GnarkBackendError::Error(e.to_string()))?;
+    let name_go_string = GoString::try_from(&name_c_str)?;
+
+    let result: SizeResult = unsafe {
+        BlackBoxFunctionSupport(curve_go_string, proving_scheme_go_string, name_go_string)
+    };
+    BlackBoxFunctionSupport::try_from(result.into_size()?)
+}
+
+// Asks the Go side how many gates the circuits it builds for `proving_scheme`
+// over `curve` spend on `func_call`, by sending a circuit holding the call
+// alone. The gadgets add the same gates whatever the values of the witnesses,
+// but the circuit shares the constants they use between the calls, so the
+// count may be a few gates too large.
+pub fn black_box_function_gates(
+    proving_scheme: ProvingScheme,
+    curve: CurveId,
+    func_call: &acvm::BlackBoxFuncCall,
+) -> Result<usize, GnarkBackendError> {
+    let curve_c_str = curve_c_str(curve)?;
+    let curve_go_string = GoString::try_from(&curve_c_str)?;
+    let proving_scheme_c_str =
+        CString::new(proving_scheme.name()).map_err(|e| GnarkBackendError::Error(e.to_string()))?;
+    let proving_scheme_go_string = GoString::try_from(&proving_scheme_c_str)?;
+    let witnesses = func_call
+        .inputs
+        .iter()
+        .map(|input| input.witness)
+        .chain(func_call.outputs.iter().copied());
+    let circuit = acvm::Circuit {
+        current_witness_index: witnesses.map(|witness| witness.0).max().unwrap_or_default(),
+        opcodes: vec![acvm::Opcode::BlackBoxFuncCall(func_call.clone())],
+        public_inputs: acvm::PublicInputs::default(),
+    };
+    let acir_json = serde_json::to_string(&circuit)
+        .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
+    let acir_c_str = CString::new(acir_json)
+        .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
+    let acir_go_string = GoString::try_from(&acir_c_str)?;
+
+    let result: SizeResult =
+        unsafe { BlackBoxFunctionGates(curve_go_string, proving_scheme_go_string, acir_go_string) };
+    result
+        .into_size()?
+        .try_into()
+        .map_err(|e: TryFromIntError| GnarkBackendError::Error(e.to_string()))
+}
+
+// The number of gates the circuits built for `proving_scheme` spend on an
+// arithmetic opcode.
+pub fn num_arithmetic_gates(proving_scheme: ProvingScheme, expression: &acvm::Expression) -> usize {
+    match proving_scheme {
+        ProvingScheme::Plonk => plonk::num_gates(expression),
+        ProvingScheme::Groth16 => groth16::num_gates(expression),
+    }
+}
+
+// Estimates the number of constraints of the arithmetic opcodes so the Go side
+// can preallocate them. The exact size of a circuit is given by
+// `get_exact_circuit_size`, which builds the constraint system.
 pub fn num_constraints(acir: &acvm::Circuit) -> Result<usize, GnarkBackendError> {
     // each multiplication term adds an extra constraint
     let mut num_opcodes = acir.opcodes.len();
 
     for opcode in acir.opcodes.iter() {
-        match opcode {
-            acvm::Opcode::Arithmetic(arith) => num_opcodes += arith.num_mul_terms() + 1, // plus one for the linear combination gate
-            acvm::Opcode::Directive(_) => (),
-            _ => {
-                return Err(GnarkBackendError::UnsupportedOpcodeError(
-                    opcode.to_string(),
-                ))
-            }
+        if let acvm::Opcode::Arithmetic(arith) = opcode {
+            num_opcodes += arith.num_mul_terms() + 1; // plus one for the linear combination gate
         }
     }
 
     Ok(num_opcodes)
 }
+
+// Walks the circuit without building its constraint system to tell whether
+// the proving scheme can prove it, see `CircuitReport`.
+pub fn circuit_report(
+    proving_scheme: ProvingScheme,
+    curve: CurveId,
+    acir: &acvm::Circuit,
+) -> Result<CircuitReport, GnarkBackendError> {
+    CircuitReport::new(
+        acir,
+        |expression| num_arithmetic_gates(proving_scheme, expression),
+        |func| black_box_function_support(proving_scheme, curve, func),
+        |func_call| black_box_function_gates(proving_scheme, curve, func_call),
+    )
+}
+
+// Verifying with the circuit metadata only gives us the values of the public
+// inputs (sorted by witness index), but gnark expects a value for every
+// witness, so the rest are zeroed.
+pub fn public_inputs_to_values(
+    circuit: &acvm::Circuit,
+    public_inputs: &[acvm::FieldElement],
+) -> Result<Vec<acvm::FieldElement>, GnarkBackendError> {
+    let public_input_indices = circuit.public_inputs.indices();
+    if public_input_indices.len() != public_inputs.len() {
+        return Err(GnarkBackendError::Error(format!(
+            "expected {} public inputs but got {}",
+            public_input_indices.len(),
+            public_inputs.len()
+        )));
+    }
+
+    let num_witnesses: usize = circuit
+        .current_witness_index
+        .try_into()
+        .map_err(|e: TryFromIntError| GnarkBackendError::Error(e.to_string()))?;
+    let mut values = vec![acvm::FieldElement::zero(); num_witnesses];
+    for (index, value) in public_input_indices.into_iter().zip(public_inputs) {
+        let position: usize = index
+            .checked_sub(1)
+            .ok_or_else(|| GnarkBackendError::Error("witness 0 cannot be public".to_owned()))?
+            .try_into()
+            .map_err(|e: TryFromIntError| GnarkBackendError::Error(e.to_string()))?;
+        let slot = values.get_mut(position).ok_or_else(|| {
+            GnarkBackendError::Error(format!("public input {index} is not a circuit witness"))
+        })?;
+        *slot = *value;
+    }
+
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve_is_embedded_in_keys_and_proofs() {
+        let proof = CurveId::Bls12_377.embed(vec![7, 8]);
+
+        assert_eq!(
+            CurveId::Bls12_377
+                .strip(&proof, GnarkBackendError::DeserializeProofError)
+                .unwrap(),
+            [7, 8]
+        );
+        assert!(matches!(
+            CurveId::Bw6_761.strip(&proof, GnarkBackendError::DeserializeProofError),
+            Err(GnarkBackendError::DeserializeProofError(message))
+                if message == "made over bls12_377 but expected bw6_761"
+        ));
+        assert!(matches!(
+            CurveId::Bn254.strip(&[], GnarkBackendError::DeserializeKeyError),
+            Err(GnarkBackendError::DeserializeKeyError(_))
+        ));
+        assert!(CurveId::Bn254
+            .strip(&[0, 7], GnarkBackendError::DeserializeKeyError)
+            .is_err());
+    }
+
+    #[test]
+    fn test_from_felt_over_several_scalar_fields() {
+        fn assert_lifts<F: PrimeField>() {
+            let felt = acvm::FieldElement::from(0x1234_u128);
+            assert_eq!(from_felt::<F>(felt).unwrap(), F::from(0x1234_u64));
+            assert_eq!(from_felt::<F>(-felt).unwrap(), -F::from(0x1234_u64));
+            assert_eq!(
+                from_felt::<F>(-acvm::FieldElement::one()).unwrap(),
+                -F::one()
+            );
+        }
+
+        assert_lifts::<ark_bn254::Fr>();
+        assert_lifts::<ark_bls12_381::Fr>();
+        assert_lifts::<fields::Bls12_377Fr>();
+        assert_lifts::<fields::Bw6_761Fr>();
+    }
+
+    #[test]
+    fn test_from_felt_fails_if_the_felt_does_not_fit() {
+        // 2^252 is larger than half the order of BLS12-377's scalar field, but
+        // not of BW6-761's.
+        let felt = acvm::FieldElement::from(2_u128).pow(&acvm::FieldElement::from(252_u128));
+
+        assert!(matches!(
+            from_felt::<fields::Bls12_377Fr>(felt),
+            Err(GnarkBackendError::SerializeFeltError(_))
+        ));
+        assert!(matches!(
+            from_felt::<fields::Bls12_377Fr>(-felt),
+            Err(GnarkBackendError::SerializeFeltError(_))
+        ));
+        assert_eq!(
+            from_felt::<fields::Bw6_761Fr>(-felt).unwrap(),
+            -ark_ff::Field::pow(&fields::Bw6_761Fr::from(2_u64), [252])
+        );
+    }
+
+    #[test]
+    fn test_encode_felts_is_as_wide_as_the_field() {
+        let felts = vec![acvm::FieldElement::one(), -acvm::FieldElement::one()];
+
+        // A big endian u32 count followed by the felts, hex encoded.
+        assert_eq!(
+            encode_felts(CurveId::Bls12_377, felts.clone())
+                .unwrap()
+                .len(),
+            2 * (4 + 2 * 32)
+        );
+        assert_eq!(
+            encode_felts(CurveId::Bw6_761, felts).unwrap().len(),
+            2 * (4 + 2 * 48)
+        );
+    }
+}
@@ -0,0 +1,431 @@
+use crate::acvm;
+use crate::gnark_backend_wrapper::errors::GnarkBackendError;
+use std::num::TryFromIntError;
+
+// gnark's PLONK works with sparse R1CS gates of the form
+//
+//     qL⋅xa + qR⋅xb + qO⋅xc + qM⋅(xa⋅xb) + qC == 0
+//
+// so an arithmetic opcode fits in one of them only if it has at most one
+// multiplication term and its linear terms involve at most three witnesses,
+// two of which must be the operands of the multiplication when there is one.
+// Wider opcodes are split before the circuit is sent over FFI by moving the
+// extra terms into fresh intermediate witnesses.
+
+/// A circuit whose arithmetic opcodes fit in a single sparse R1CS gate each.
+pub struct DecomposedCircuit {
+    pub circuit: acvm::Circuit,
+    num_original_witnesses: u32,
+    // The intermediate witnesses in ascending order, each one with the
+    // expression whose value it holds.
+    intermediates: Vec<(acvm::Witness, acvm::Expression)>,
+}
+
+impl DecomposedCircuit {
+    pub fn new(circuit: &acvm::Circuit) -> Self {
+        let mut decomposer = Decomposer {
+            current_witness_index: circuit.current_witness_index,
+            opcodes: Vec::new(),
+            intermediates: Vec::new(),
+        };
+
+        for opcode in &circuit.opcodes {
+            match opcode {
+                acvm::Opcode::Arithmetic(expression) => decomposer.decompose(expression),
+                _ => decomposer.opcodes.push(opcode.clone()),
+            }
+        }
+
+        Self {
+            circuit: acvm::Circuit {
+                current_witness_index: decomposer.current_witness_index,
+                opcodes: decomposer.opcodes,
+                public_inputs: circuit.public_inputs.clone(),
+            },
+            num_original_witnesses: circuit.current_witness_index,
+            intermediates: decomposer.intermediates,
+        }
+    }
+
+    /// Extends the values of the original circuit's witnesses with the values
+    /// of the intermediate witnesses.
+    pub fn solve(
+        &self,
+        mut values: Vec<acvm::FieldElement>,
+    ) -> Result<Vec<acvm::FieldElement>, GnarkBackendError> {
+        self.check_num_values(&values)?;
+        for (_, expression) in &self.intermediates {
+            let value = evaluate(expression, &values)?;
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// Extends the values of the original circuit's witnesses with zeros.
+    /// Intermediate witnesses are never public, so this is enough to verify.
+    pub fn pad(
+        &self,
+        mut values: Vec<acvm::FieldElement>,
+    ) -> Result<Vec<acvm::FieldElement>, GnarkBackendError> {
+        self.check_num_values(&values)?;
+        let num_witnesses: usize = self
+            .circuit
+            .current_witness_index
+            .try_into()
+            .map_err(|e: TryFromIntError| GnarkBackendError::Error(e.to_string()))?;
+        values.resize(num_witnesses, acvm::FieldElement::zero());
+        Ok(values)
+    }
+
+    fn check_num_values(&self, values: &[acvm::FieldElement]) -> Result<(), GnarkBackendError> {
+        let num_original_witnesses: usize = self
+            .num_original_witnesses
+            .try_into()
+            .map_err(|e: TryFromIntError| GnarkBackendError::Error(e.to_string()))?;
+        if values.len() != num_original_witnesses {
+            return Err(GnarkBackendError::Error(format!(
+                "expected {} values but got {}",
+                num_original_witnesses,
+                values.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// The number of sparse R1CS gates `expression` is split into, that is the
+/// gate it is left with and one per intermediate witness.
+pub fn num_gates(expression: &acvm::Expression) -> usize {
+    // The intermediate witnesses must not be confused with the ones of the
+    // expression.
+    let current_witness_index = expression
+        .mul_terms
+        .iter()
+        .flat_map(|&(_, multiplicand, multiplier)| [multiplicand, multiplier])
+        .chain(
+            expression
+                .linear_combinations
+                .iter()
+                .map(|&(_, witness)| witness),
+        )
+        .map(|witness| witness.0)
+        .max()
+        .unwrap_or_default();
+    let mut decomposer = Decomposer {
+        current_witness_index,
+        opcodes: Vec::new(),
+        intermediates: Vec::new(),
+    };
+    decomposer.decompose(expression);
+    decomposer.opcodes.len()
+}
+
+struct Decomposer {
+    current_witness_index: u32,
+    opcodes: Vec<acvm::Opcode>,
+    intermediates: Vec<(acvm::Witness, acvm::Expression)>,
+}
+
+impl Decomposer {
+    fn decompose(&mut self, expression: &acvm::Expression) {
+        let mut mul_terms = expression
+            .mul_terms
+            .iter()
+            .copied()
+            .filter(|(coefficient, _, _)| !coefficient.is_zero());
+
+        // The first multiplication term stays in the gate, together with the
+        // linear terms of its operands.
+        let kept_mul_term = mul_terms.next();
+        let operands: Vec<acvm::Witness> = kept_mul_term
+            .map(|(_, multiplicand, multiplier)| vec![multiplicand, multiplier])
+            .unwrap_or_default();
+        let (mut linear_terms, mut free_terms): (Vec<_>, Vec<_>) =
+            merge_linear_terms(&expression.linear_combinations)
+                .into_iter()
+                .partition(|(_, witness)| operands.contains(witness));
+
+        // Every other product goes into its own gate, which also takes the
+        // linear terms of its operands.
+        for (coefficient, multiplicand, multiplier) in mul_terms {
+            let (product_linear_terms, rest): (Vec<_>, Vec<_>) = free_terms
+                .into_iter()
+                .partition(|(_, witness)| *witness == multiplicand || *witness == multiplier);
+            free_terms = rest;
+            let product = self.intermediate(acvm::Expression {
+                mul_terms: vec![(coefficient, multiplicand, multiplier)],
+                linear_combinations: product_linear_terms,
+                q_c: acvm::FieldElement::zero(),
+            });
+            free_terms.push((acvm::FieldElement::one(), product));
+        }
+
+        // The gate has room for one more witness next to a product, or three
+        // without one. Pairs of terms are summed up until the rest fit.
+        let capacity = if kept_mul_term.is_some() { 1 } else { 3 };
+        while free_terms.len() > capacity {
+            let sum = self.intermediate(acvm::Expression {
+                mul_terms: Vec::new(),
+                linear_combinations: free_terms.drain(..2).collect(),
+                q_c: acvm::FieldElement::zero(),
+            });
+            free_terms.push((acvm::FieldElement::one(), sum));
+        }
+
+        linear_terms.append(&mut free_terms);
+        self.opcodes
+            .push(acvm::Opcode::Arithmetic(acvm::Expression {
+                mul_terms: kept_mul_term.into_iter().collect(),
+                linear_combinations: linear_terms,
+                q_c: expression.q_c,
+            }));
+    }
+
+    // Adds a fresh witness and a gate constraining it to the value of
+    // `expression`.
+    fn intermediate(&mut self, expression: acvm::Expression) -> acvm::Witness {
+        self.current_witness_index += 1;
+        let witness = acvm::Witness(self.current_witness_index);
+
+        let mut gate = expression.clone();
+        gate.linear_combinations
+            .push((-acvm::FieldElement::one(), witness));
+        self.opcodes.push(acvm::Opcode::Arithmetic(gate));
+        self.intermediates.push((witness, expression));
+
+        witness
+    }
+}
+
+// Adds up the coefficients of repeated witnesses and drops the null terms.
+fn merge_linear_terms(
+    linear_terms: &[(acvm::FieldElement, acvm::Witness)],
+) -> Vec<(acvm::FieldElement, acvm::Witness)> {
+    let mut merged: Vec<(acvm::FieldElement, acvm::Witness)> = Vec::new();
+    for &(coefficient, witness) in linear_terms {
+        match merged
+            .iter_mut()
+            .find(|(_, merged_witness)| *merged_witness == witness)
+        {
+            Some((merged_coefficient, _)) => *merged_coefficient += coefficient,
+            None => merged.push((coefficient, witness)),
+        }
+    }
+    merged.retain(|(coefficient, _)| !coefficient.is_zero());
+    merged
+}
+
+// `values` holds the value of witness `i` at position `i - 1`.
+fn evaluate(
+    expression: &acvm::Expression,
+    values: &[acvm::FieldElement],
+) -> Result<acvm::FieldElement, GnarkBackendError> {
+    let value_of = |witness: acvm::Witness| {
+        let position: usize = witness
+            .0
+            .checked_sub(1)
+            .ok_or_else(|| GnarkBackendError::Error("witness 0 has no value".to_owned()))?
+            .try_into()
+            .map_err(|e: TryFromIntError| GnarkBackendError::Error(e.to_string()))?;
+        values
+            .get(position)
+            .copied()
+            .ok_or_else(|| GnarkBackendError::Error(format!("witness {} has no value", witness.0)))
+    };
+
+    let mut result = expression.q_c;
+    for &(coefficient, multiplicand, multiplier) in &expression.mul_terms {
+        result += coefficient * value_of(multiplicand)? * value_of(multiplier)?;
+    }
+    for &(coefficient, witness) in &expression.linear_combinations {
+        result += coefficient * value_of(witness)?;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    const NUM_WITNESSES: u32 = 6;
+
+    fn random_felt() -> acvm::FieldElement {
+        acvm::FieldElement::from(rand::random::<u128>())
+    }
+
+    fn random_witness() -> acvm::Witness {
+        acvm::Witness(rand::thread_rng().gen_range(1..=NUM_WITNESSES))
+    }
+
+    fn random_expression() -> acvm::Expression {
+        let mut rng = rand::thread_rng();
+        acvm::Expression {
+            mul_terms: (0..rng.gen_range(0..5))
+                .map(|_| (random_felt(), random_witness(), random_witness()))
+                .collect(),
+            linear_combinations: (0..rng.gen_range(0..8))
+                .map(|_| (random_felt(), random_witness()))
+                .collect(),
+            q_c: random_felt(),
+        }
+    }
+
+    fn circuit(expression: acvm::Expression) -> acvm::Circuit {
+        acvm::Circuit {
+            current_witness_index: NUM_WITNESSES,
+            opcodes: vec![acvm::Opcode::Arithmetic(expression)],
+            public_inputs: acvm::PublicInputs::default(),
+        }
+    }
+
+    fn is_satisfied(circuit: &acvm::Circuit, values: &[acvm::FieldElement]) -> bool {
+        circuit.opcodes.iter().all(|opcode| match opcode {
+            acvm::Opcode::Arithmetic(expression) => evaluate(expression, values).unwrap().is_zero(),
+            _ => true,
+        })
+    }
+
+    fn fits_in_a_gate(expression: &acvm::Expression) -> bool {
+        let operands: Vec<acvm::Witness> = expression
+            .mul_terms
+            .iter()
+            .flat_map(|&(_, multiplicand, multiplier)| [multiplicand, multiplier])
+            .collect();
+        let mut free_witnesses: Vec<acvm::Witness> = expression
+            .linear_combinations
+            .iter()
+            .map(|&(_, witness)| witness)
+            .filter(|witness| !operands.contains(witness))
+            .collect();
+        free_witnesses.dedup();
+        let capacity = if operands.is_empty() { 3 } else { 1 };
+        expression.mul_terms.len() <= 1 && free_witnesses.len() <= capacity
+    }
+
+    #[test]
+    fn test_decomposed_opcodes_fit_in_a_gate() {
+        for _ in 0..100_u32 {
+            let decomposed = DecomposedCircuit::new(&circuit(random_expression()));
+            for opcode in &decomposed.circuit.opcodes {
+                let expression = opcode.clone().arithmetic().unwrap();
+                assert!(fits_in_a_gate(&expression), "{expression:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_num_gates_counts_the_decomposed_opcodes() {
+        for _ in 0..100_u32 {
+            let expression = random_expression();
+            let decomposed = DecomposedCircuit::new(&circuit(expression.clone()));
+            assert_eq!(num_gates(&expression), decomposed.circuit.opcodes.len());
+        }
+
+        // x⋅y + z⋅w + u⋅v: the gate keeps x⋅y, the other products get one
+        // gate each and their sum one more.
+        let expression = acvm::Expression {
+            mul_terms: (1..=NUM_WITNESSES)
+                .step_by(2)
+                .map(|x| {
+                    (
+                        acvm::FieldElement::one(),
+                        acvm::Witness(x),
+                        acvm::Witness(x + 1),
+                    )
+                })
+                .collect(),
+            linear_combinations: Vec::new(),
+            q_c: acvm::FieldElement::zero(),
+        };
+        assert_eq!(num_gates(&expression), 4);
+    }
+
+    #[test]
+    fn test_narrow_opcodes_are_not_decomposed() {
+        let expression = acvm::Expression {
+            mul_terms: vec![(random_felt(), acvm::Witness(1), acvm::Witness(2))],
+            linear_combinations: vec![
+                (random_felt(), acvm::Witness(1)),
+                (random_felt(), acvm::Witness(2)),
+                (random_felt(), acvm::Witness(3)),
+            ],
+            q_c: random_felt(),
+        };
+
+        let decomposed = DecomposedCircuit::new(&circuit(expression.clone()));
+
+        assert_eq!(decomposed.circuit.current_witness_index, NUM_WITNESSES);
+        assert_eq!(
+            decomposed.circuit.opcodes,
+            vec![acvm::Opcode::Arithmetic(expression)]
+        );
+    }
+
+    #[test]
+    fn test_decomposed_circuit_is_satisfied_exactly_when_the_original_is() {
+        for _ in 0..100_u32 {
+            let mut expression = random_expression();
+            let values: Vec<acvm::FieldElement> =
+                (0..NUM_WITNESSES).map(|_| random_felt()).collect();
+            // Shift the constant term so that the values satisfy the expression.
+            expression.q_c -= evaluate(&expression, &values).unwrap();
+
+            let satisfied_circuit = circuit(expression.clone());
+            let decomposed = DecomposedCircuit::new(&satisfied_circuit);
+            let solved_values = decomposed.solve(values.clone()).unwrap();
+            assert!(is_satisfied(&satisfied_circuit, &values));
+            assert!(is_satisfied(&decomposed.circuit, &solved_values));
+
+            expression.q_c += acvm::FieldElement::one();
+            let unsatisfied_circuit = circuit(expression);
+            let decomposed = DecomposedCircuit::new(&unsatisfied_circuit);
+            let solved_values = decomposed.solve(values.clone()).unwrap();
+            assert!(!is_satisfied(&unsatisfied_circuit, &values));
+            assert!(!is_satisfied(&decomposed.circuit, &solved_values));
+        }
+    }
+
+    #[test]
+    fn test_intermediate_witnesses_are_constrained() {
+        let expression = acvm::Expression {
+            mul_terms: vec![
+                (
+                    acvm::FieldElement::one(),
+                    acvm::Witness(1),
+                    acvm::Witness(2),
+                ),
+                (
+                    acvm::FieldElement::one(),
+                    acvm::Witness(3),
+                    acvm::Witness(4),
+                ),
+            ],
+            linear_combinations: vec![(-acvm::FieldElement::one(), acvm::Witness(5))],
+            q_c: acvm::FieldElement::zero(),
+        };
+        // 2⋅3 + 4⋅5 - 26 == 0
+        let values: Vec<acvm::FieldElement> = [2_u128, 3, 4, 5, 26, 0]
+            .into_iter()
+            .map(acvm::FieldElement::from)
+            .collect();
+
+        let decomposed = DecomposedCircuit::new(&circuit(expression));
+        let mut solved_values = decomposed.solve(values).unwrap();
+        assert!(solved_values.len() > NUM_WITNESSES.try_into().unwrap());
+        assert!(is_satisfied(&decomposed.circuit, &solved_values));
+
+        // No other value of an intermediate witness satisfies the circuit.
+        let intermediate = solved_values.last_mut().unwrap();
+        *intermediate += acvm::FieldElement::one();
+        assert!(!is_satisfied(&decomposed.circuit, &solved_values));
+    }
+
+    #[test]
+    fn test_solve_fails_with_the_wrong_number_of_values() {
+        let decomposed = DecomposedCircuit::new(&circuit(random_expression()));
+        let values = vec![acvm::FieldElement::zero(); 2];
+
+        assert!(decomposed.solve(values).is_err());
+    }
+}
@@ -1,75 +1,115 @@
-use super::serialize;
-use super::{from_felt, num_constraints, serialize::serialize_felts};
+use super::{encode_felts, public_inputs_to_values};
 use crate::acvm;
-use crate::gnark_backend_wrapper::c_go_structures::{GoString, KeyPair};
+use crate::gnark_backend_wrapper::c_go_structures::{
+    GoString, KeyPair, ProofResult, SizeResult, VerifyResult,
+};
 use crate::gnark_backend_wrapper::errors::GnarkBackendError;
-use std::ffi::{CStr, CString};
+use crate::gnark_backend_wrapper::{curve_c_str, CurveId, Srs};
+use std::ffi::CString;
 use std::num::TryFromIntError;
-use std::os::raw::{c_char, c_uchar};
+use std::os::raw::c_ulonglong;
+
+mod decompose;
+pub use decompose::num_gates;
+use decompose::DecomposedCircuit;
 
 extern "C" {
-    fn PlonkVerifyWithMeta(acir: GoString, encoded_values: GoString, proof: GoString) -> c_uchar;
-    fn PlonkProveWithMeta(acir: GoString, encoded_values: GoString) -> *const c_char;
+    fn PlonkVerifyWithMeta(
+        curve: GoString,
+        acir: GoString,
+        encoded_values: GoString,
+        proof: GoString,
+        srs: c_ulonglong,
+    ) -> VerifyResult;
+    fn PlonkProveWithMeta(
+        curve: GoString,
+        acir: GoString,
+        encoded_values: GoString,
+        srs: c_ulonglong,
+    ) -> ProofResult;
     fn PlonkVerifyWithVK(
+        curve: GoString,
         acir: GoString,
         proof: GoString,
         public_inputs: GoString,
         verifying_key: GoString,
-    ) -> c_uchar;
+        srs: c_ulonglong,
+    ) -> VerifyResult;
     fn PlonkProveWithPK(
+        curve: GoString,
         acir: GoString,
         encoded_values: GoString,
         proving_key: GoString,
-    ) -> *const c_char;
-    fn PlonkPreprocess(acir: GoString, encoded_random_values: GoString) -> KeyPair;
+        srs: c_ulonglong,
+    ) -> ProofResult;
+    fn PlonkGetExactCircuitSize(curve: GoString, acir: GoString) -> SizeResult;
+    fn PlonkRequiredSRSSize(curve: GoString, acir: GoString) -> SizeResult;
+    fn PlonkPreprocess(
+        curve: GoString,
+        acir: GoString,
+        encoded_random_values: GoString,
+        srs: c_ulonglong,
+    ) -> KeyPair;
 }
 
 pub fn prove_with_meta(
+    curve: CurveId,
     circuit: acvm::Circuit,
     values: Vec<acvm::FieldElement>,
+    srs: &Srs,
 ) -> Result<Vec<u8>, GnarkBackendError> {
+    let curve_c_str = curve_c_str(curve)?;
+    let curve_go_string = GoString::try_from(&curve_c_str)?;
+    let decomposed = DecomposedCircuit::new(&circuit);
+    let values = decomposed.solve(values)?;
+
     // Serialize to json and then convert to GoString
-    let acir_json = serde_json::to_string(&circuit)
+    let acir_json = serde_json::to_string(&decomposed.circuit)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let acir_c_str = CString::new(acir_json)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let acir_go_string = GoString::try_from(&acir_c_str)?;
 
-    let felts: Vec<super::Fr> = values.into_iter().map(from_felt).collect();
-    let felts_serialized: Vec<u8> = Vec::new();
-    let mut serializer = serde_json::Serializer::new(felts_serialized);
-    serialize_felts(&felts, &mut serializer)
-        .map_err(|e| GnarkBackendError::SerializeFeltsError(e.to_string()))?;
-    let encoded_felts = String::from_utf8(serializer.into_inner())
-        .map_err(|e| GnarkBackendError::SerializeFeltsError(e.to_string()))?;
+    let encoded_felts = encode_felts(curve, values)?;
     let felts_c_str = CString::new(encoded_felts)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let values_go_string = GoString::try_from(&felts_c_str)?;
 
-    let result: *const c_char = unsafe { PlonkProveWithMeta(acir_go_string, values_go_string) };
-    let c_str = unsafe { CStr::from_ptr(result) };
-    let bytes = c_str
-        .to_str()
-        .map_err(|e| GnarkBackendError::DeserializeProofError(e.to_string()))?
-        .as_bytes();
+    let result: ProofResult = unsafe {
+        PlonkProveWithMeta(
+            curve_go_string,
+            acir_go_string,
+            values_go_string,
+            srs.handle(),
+        )
+    };
+    let proof_str = result.into_proof()?;
+    let decoded_proof = hex::decode(proof_str)
+        .map_err(|e| GnarkBackendError::DeserializeProofError(e.to_string()))?;
 
-    Ok(bytes.to_vec())
+    Ok(decoded_proof)
 }
 
 pub fn prove_with_pk(
+    curve: CurveId,
     circuit: &acvm::Circuit,
     values: Vec<acvm::FieldElement>,
     proving_key: &[u8],
+    srs: &Srs,
 ) -> Result<Vec<u8>, GnarkBackendError> {
+    let curve_c_str = curve_c_str(curve)?;
+    let curve_go_string = GoString::try_from(&curve_c_str)?;
+    let decomposed = DecomposedCircuit::new(circuit);
+    let values = decomposed.solve(values)?;
+
     // Serialize to json and then convert to GoString
-    let acir_json = serde_json::to_string(&circuit)
+    let acir_json = serde_json::to_string(&decomposed.circuit)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let acir_c_str = CString::new(acir_json)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let acir_go_string = GoString::try_from(&acir_c_str)?;
 
-    let felts: Vec<super::Fr> = values.into_iter().map(from_felt).collect();
-    let encoded_felts = serialize::encode_felts(&felts)?;
+    let encoded_felts = encode_felts(curve, values)?;
     let felts_c_str = CString::new(encoded_felts)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let values_go_string = GoString::try_from(&felts_c_str)?;
@@ -80,12 +120,16 @@ pub fn prove_with_pk(
     let proving_key_go_string = GoString::try_from(&proving_key_c_str)
         .map_err(|e| GnarkBackendError::SerializeKeyError(e.to_string()))?;
 
-    let proof: *const c_char =
-        unsafe { PlonkProveWithPK(acir_go_string, values_go_string, proving_key_go_string) };
-    let proof_c_str = unsafe { CStr::from_ptr(proof) };
-    let proof_str = proof_c_str
-        .to_str()
-        .map_err(|e| GnarkBackendError::DeserializeProofError(e.to_string()))?;
+    let result: ProofResult = unsafe {
+        PlonkProveWithPK(
+            curve_go_string,
+            acir_go_string,
+            values_go_string,
+            proving_key_go_string,
+            srs.handle(),
+        )
+    };
+    let proof_str = result.into_proof()?;
     let decoded_proof = hex::decode(proof_str)
         .map_err(|e| GnarkBackendError::DeserializeProofError(e.to_string()))?;
 
@@ -93,57 +137,67 @@ pub fn prove_with_pk(
 }
 
 pub fn verify_with_meta(
+    curve: CurveId,
     circuit: acvm::Circuit,
     proof: &[u8],
     public_inputs: &[acvm::FieldElement],
+    srs: &Srs,
 ) -> Result<bool, GnarkBackendError> {
+    let curve_c_str = curve_c_str(curve)?;
+    let curve_go_string = GoString::try_from(&curve_c_str)?;
+    let decomposed = DecomposedCircuit::new(&circuit);
+    let values = public_inputs_to_values(&decomposed.circuit, public_inputs)?;
+
     // Serialize to json and then convert to GoString
-    let acir_json = serde_json::to_string(&circuit)
+    let acir_json = serde_json::to_string(&decomposed.circuit)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let acir_c_str = CString::new(acir_json)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let acir_go_string = GoString::try_from(&acir_c_str)?;
 
-    let felts: Vec<super::Fr> = public_inputs.iter().cloned().map(from_felt).collect();
-    let felts_serialized: Vec<u8> = Vec::new();
-    let mut serializer = serde_json::Serializer::new(felts_serialized);
-    serialize_felts(&felts, &mut serializer)
-        .map_err(|e| GnarkBackendError::SerializeFeltsError(e.to_string()))?;
-    let encoded_felts = String::from_utf8(serializer.into_inner())
-        .map_err(|e| GnarkBackendError::SerializeFeltsError(e.to_string()))?;
+    let encoded_felts = encode_felts(curve, values)?;
     let felts_c_str = CString::new(encoded_felts)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let values_go_string = GoString::try_from(&felts_c_str)?;
 
-    let serialized_proof = String::from_utf8(proof.to_vec())
-        .map_err(|e| GnarkBackendError::SerializeProofError(e.to_string()))?;
-    let c_str = CString::new(serialized_proof)
+    let proof_serialized = hex::encode(proof);
+    let proof_c_str = CString::new(proof_serialized)
         .map_err(|e| GnarkBackendError::SerializeProofError(e.to_string()))?;
-    let go_string_proof = GoString::try_from(&c_str)?;
+    let proof_go_string = GoString::try_from(&proof_c_str)?;
 
-    let result = unsafe { PlonkVerifyWithMeta(acir_go_string, values_go_string, go_string_proof) };
-    match result {
-        0 => Ok(false),
-        1 => Ok(true),
-        _ => Err(GnarkBackendError::VerifyInvalidBoolError),
-    }
+    let result: VerifyResult = unsafe {
+        PlonkVerifyWithMeta(
+            curve_go_string,
+            acir_go_string,
+            values_go_string,
+            proof_go_string,
+            srs.handle(),
+        )
+    };
+    result.into_verifies()
 }
 
 pub fn verify_with_vk(
+    curve: CurveId,
     circuit: &acvm::Circuit,
     proof: &[u8],
     public_inputs: &[acvm::FieldElement],
     verifying_key: &[u8],
+    srs: &Srs,
 ) -> Result<bool, GnarkBackendError> {
+    let curve_c_str = curve_c_str(curve)?;
+    let curve_go_string = GoString::try_from(&curve_c_str)?;
+    let decomposed = DecomposedCircuit::new(circuit);
+    let public_inputs = decomposed.pad(public_inputs.to_vec())?;
+
     // Serialize to json and then convert to GoString
-    let acir_json = serde_json::to_string(&circuit)
+    let acir_json = serde_json::to_string(&decomposed.circuit)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let acir_c_str = CString::new(acir_json)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let acir_go_string = GoString::try_from(&acir_c_str)?;
 
-    let felts: Vec<super::Fr> = public_inputs.iter().cloned().map(from_felt).collect();
-    let encoded_felts = serialize::encode_felts(&felts)?;
+    let encoded_felts = encode_felts(curve, public_inputs)?;
     let felts_c_str = CString::new(encoded_felts)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let public_inputs_go_string = GoString::try_from(&felts_c_str)?;
@@ -158,29 +212,71 @@ pub fn verify_with_vk(
         .map_err(|e| GnarkBackendError::SerializeKeyError(e.to_string()))?;
     let verifying_key_go_string = GoString::try_from(&verifying_key_c_str)?;
 
-    let verifies = unsafe {
+    let result: VerifyResult = unsafe {
         PlonkVerifyWithVK(
+            curve_go_string,
             acir_go_string,
             proof_go_string,
             public_inputs_go_string,
             verifying_key_go_string,
+            srs.handle(),
         )
     };
-    match verifies {
-        0 => Ok(false),
-        1 => Ok(true),
-        _ => Err(GnarkBackendError::VerifyInvalidBoolError),
-    }
+    result.into_verifies()
 }
 
-pub fn get_exact_circuit_size(circuit: &acvm::Circuit) -> Result<u32, GnarkBackendError> {
-    let size: u32 = num_constraints(circuit)?
-        .try_into()
-        .map_err(|e: TryFromIntError| GnarkBackendError::Error(e.to_string()))?;
-    Ok(size)
+// The size is the number of constraints of the sparse R1CS that the Go side
+// builds when proving, so it accounts for the decomposition of wide opcodes
+// and the constraints of the gadgets.
+pub fn get_exact_circuit_size(
+    curve: CurveId,
+    circuit: &acvm::Circuit,
+) -> Result<u32, GnarkBackendError> {
+    let curve_c_str = curve_c_str(curve)?;
+    let curve_go_string = GoString::try_from(&curve_c_str)?;
+    let decomposed = DecomposedCircuit::new(circuit);
+
+    // Serialize to json and then convert to GoString
+    let acir_json = serde_json::to_string(&decomposed.circuit)
+        .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
+    let acir_c_str = CString::new(acir_json)
+        .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
+    let acir_go_string = GoString::try_from(&acir_c_str)?;
+
+    let result: SizeResult = unsafe { PlonkGetExactCircuitSize(curve_go_string, acir_go_string) };
+    result.into_size()
 }
 
-pub fn preprocess(circuit: &acvm::Circuit) -> Result<(Vec<u8>, Vec<u8>), GnarkBackendError> {
+// The number of G1 points `plonk.Setup` needs the SRS to have for the sparse
+// R1CS that the Go side builds, see `get_exact_circuit_size`.
+pub fn required_srs_size(
+    curve: CurveId,
+    circuit: &acvm::Circuit,
+) -> Result<u32, GnarkBackendError> {
+    let curve_c_str = curve_c_str(curve)?;
+    let curve_go_string = GoString::try_from(&curve_c_str)?;
+    let decomposed = DecomposedCircuit::new(circuit);
+
+    // Serialize to json and then convert to GoString
+    let acir_json = serde_json::to_string(&decomposed.circuit)
+        .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
+    let acir_c_str = CString::new(acir_json)
+        .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
+    let acir_go_string = GoString::try_from(&acir_c_str)?;
+
+    let result: SizeResult = unsafe { PlonkRequiredSRSSize(curve_go_string, acir_go_string) };
+    result.into_size()
+}
+
+pub fn preprocess(
+    curve: CurveId,
+    circuit: &acvm::Circuit,
+    srs: &Srs,
+) -> Result<(Vec<u8>, Vec<u8>), GnarkBackendError> {
+    let curve_c_str = curve_c_str(curve)?;
+    let curve_go_string = GoString::try_from(&curve_c_str)?;
+    let decomposed = DecomposedCircuit::new(circuit);
+    let circuit = &decomposed.circuit;
     let num_witnesses: usize = circuit
         .num_vars()
         .try_into()
@@ -193,30 +289,25 @@ pub fn preprocess(circuit: &acvm::Circuit) -> Result<(Vec<u8>, Vec<u8>), GnarkBa
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let acir_go_string = GoString::try_from(&acir_c_str)?;
 
-    let random_values: Vec<super::Fr> = vec![rand::random(); num_witnesses - 1];
-    let serialized_random_values: Vec<u8> = Vec::new();
-    let mut serializer = serde_json::Serializer::new(serialized_random_values);
-    serialize_felts(&random_values, &mut serializer)
-        .map_err(|e| GnarkBackendError::SerializeFeltsError(e.to_string()))?;
-    let encoded_random_values = String::from_utf8(serializer.into_inner())
+    let random_values = vec![acvm::FieldElement::from(rand::random::<u128>()); num_witnesses - 1];
+    let encoded_random_values = serde_json::to_string(&encode_felts(curve, random_values)?)
         .map_err(|e| GnarkBackendError::SerializeFeltsError(e.to_string()))?;
     let random_values_c_str = CString::new(encoded_random_values)
         .map_err(|e| GnarkBackendError::SerializeCircuitError(e.to_string()))?;
     let random_values_go_string = GoString::try_from(&random_values_c_str)?;
 
-    let key_pair: KeyPair = unsafe { PlonkPreprocess(acir_go_string, random_values_go_string) };
+    let key_pair: KeyPair = unsafe {
+        PlonkPreprocess(
+            curve_go_string,
+            acir_go_string,
+            random_values_go_string,
+            srs.handle(),
+        )
+    };
+    let (proving_key_str, verifying_key_str) = key_pair.into_keys()?;
 
-    let proving_key_c_str = unsafe { CStr::from_ptr(key_pair.proving_key) };
-    let proving_key_str = proving_key_c_str
-        .to_str()
-        .map_err(|e| GnarkBackendError::DeserializeKeyError(e.to_string()))?;
     let decoded_proving_key = hex::decode(proving_key_str)
         .map_err(|e| GnarkBackendError::DeserializeKeyError(e.to_string()))?;
-
-    let verifying_key_c_str = unsafe { CStr::from_ptr(key_pair.verifying_key) };
-    let verifying_key_str = verifying_key_c_str
-        .to_str()
-        .map_err(|e| GnarkBackendError::DeserializeKeyError(e.to_string()))?;
     let decoded_verifying_key = hex::decode(verifying_key_str)
         .map_err(|e| GnarkBackendError::DeserializeKeyError(e.to_string()))?;
 
@@ -226,6 +317,7 @@ pub fn preprocess(circuit: &acvm::Circuit) -> Result<(Vec<u8>, Vec<u8>), GnarkBa
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::ffi::CStr;
 
     #[test]
     fn test_go_string_from_cstring() {
@@ -239,7 +331,7 @@ mod tests {
 
     #[test]
     fn get_exact_circuit_size_should_return_zero_with_an_empty_circuit() {
-        let size = get_exact_circuit_size(&acvm::Circuit::default()).unwrap();
+        let size = get_exact_circuit_size(CurveId::Bn254, &acvm::Circuit::default()).unwrap();
         assert_eq!(size, 0);
     }
 }
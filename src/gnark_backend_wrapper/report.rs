@@ -0,0 +1,447 @@
+use crate::acvm;
+use crate::gnark_backend_wrapper::errors::GnarkBackendError;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// How a proving scheme handles the calls to a black box function. The values
+/// are the ones returned by BlackBoxFunctionSupport in
+/// gnark_backend_ffi/backend/support.go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlackBoxFunctionSupport {
+    /// Building a circuit calling the function fails.
+    Unsupported,
+    /// The calls are left out of the circuit, so nothing constrains their
+    /// outputs.
+    Unconstrained,
+    /// A gadget constrains the outputs of the calls.
+    Constrained,
+}
+
+impl TryFrom<u32> for BlackBoxFunctionSupport {
+    type Error = GnarkBackendError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Unsupported),
+            1 => Ok(Self::Unconstrained),
+            2 => Ok(Self::Constrained),
+            _ => Err(GnarkBackendError::Error(format!(
+                "unknown black box function support {value}"
+            ))),
+        }
+    }
+}
+
+/// The name of `func` in serialized ACIR, which the Go side knows the
+/// functions by. It differs from `BlackBoxFunc::name`, which is snake case.
+pub fn acir_name(func: &acvm::BlackBoxFunc) -> Result<String, GnarkBackendError> {
+    match serde_json::to_value(func) {
+        Ok(serde_json::Value::String(name)) => Ok(name),
+        Ok(value) => Err(GnarkBackendError::SerializeCircuitError(format!(
+            "black box function serialized as {value}"
+        ))),
+        Err(e) => Err(GnarkBackendError::SerializeCircuitError(e.to_string())),
+    }
+}
+
+/// Why an opcode would not be proven as expected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpcodeIssue {
+    /// Preprocessing or proving the circuit fails on the call.
+    UnsupportedBlackBoxFunction(String),
+    /// The proof says nothing about the outputs of the call.
+    UnconstrainedBlackBoxFunction(String),
+}
+
+impl OpcodeIssue {
+    /// The name of the black box function called by the opcode.
+    pub fn black_box_function(&self) -> &str {
+        match self {
+            Self::UnsupportedBlackBoxFunction(name) | Self::UnconstrainedBlackBoxFunction(name) => {
+                name
+            }
+        }
+    }
+}
+
+/// The arithmetic opcode involving the most witnesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WidestExpression {
+    pub opcode_index: usize,
+    /// The number of distinct witnesses of the expression.
+    pub width: usize,
+    pub num_mul_terms: usize,
+    pub num_linear_terms: usize,
+}
+
+/// What the proving scheme would make of a circuit, computed without building
+/// its constraint system. See [`super::circuit_report`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CircuitReport {
+    pub num_arithmetic_opcodes: usize,
+    pub num_black_box_func_calls: usize,
+    pub num_directives: usize,
+    /// The number of calls to each black box function the circuit uses.
+    pub black_box_functions: BTreeMap<String, usize>,
+    pub unsupported_black_box_functions: BTreeSet<String>,
+    pub unconstrained_black_box_functions: BTreeSet<String>,
+    pub widest_expression: Option<WidestExpression>,
+    /// The estimated number of constraints of every opcode, `None` for the
+    /// calls to unsupported black box functions.
+    pub estimated_constraints: Vec<Option<usize>>,
+    /// The index of every opcode with an issue, in ascending order.
+    pub issues: Vec<(usize, OpcodeIssue)>,
+}
+
+impl CircuitReport {
+    /// Walks `circuit`, asking `support` how the proving scheme handles each
+    /// black box function it calls. The constraints of the arithmetic opcodes
+    /// are counted by `num_arithmetic_gates` and the ones of the calls to
+    /// constrained functions by `num_gadget_gates`, which is asked once per
+    /// function and shape of the call.
+    pub fn new(
+        circuit: &acvm::Circuit,
+        num_arithmetic_gates: impl Fn(&acvm::Expression) -> usize,
+        mut support: impl FnMut(
+            &acvm::BlackBoxFunc,
+        ) -> Result<BlackBoxFunctionSupport, GnarkBackendError>,
+        mut num_gadget_gates: impl FnMut(&acvm::BlackBoxFuncCall) -> Result<usize, GnarkBackendError>,
+    ) -> Result<Self, GnarkBackendError> {
+        let mut report = Self::default();
+        let mut supports = BTreeMap::new();
+        // The gates of the gadgets do not depend on the witnesses of the call,
+        // only on the number of bits of its inputs and its number of outputs.
+        let mut gadget_gates = BTreeMap::new();
+
+        for (index, opcode) in circuit.opcodes.iter().enumerate() {
+            let estimated_constraints = match opcode {
+                acvm::Opcode::Arithmetic(expression) => {
+                    report.num_arithmetic_opcodes += 1;
+                    report.record_width(index, expression);
+                    Some(num_arithmetic_gates(expression))
+                }
+                acvm::Opcode::BlackBoxFuncCall(func_call) => {
+                    report.num_black_box_func_calls += 1;
+                    let name = acir_name(&func_call.name)?;
+                    *report.black_box_functions.entry(name.clone()).or_default() += 1;
+
+                    let func_support = match supports.get(&name) {
+                        Some(func_support) => *func_support,
+                        None => {
+                            let func_support = support(&func_call.name)?;
+                            supports.insert(name.clone(), func_support);
+                            func_support
+                        }
+                    };
+                    match func_support {
+                        BlackBoxFunctionSupport::Unsupported => {
+                            report.unsupported_black_box_functions.insert(name.clone());
+                            report
+                                .issues
+                                .push((index, OpcodeIssue::UnsupportedBlackBoxFunction(name)));
+                            None
+                        }
+                        BlackBoxFunctionSupport::Unconstrained => {
+                            report
+                                .unconstrained_black_box_functions
+                                .insert(name.clone());
+                            report
+                                .issues
+                                .push((index, OpcodeIssue::UnconstrainedBlackBoxFunction(name)));
+                            Some(0)
+                        }
+                        BlackBoxFunctionSupport::Constrained => {
+                            let shape = (
+                                name,
+                                func_call
+                                    .inputs
+                                    .iter()
+                                    .map(|input| input.num_bits)
+                                    .collect::<Vec<_>>(),
+                                func_call.outputs.len(),
+                            );
+                            let num_gates = match gadget_gates.get(&shape) {
+                                Some(num_gates) => *num_gates,
+                                None => {
+                                    let num_gates = num_gadget_gates(func_call)?;
+                                    gadget_gates.insert(shape, num_gates);
+                                    num_gates
+                                }
+                            };
+                            Some(num_gates)
+                        }
+                    }
+                }
+                // Directives are solved on the Rust side, the opcodes using
+                // their results constrain them.
+                acvm::Opcode::Directive(_) => {
+                    report.num_directives += 1;
+                    Some(0)
+                }
+            };
+            report.estimated_constraints.push(estimated_constraints);
+        }
+
+        Ok(report)
+    }
+
+    /// Whether every opcode would be constrained by the proving scheme.
+    pub fn is_fully_constrained(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// The sum of the estimated constraints, leaving out the calls to
+    /// unsupported black box functions.
+    pub fn num_estimated_constraints(&self) -> usize {
+        self.estimated_constraints.iter().flatten().sum()
+    }
+
+    fn record_width(&mut self, opcode_index: usize, expression: &acvm::Expression) {
+        let mut witnesses = BTreeSet::new();
+        for (_, multiplicand, multiplier) in &expression.mul_terms {
+            witnesses.insert(multiplicand);
+            witnesses.insert(multiplier);
+        }
+        for (_, witness) in &expression.linear_combinations {
+            witnesses.insert(witness);
+        }
+
+        let width = witnesses.len();
+        if self
+            .widest_expression
+            .is_none_or(|widest| width > widest.width)
+        {
+            self.widest_expression = Some(WidestExpression {
+                opcode_index,
+                width,
+                num_mul_terms: expression.mul_terms.len(),
+                num_linear_terms: expression.linear_combinations.len(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::acvm::{BlackBoxFunc, Circuit, Expression, FieldElement, Opcode, Witness};
+    use ::acvm::acir::circuit::directives::Directive;
+    use ::acvm::acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+
+    fn call(name: BlackBoxFunc) -> Opcode {
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+            name,
+            inputs: vec![],
+            outputs: vec![],
+        })
+    }
+
+    // x⋅y + z - w with x = w, so three distinct witnesses.
+    fn expression() -> Expression {
+        Expression {
+            mul_terms: vec![(FieldElement::one(), Witness(1), Witness(2))],
+            linear_combinations: vec![
+                (FieldElement::one(), Witness(3)),
+                (-FieldElement::one(), Witness(1)),
+            ],
+            q_c: FieldElement::zero(),
+        }
+    }
+
+    fn circuit() -> Circuit {
+        Circuit {
+            current_witness_index: 4,
+            opcodes: vec![
+                Opcode::Arithmetic(Expression::default()),
+                call(BlackBoxFunc::SHA256),
+                Opcode::Directive(Directive::Invert {
+                    x: Witness(1),
+                    result: Witness(4),
+                }),
+                Opcode::Arithmetic(expression()),
+                call(BlackBoxFunc::AES),
+                call(BlackBoxFunc::SHA256),
+            ],
+            ..Circuit::default()
+        }
+    }
+
+    fn num_arithmetic_gates(expression: &Expression) -> usize {
+        1 + expression.linear_combinations.len()
+    }
+
+    fn num_gadget_gates(func_call: &BlackBoxFuncCall) -> Result<usize, GnarkBackendError> {
+        match func_call.name {
+            BlackBoxFunc::SHA256 => Ok(100),
+            _ => Ok(200),
+        }
+    }
+
+    fn support(func: &BlackBoxFunc) -> Result<BlackBoxFunctionSupport, GnarkBackendError> {
+        match func {
+            BlackBoxFunc::SHA256 => Ok(BlackBoxFunctionSupport::Unconstrained),
+            BlackBoxFunc::AES => Ok(BlackBoxFunctionSupport::Unsupported),
+            _ => Ok(BlackBoxFunctionSupport::Constrained),
+        }
+    }
+
+    #[test]
+    fn test_circuit_report() {
+        let report =
+            CircuitReport::new(&circuit(), num_arithmetic_gates, support, num_gadget_gates)
+                .unwrap();
+
+        assert_eq!(report.num_arithmetic_opcodes, 2);
+        assert_eq!(report.num_black_box_func_calls, 3);
+        assert_eq!(report.num_directives, 1);
+        assert_eq!(
+            report.black_box_functions,
+            BTreeMap::from([("AES".to_owned(), 1), ("SHA256".to_owned(), 2)])
+        );
+        assert_eq!(
+            report.unsupported_black_box_functions,
+            BTreeSet::from(["AES".to_owned()])
+        );
+        assert_eq!(
+            report.unconstrained_black_box_functions,
+            BTreeSet::from(["SHA256".to_owned()])
+        );
+        assert_eq!(
+            report.widest_expression,
+            Some(WidestExpression {
+                opcode_index: 3,
+                width: 3,
+                num_mul_terms: 1,
+                num_linear_terms: 2,
+            })
+        );
+        assert_eq!(
+            report.estimated_constraints,
+            [Some(1), Some(0), Some(0), Some(3), None, Some(0)]
+        );
+        assert_eq!(report.num_estimated_constraints(), 4);
+        assert_eq!(
+            report.issues,
+            [
+                (
+                    1,
+                    OpcodeIssue::UnconstrainedBlackBoxFunction("SHA256".to_owned())
+                ),
+                (
+                    4,
+                    OpcodeIssue::UnsupportedBlackBoxFunction("AES".to_owned())
+                ),
+                (
+                    5,
+                    OpcodeIssue::UnconstrainedBlackBoxFunction("SHA256".to_owned())
+                ),
+            ]
+        );
+        assert!(!report.is_fully_constrained());
+    }
+
+    #[test]
+    fn test_circuit_report_asks_once_per_function() {
+        let mut asked = Vec::new();
+        let mut sized = Vec::new();
+        let report = CircuitReport::new(
+            &circuit(),
+            num_arithmetic_gates,
+            |func| {
+                asked.push(*func);
+                Ok(BlackBoxFunctionSupport::Constrained)
+            },
+            |func_call| {
+                sized.push(func_call.name);
+                num_gadget_gates(func_call)
+            },
+        )
+        .unwrap();
+
+        assert_eq!(asked, [BlackBoxFunc::SHA256, BlackBoxFunc::AES]);
+        assert_eq!(sized, [BlackBoxFunc::SHA256, BlackBoxFunc::AES]);
+        assert!(report.is_fully_constrained());
+        assert_eq!(
+            report.estimated_constraints,
+            [Some(1), Some(100), Some(0), Some(3), Some(200), Some(100)]
+        );
+        assert_eq!(report.num_estimated_constraints(), 404);
+    }
+
+    #[test]
+    fn test_circuit_report_sizes_every_shape_of_call() {
+        let range = |witness, num_bits| {
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+                name: BlackBoxFunc::RANGE,
+                inputs: vec![FunctionInput {
+                    witness: Witness(witness),
+                    num_bits,
+                }],
+                outputs: vec![],
+            })
+        };
+        let circuit = Circuit {
+            current_witness_index: 3,
+            opcodes: vec![range(1, 8), range(2, 8), range(3, 16)],
+            ..Circuit::default()
+        };
+
+        let mut sized = Vec::new();
+        let report = CircuitReport::new(
+            &circuit,
+            num_arithmetic_gates,
+            |_| Ok(BlackBoxFunctionSupport::Constrained),
+            |func_call| {
+                let [input] = func_call.inputs.as_slice() else {
+                    unreachable!("RANGE takes one input");
+                };
+                sized.push(input.witness);
+                Ok(usize::try_from(input.num_bits).unwrap() + 1)
+            },
+        )
+        .unwrap();
+
+        assert_eq!(sized, [Witness(1), Witness(3)]);
+        assert_eq!(report.estimated_constraints, [Some(9), Some(9), Some(17)]);
+    }
+
+    #[test]
+    fn test_acir_name() {
+        assert_eq!(acir_name(&BlackBoxFunc::AES).unwrap(), "AES");
+        assert_eq!(
+            acir_name(&BlackBoxFunc::HashToField128Security).unwrap(),
+            "HashToField128Security"
+        );
+    }
+
+    #[test]
+    fn test_circuit_report_fails_if_support_fails() {
+        let result = CircuitReport::new(
+            &circuit(),
+            num_arithmetic_gates,
+            |_| {
+                Err(GnarkBackendError::Error(
+                    "unknown proving scheme".to_owned(),
+                ))
+            },
+            num_gadget_gates,
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_circuit_report_fails_if_sizing_a_gadget_fails() {
+        let result = CircuitReport::new(
+            &circuit(),
+            num_arithmetic_gates,
+            |_| Ok(BlackBoxFunctionSupport::Constrained),
+            |_| {
+                Err(GnarkBackendError::Error(
+                    "unknown proving scheme".to_owned(),
+                ))
+            },
+        );
+
+        assert!(result.is_err());
+    }
+}
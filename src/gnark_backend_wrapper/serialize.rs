@@ -1,23 +1,19 @@
 use super::GnarkBackendError;
-use crate::gnark_backend_wrapper as gnark_backend;
-#[cfg(feature = "groth16")]
+use ark_ff::PrimeField;
 use ark_serialize::CanonicalDeserialize;
-use ark_serialize::CanonicalSerialize;
-#[cfg(feature = "groth16")]
 use serde::Deserialize;
 use std::num::TryFromIntError;
 
-pub fn serialize_felt_unchecked(felt: &gnark_backend::Fr) -> Vec<u8> {
+pub fn serialize_felt_unchecked<F: PrimeField>(felt: &F) -> Vec<u8> {
     let mut serialized_felt = Vec::new();
-    #[allow(clippy::unwrap_used)]
+    #[allow(clippy::unwrap_used, reason = "serializing into a Vec can not fail")]
     felt.serialize_uncompressed(&mut serialized_felt).unwrap();
     // Turn little-endian to big-endian.
     serialized_felt.reverse();
     serialized_felt
 }
 
-#[cfg(feature = "groth16")]
-pub fn serialize_felt<S>(felt: &gnark_backend::Fr, serializer: S) -> Result<S::Ok, S::Error>
+pub fn serialize_felt<F: PrimeField, S>(felt: &F, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::ser::Serializer,
 {
@@ -30,7 +26,7 @@ where
     serializer.serialize_str(&encoded_coefficient)
 }
 
-pub fn encode_felts(felts: &[gnark_backend::Fr]) -> Result<String, GnarkBackendError> {
+pub fn encode_felts<F: PrimeField>(felts: &[F]) -> Result<String, GnarkBackendError> {
     let mut buff: Vec<u8> = Vec::new();
     let n_felts: u32 = felts
         .len()
@@ -46,7 +42,7 @@ pub fn encode_felts(felts: &[gnark_backend::Fr]) -> Result<String, GnarkBackendE
     Ok(hex::encode(buff))
 }
 
-pub fn serialize_felts<S>(felts: &[gnark_backend::Fr], serializer: S) -> Result<S::Ok, S::Error>
+pub fn serialize_felts<F: PrimeField, S>(felts: &[F], serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::ser::Serializer,
 {
@@ -54,8 +50,7 @@ where
     serializer.serialize_str(&encoded_buff)
 }
 
-#[cfg(feature = "groth16")]
-pub fn deserialize_felt<'de, D>(deserializer: D) -> Result<gnark_backend::Fr, D::Error>
+pub fn deserialize_felt<'de, F: PrimeField, D>(deserializer: D) -> Result<F, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
@@ -63,12 +58,10 @@ where
     let mut decoded = hex::decode(felt_bytes).map_err(serde::de::Error::custom)?;
     // Turn big-endian to little-endian.
     decoded.reverse();
-    gnark_backend::Fr::deserialize_uncompressed(decoded.as_slice())
-        .map_err(serde::de::Error::custom)
+    F::deserialize_uncompressed(decoded.as_slice()).map_err(serde::de::Error::custom)
 }
 
-#[cfg(feature = "groth16")]
-pub fn deserialize_felts<'de, D>(deserializer: D) -> Result<Vec<gnark_backend::Fr>, D::Error>
+pub fn deserialize_felts<'de, F: PrimeField, D>(deserializer: D) -> Result<Vec<F>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
@@ -85,19 +78,20 @@ where
     )
     .try_into()
     .map_err(serde::de::Error::custom)?;
-    let mut deserialized_felts: Vec<gnark_backend::Fr> = Vec::with_capacity(n_felts);
+    let mut deserialized_felts: Vec<F> = Vec::with_capacity(n_felts);
+    // The felts are as wide as the scalar field of the curve we compile with.
+    let felt_size = F::default().uncompressed_size();
 
     decoded_felts
         .get_mut(4..) // Skip the vector length corresponding to the first four bytes.
         .ok_or_else(|| serde::de::Error::custom("Error getting decoded felts"))?
-        .chunks_mut(32)
+        .chunks_mut(felt_size)
         .try_for_each(|decoded_felt| {
             // Turn big-endian to little-endian.
             decoded_felt.reverse();
             // Here I reference after dereference because I had a mutable reference and I need a non-mutable one.
-            let felt: gnark_backend::Fr =
-                CanonicalDeserialize::deserialize_uncompressed(&*decoded_felt)
-                    .map_err(serde::de::Error::custom)?;
+            let felt: F = CanonicalDeserialize::deserialize_uncompressed(&*decoded_felt)
+                .map_err(serde::de::Error::custom)?;
             deserialized_felts.push(felt);
             Ok::<(), D::Error>(())
         })?;
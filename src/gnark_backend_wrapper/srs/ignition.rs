@@ -0,0 +1,243 @@
+// Reads the powers of τ of the transcripts of the Aztec Ignition ceremony,
+// `transcript00.dat` to `transcript19.dat`.
+//
+// Every transcript starts with a manifest of 7 big-endian u32, followed by its
+// points in G1 and, in the first transcript, by [τ]₂. The coordinates are in
+// regular form, as 4 big-endian u64 limbs from the least significant one. The
+// transcripts start from [τ]₁, [1]₁ being left out.
+
+use super::powers::Powers;
+use crate::gnark_backend_wrapper::GnarkBackendError;
+use ark_bn254::{Fq, Fq2, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInt, PrimeField};
+use std::io::Read;
+
+const G1_POINT_SIZE: u64 = 2 * 32;
+
+struct Manifest {
+    transcript_number: u32,
+    total_transcripts: u32,
+    num_g1_points: u32,
+    num_g2_points: u32,
+    start_from: u32,
+}
+
+// Returns the first `num_g1_points` powers of τ in G1 and the first two in G2,
+// without checking them. The transcripts are only read up to the last point
+// needed.
+pub(crate) fn read<R: Read>(
+    transcripts: impl IntoIterator<Item = Result<R, GnarkBackendError>>,
+    num_g1_points: usize,
+) -> Result<Powers, GnarkBackendError> {
+    let mut g1 = Vec::with_capacity(num_g1_points);
+    g1.push(G1Affine::generator());
+    let mut tau_g2 = None;
+
+    for (number, transcript) in (0..).zip(transcripts) {
+        if g1.len() >= num_g1_points && tau_g2.is_some() {
+            break;
+        }
+        let mut transcript = transcript?;
+        let manifest = read_manifest(&mut transcript)?;
+        if manifest.transcript_number != number {
+            return Err(GnarkBackendError::SRSError(format!(
+                "expected transcript {number} but got transcript {}",
+                manifest.transcript_number
+            )));
+        }
+        let start_from = usize::try_from(manifest.start_from)
+            .map_err(|e| GnarkBackendError::SRSError(e.to_string()))?;
+        if start_from + 1 != g1.len() {
+            return Err(GnarkBackendError::SRSError(format!(
+                "transcript {number} starts from point {start_from} instead of {}",
+                g1.len() - 1
+            )));
+        }
+
+        let num_points = usize::try_from(manifest.num_g1_points)
+            .map_err(|e| GnarkBackendError::SRSError(e.to_string()))?;
+        let num_read = num_points.min(num_g1_points.saturating_sub(g1.len()));
+        for _ in 0..num_read {
+            let x = read_fq(&mut transcript)?;
+            let y = read_fq(&mut transcript)?;
+            g1.push(G1Affine::new_unchecked(x, y));
+        }
+
+        if number == 0 {
+            if manifest.num_g2_points == 0 {
+                return Err(GnarkBackendError::SRSError(
+                    "the first transcript holds no point in G2".to_owned(),
+                ));
+            }
+            // Skip the points in G1 that are not needed.
+            let skipped = u64::try_from(num_points - num_read)
+                .map_err(|e| GnarkBackendError::SRSError(e.to_string()))?;
+            std::io::copy(
+                &mut transcript.by_ref().take(skipped * G1_POINT_SIZE),
+                &mut std::io::sink(),
+            )
+            .map_err(io_error)?;
+            let x = Fq2::new(read_fq(&mut transcript)?, read_fq(&mut transcript)?);
+            let y = Fq2::new(read_fq(&mut transcript)?, read_fq(&mut transcript)?);
+            tau_g2 = Some(G2Affine::new_unchecked(x, y));
+        }
+
+        if manifest.transcript_number + 1 >= manifest.total_transcripts {
+            break;
+        }
+    }
+
+    if g1.len() < num_g1_points {
+        return Err(GnarkBackendError::SRSError(format!(
+            "the transcripts only hold {} points but {num_g1_points} are needed",
+            g1.len()
+        )));
+    }
+    let tau_g2 = tau_g2
+        .ok_or_else(|| GnarkBackendError::SRSError("missing the first transcript".to_owned()))?;
+
+    Ok(Powers {
+        g1,
+        g2: [G2Affine::generator(), tau_g2],
+    })
+}
+
+fn read_manifest(reader: &mut impl Read) -> Result<Manifest, GnarkBackendError> {
+    let mut read_u32 = || {
+        let mut bytes = [0; 4];
+        reader.read_exact(&mut bytes).map_err(io_error)?;
+        Ok::<_, GnarkBackendError>(u32::from_be_bytes(bytes))
+    };
+    let transcript_number = read_u32()?;
+    let total_transcripts = read_u32()?;
+    let _total_g1_points = read_u32()?;
+    let _total_g2_points = read_u32()?;
+    Ok(Manifest {
+        transcript_number,
+        total_transcripts,
+        num_g1_points: read_u32()?,
+        num_g2_points: read_u32()?,
+        start_from: read_u32()?,
+    })
+}
+
+fn read_fq(reader: &mut impl Read) -> Result<Fq, GnarkBackendError> {
+    let mut limbs = [0; 4];
+    for limb in &mut limbs {
+        let mut bytes = [0; 8];
+        reader.read_exact(&mut bytes).map_err(io_error)?;
+        *limb = u64::from_be_bytes(bytes);
+    }
+    Fq::from_bigint(BigInt::new(limbs)).ok_or_else(|| {
+        GnarkBackendError::SRSError("a coordinate is not a field element".to_owned())
+    })
+}
+
+fn io_error(error: std::io::Error) -> GnarkBackendError {
+    GnarkBackendError::SRSError(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::powers::from_secret;
+    use super::*;
+
+    fn write_fq(bytes: &mut Vec<u8>, element: &Fq) {
+        for limb in element.into_bigint().0 {
+            bytes.extend(limb.to_be_bytes());
+        }
+    }
+
+    // The transcripts of the powers of 5, each holding `points_per_transcript`
+    // points in G1.
+    fn transcripts(num_transcripts: u32, points_per_transcript: u32) -> Vec<Vec<u8>> {
+        let num_points = usize::try_from(num_transcripts * points_per_transcript).unwrap();
+        let powers = from_secret(5, num_points + 1);
+        let mut points = powers
+            .g1
+            .get(1..)
+            .unwrap()
+            .chunks(usize::try_from(points_per_transcript).unwrap());
+
+        (0..num_transcripts)
+            .map(|number| {
+                let num_g2_points = u32::from(number == 0);
+                let mut bytes = Vec::new();
+                for field in [
+                    number,
+                    num_transcripts,
+                    num_transcripts * points_per_transcript,
+                    1,
+                    points_per_transcript,
+                    num_g2_points,
+                    number * points_per_transcript,
+                ] {
+                    bytes.extend(field.to_be_bytes());
+                }
+                for point in points.next().unwrap() {
+                    let (x, y) = point.xy().unwrap();
+                    write_fq(&mut bytes, x);
+                    write_fq(&mut bytes, y);
+                }
+                if number == 0 {
+                    let (x, y) = powers.g2[1].xy().unwrap();
+                    for coordinate in [x.c0, x.c1, y.c0, y.c1] {
+                        write_fq(&mut bytes, &coordinate);
+                    }
+                }
+                bytes
+            })
+            .collect()
+    }
+
+    fn read_transcripts(
+        transcripts: &[Vec<u8>],
+        num_g1_points: usize,
+    ) -> Result<Powers, GnarkBackendError> {
+        read(
+            transcripts.iter().map(|bytes| Ok(bytes.as_slice())),
+            num_g1_points,
+        )
+    }
+
+    #[test]
+    fn test_read_across_transcripts() {
+        let transcripts = transcripts(3, 4);
+
+        let powers = read_transcripts(&transcripts, 10).unwrap();
+
+        let expected = from_secret(5, 10);
+        assert_eq!(powers.g1, expected.g1);
+        assert_eq!(powers.g2, expected.g2);
+        assert!(powers.check().is_ok());
+
+        // The points in G1 of the first transcript are skipped to [τ]₂.
+        let powers = read_transcripts(&transcripts, 2).unwrap();
+        assert_eq!(powers.g1, from_secret(5, 2).g1);
+        assert_eq!(powers.g2, expected.g2);
+    }
+
+    #[test]
+    fn test_read_fails_without_enough_points() {
+        let transcripts = transcripts(3, 4);
+
+        assert!(matches!(
+            read_transcripts(&transcripts, 14),
+            Err(GnarkBackendError::SRSError(message))
+                if message == "the transcripts only hold 13 points but 14 are needed"
+        ));
+    }
+
+    #[test]
+    fn test_read_fails_with_transcripts_out_of_order() {
+        let mut transcripts = transcripts(3, 4);
+        transcripts.swap(1, 2);
+
+        assert!(matches!(
+            read_transcripts(&transcripts, 10),
+            Err(GnarkBackendError::SRSError(message))
+                if message == "expected transcript 1 but got transcript 2"
+        ));
+    }
+}
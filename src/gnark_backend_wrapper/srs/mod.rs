@@ -0,0 +1,274 @@
+use super::c_go_structures::{GoString, SRSHandleResult, SRSResult};
+use super::{curve_c_str, CurveId, GnarkBackendError};
+use std::ffi::CString;
+use std::fs::File;
+use std::io::BufReader;
+use std::os::raw::{c_uint, c_ulonglong};
+use std::path::Path;
+use std::sync::Arc;
+
+mod ignition;
+mod powers;
+mod ptau;
+use powers::Powers;
+
+extern "C" {
+    fn GenerateInsecureSRS(curve: GoString, size: c_uint) -> SRSResult;
+    fn LoadSRS(curve: GoString, encoded_srs: GoString) -> SRSHandleResult;
+    fn SerializeSRS(curve: GoString, handle: c_ulonglong) -> SRSResult;
+    fn FreeSRS(handle: c_ulonglong);
+}
+
+/// A KZG structured reference string, the powers of a secret in G1 and G2
+/// that PLONK commits to polynomials with.
+///
+/// It is parsed once by the Go side, which the PLONK functions only hand its
+/// handle to, and which encodes it back for [`Srs::save`]. The Go side never
+/// reads nor writes one by itself. A circuit needs an SRS of at least
+/// [`required_srs_size`](super::required_srs_size) points.
+#[derive(Debug, Clone)]
+pub struct Srs {
+    curve: CurveId,
+    size: u32,
+    handle: Arc<SrsHandle>,
+}
+
+// The handle of the SRS parsed by the Go side, released once every clone of
+// the Srs is dropped.
+#[derive(Debug)]
+struct SrsHandle(c_ulonglong);
+
+impl Drop for SrsHandle {
+    fn drop(&mut self) {
+        unsafe { FreeSRS(self.0) }
+    }
+}
+
+// Two SRS are the same if they hold the same points, whichever handles they
+// were loaded with. The points are only compared when the handles differ,
+// encoding both SRS on the Go side.
+impl PartialEq for Srs {
+    fn eq(&self, other: &Self) -> bool {
+        self.curve == other.curve
+            && self.size == other.size
+            && (Arc::ptr_eq(&self.handle, &other.handle)
+                || matches!(
+                    (self.to_bytes(), other.to_bytes()),
+                    (Ok(bytes), Ok(other_bytes)) if bytes == other_bytes
+                ))
+    }
+}
+
+impl Eq for Srs {}
+
+impl Srs {
+    /// Reads an SRS over `curve` in gnark's binary encoding, checking that its
+    /// points are valid, and loads it on the Go side.
+    pub fn from_bytes(curve: CurveId, bytes: Vec<u8>) -> Result<Self, GnarkBackendError> {
+        let curve_c_str = curve_c_str(curve)?;
+        let curve_go_string = GoString::try_from(&curve_c_str)?;
+        let srs_c_str = encode(&bytes)?;
+        let srs_go_string = GoString::try_from(&srs_c_str)?;
+
+        let result: SRSHandleResult = unsafe { LoadSRS(curve_go_string, srs_go_string) };
+        let (handle, size) = result.into_handle()?;
+
+        Ok(Self {
+            curve,
+            size,
+            handle: Arc::new(SrsHandle(handle)),
+        })
+    }
+
+    /// Reads an SRS over `curve` written by [`Srs::save`].
+    pub fn load(curve: CurveId, path: impl AsRef<Path>) -> Result<Self, GnarkBackendError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .map_err(|e| GnarkBackendError::SRSError(format!("{}: {e}", path.display())))?;
+        Self::from_bytes(curve, bytes)
+    }
+
+    /// Generates an SRS of `size` points from local randomness.
+    ///
+    /// Whoever generates it may learn the secret it is made of and forge
+    /// proofs, so it must only be used for tests.
+    pub fn insecure_test_srs(curve: CurveId, size: u32) -> Result<Self, GnarkBackendError> {
+        let curve_c_str = curve_c_str(curve)?;
+        let curve_go_string = GoString::try_from(&curve_c_str)?;
+
+        let result: SRSResult = unsafe { GenerateInsecureSRS(curve_go_string, size) };
+        let bytes = hex::decode(result.into_srs()?)
+            .map_err(|e| GnarkBackendError::SRSError(e.to_string()))?;
+
+        Self::from_bytes(curve, bytes)
+    }
+
+    /// Imports the first `size` powers of a BN254 powers of tau file, as
+    /// written by snarkjs for the Perpetual Powers of Tau ceremony, checking
+    /// that they are the powers of the same secret.
+    pub fn import_ptau(path: impl AsRef<Path>, size: u32) -> Result<Self, GnarkBackendError> {
+        let path = path.as_ref();
+        let file = open(path)?;
+        let powers =
+            ptau::read(BufReader::new(file), usize_size(size)?).map_err(|e| in_file(path, e))?;
+        Self::from_powers(powers)
+    }
+
+    /// Imports the first `size` powers of the Aztec Ignition ceremony from
+    /// the transcripts `transcript00.dat`, `transcript01.dat`, ... in `dir`,
+    /// checking that they are the powers of the same secret. Only the
+    /// transcripts holding these powers are read.
+    pub fn import_ignition(dir: impl AsRef<Path>, size: u32) -> Result<Self, GnarkBackendError> {
+        let dir = dir.as_ref();
+        let transcripts = (0_u32..).map(|number| {
+            let path = dir.join(format!("transcript{number:02}.dat"));
+            open(&path).map(BufReader::new)
+        });
+        let powers = ignition::read(transcripts, usize_size(size)?).map_err(|e| in_file(dir, e))?;
+        Self::from_powers(powers)
+    }
+
+    fn from_powers(powers: Powers) -> Result<Self, GnarkBackendError> {
+        powers.check()?;
+        Self::from_bytes(CurveId::Bn254, powers.to_gnark_bytes()?)
+    }
+
+    /// Writes the SRS in gnark's binary encoding, which [`Srs::load`] reads.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), GnarkBackendError> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_bytes()?)
+            .map_err(|e| GnarkBackendError::SRSError(format!("{}: {e}", path.display())))
+    }
+
+    pub fn curve(&self) -> CurveId {
+        self.curve
+    }
+
+    /// The number of G1 points, which bounds the size of the circuits the SRS
+    /// can be used with.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Encodes the SRS in gnark's binary encoding, which [`Srs::from_bytes`]
+    /// reads. The Go side encodes the points it keeps, so this allocates a
+    /// copy of them every time.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, GnarkBackendError> {
+        let curve_c_str = curve_c_str(self.curve)?;
+        let curve_go_string = GoString::try_from(&curve_c_str)?;
+
+        let result: SRSResult = unsafe { SerializeSRS(curve_go_string, self.handle()) };
+        hex::decode(result.into_srs()?).map_err(|e| GnarkBackendError::SRSError(e.to_string()))
+    }
+
+    /// Fails if the SRS has less than `required_size` points.
+    pub fn check_size(&self, required_size: u32) -> Result<(), GnarkBackendError> {
+        if self.size < required_size {
+            return Err(GnarkBackendError::SRSError(format!(
+                "the SRS has {} points but the circuit needs {required_size}",
+                self.size
+            )));
+        }
+        Ok(())
+    }
+
+    // The handle the PLONK functions of the Go side take last.
+    pub(crate) fn handle(&self) -> c_ulonglong {
+        self.handle.0
+    }
+}
+
+fn open(path: &Path) -> Result<File, GnarkBackendError> {
+    File::open(path).map_err(|e| GnarkBackendError::SRSError(format!("{}: {e}", path.display())))
+}
+
+// Prefixes the errors of the importers, which read from readers, with the
+// path they were reading.
+fn in_file(path: &Path, error: GnarkBackendError) -> GnarkBackendError {
+    match error {
+        GnarkBackendError::SRSError(message) if !message.starts_with(&*path.to_string_lossy()) => {
+            GnarkBackendError::SRSError(format!("{}: {message}", path.display()))
+        }
+        error => error,
+    }
+}
+
+fn usize_size(size: u32) -> Result<usize, GnarkBackendError> {
+    usize::try_from(size).map_err(|e| GnarkBackendError::SRSError(e.to_string()))
+}
+
+fn encode(bytes: &[u8]) -> Result<CString, GnarkBackendError> {
+    CString::new(hex::encode(bytes)).map_err(|e| GnarkBackendError::SRSError(e.to_string()))
+}
+
+// Returns the SRS a PLONK function was given, failing if there is none or if
+// it was made over another curve than the circuit.
+pub(crate) fn required_srs(curve: CurveId, srs: Option<&Srs>) -> Result<&Srs, GnarkBackendError> {
+    let srs = srs.ok_or_else(|| {
+        GnarkBackendError::SRSError("PLONK needs an SRS, see Gnark::with_srs".to_owned())
+    })?;
+    if srs.curve != curve {
+        return Err(GnarkBackendError::SRSError(format!(
+            "the SRS is over {} but the circuit over {}",
+            srs.curve.name(),
+            curve.name()
+        )));
+    }
+    Ok(srs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An SRS the Go side never loaded, whose handle 0 refers to none.
+    fn unloaded_srs(curve: CurveId, size: u32) -> Srs {
+        Srs {
+            curve,
+            size,
+            handle: Arc::new(SrsHandle(0)),
+        }
+    }
+
+    #[test]
+    fn test_required_srs_matches_the_curve() {
+        let srs = unloaded_srs(CurveId::Bn254, 8);
+
+        assert!(required_srs(CurveId::Bn254, Some(&srs)).is_ok());
+        assert!(matches!(
+            required_srs(CurveId::Bls12_377, Some(&srs)),
+            Err(GnarkBackendError::SRSError(message))
+                if message == "the SRS is over bn254 but the circuit over bls12_377"
+        ));
+        assert!(matches!(
+            required_srs(CurveId::Bn254, None),
+            Err(GnarkBackendError::SRSError(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_size() {
+        let srs = unloaded_srs(CurveId::Bn254, 8);
+
+        assert!(srs.check_size(8).is_ok());
+        assert!(matches!(
+            srs.check_size(9),
+            Err(GnarkBackendError::SRSError(message))
+                if message == "the SRS has 8 points but the circuit needs 9"
+        ));
+    }
+
+    #[test]
+    fn test_insecure_test_srs_round_trips() {
+        let srs = Srs::insecure_test_srs(CurveId::Bn254, 16).unwrap();
+        assert_eq!(srs.size(), 16);
+
+        let read = Srs::from_bytes(CurveId::Bn254, srs.to_bytes().unwrap()).unwrap();
+        assert_eq!(read, srs);
+
+        assert!(matches!(
+            Srs::from_bytes(CurveId::Bn254, vec![0]),
+            Err(GnarkBackendError::SRSError(_))
+        ));
+    }
+}
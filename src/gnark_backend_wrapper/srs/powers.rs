@@ -0,0 +1,218 @@
+// The powers of a secret τ that a KZG SRS over BN254 is made of, as read from
+// the files of a public trusted setup, and their encoding as gnark-crypto's
+// kzg.SRS.
+
+use crate::gnark_backend_wrapper::GnarkBackendError;
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, VariableBaseMSM};
+use ark_ff::{BigInteger, PrimeField, Zero};
+
+// gnark's flags in the two most significant bits of a compressed point.
+const COMPRESSED_SMALLEST: u8 = 0b10 << 6;
+const COMPRESSED_LARGEST: u8 = 0b11 << 6;
+const COMPRESSED_INFINITY: u8 = 0b01 << 6;
+
+pub(crate) struct Powers {
+    // [τ⁰]₁, [τ¹]₁, [τ²]₁, ...
+    pub g1: Vec<G1Affine>,
+    // [1]₂ and [τ]₂.
+    pub g2: [G2Affine; 2],
+}
+
+impl Powers {
+    // Checks that the points are valid and start from the generators, and
+    // that e([τⁱ⁺¹]₁, [1]₂) = e([τⁱ]₁, [τ]₂) for every i, which holds if and
+    // only if they are the successive powers of the same τ. The equations
+    // are checked at once for a random linear combination of them.
+    pub fn check(&self) -> Result<(), GnarkBackendError> {
+        let [one_g2, tau_g2] = self.g2;
+        if self.g1.first() != Some(&G1Affine::generator()) || one_g2 != G2Affine::generator() {
+            return Err(GnarkBackendError::SRSError(
+                "the powers do not start from the generators".to_owned(),
+            ));
+        }
+        let g1_valid = self
+            .g1
+            .iter()
+            .all(|point| point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve());
+        let g2_valid = tau_g2.is_on_curve() && tau_g2.is_in_correct_subgroup_assuming_on_curve();
+        if !g1_valid || !g2_valid || tau_g2.is_zero() {
+            return Err(GnarkBackendError::SRSError(
+                "a point is not in the group".to_owned(),
+            ));
+        }
+
+        let (Some((_, higher)), Some((_, lower))) = (self.g1.split_first(), self.g1.split_last())
+        else {
+            return Err(GnarkBackendError::SRSError(
+                "the SRS has no points".to_owned(),
+            ));
+        };
+        let coefficients: Vec<Fr> = lower.iter().map(|_| rand::random()).collect();
+        let msm = |bases| {
+            G1Projective::msm(bases, &coefficients)
+                .map_err(|e| GnarkBackendError::SRSError(format!("{e} points left over")))
+        };
+        if Bn254::pairing(msm(higher)?, one_g2) != Bn254::pairing(msm(lower)?, tau_g2) {
+            return Err(GnarkBackendError::SRSError(
+                "the points are not the powers of the same secret".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    // Encodes the points as gnark-crypto v0.9 encodes a kzg.SRS over BN254:
+    // [1]₂ and [τ]₂, then the number of G1 points as a big-endian u32 and the
+    // G1 points, every point being compressed.
+    pub fn to_gnark_bytes(&self) -> Result<Vec<u8>, GnarkBackendError> {
+        let num_g1_points =
+            u32::try_from(self.g1.len()).map_err(|e| GnarkBackendError::SRSError(e.to_string()))?;
+
+        let mut bytes = Vec::with_capacity(2 * 64 + 4 + 32 * self.g1.len());
+        for point in &self.g2 {
+            bytes.extend(compress_g2(point));
+        }
+        bytes.extend(num_g1_points.to_be_bytes());
+        for point in &self.g1 {
+            bytes.extend(compress_g1(point));
+        }
+        Ok(bytes)
+    }
+}
+
+// The big-endian x coordinate, flagged with whether y is the largest of the
+// two square roots.
+fn compress_g1(point: &G1Affine) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    let Some((x, y)) = point.xy() else {
+        bytes[0] = COMPRESSED_INFINITY;
+        return bytes;
+    };
+    write_be(&mut bytes, x);
+    bytes[0] |= if is_largest(y) {
+        COMPRESSED_LARGEST
+    } else {
+        COMPRESSED_SMALLEST
+    };
+    bytes
+}
+
+// The big-endian imaginary and real parts of the x coordinate, flagged as in
+// compress_g1, where the largest y is the one whose imaginary part is the
+// largest, or whose real part is if it is real.
+fn compress_g2(point: &G2Affine) -> [u8; 64] {
+    let mut bytes = [0; 64];
+    let Some((x, y)) = point.xy() else {
+        bytes[0] = COMPRESSED_INFINITY;
+        return bytes;
+    };
+    let (c1, c0) = bytes.split_at_mut(32);
+    write_be(c1, &x.c1);
+    write_be(c0, &x.c0);
+    bytes[0] |= if is_largest_fq2(y) {
+        COMPRESSED_LARGEST
+    } else {
+        COMPRESSED_SMALLEST
+    };
+    bytes
+}
+
+fn write_be(bytes: &mut [u8], element: &Fq) {
+    for (byte, value) in bytes
+        .iter_mut()
+        .rev()
+        .zip(element.into_bigint().to_bytes_le())
+    {
+        *byte = value;
+    }
+}
+
+fn is_largest(element: &Fq) -> bool {
+    element.into_bigint() > Fq::MODULUS_MINUS_ONE_DIV_TWO
+}
+
+fn is_largest_fq2(element: &Fq2) -> bool {
+    if element.c1.is_zero() {
+        is_largest(&element.c0)
+    } else {
+        is_largest(&element.c1)
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn from_secret(tau: u64, num_g1_points: usize) -> Powers {
+    let mut g1 = Vec::new();
+    let mut power = Fr::from(1_u64);
+    for _ in 0..num_g1_points {
+        g1.push((G1Affine::generator() * power).into());
+        power *= Fr::from(tau);
+    }
+    Powers {
+        g1,
+        g2: [
+            G2Affine::generator(),
+            (G2Affine::generator() * Fr::from(tau)).into(),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_accepts_successive_powers() {
+        assert!(from_secret(5, 8).check().is_ok());
+    }
+
+    #[test]
+    fn test_check_rejects_other_points() {
+        let mut powers = from_secret(5, 8);
+        *powers.g1.get_mut(3).unwrap() = (G1Affine::generator() * Fr::from(7_u64)).into();
+        assert!(matches!(
+            powers.check(),
+            Err(GnarkBackendError::SRSError(message))
+                if message == "the points are not the powers of the same secret"
+        ));
+
+        let mut powers = from_secret(5, 8);
+        powers.g2[1] = (G2Affine::generator() * Fr::from(6_u64)).into();
+        assert!(powers.check().is_err());
+
+        let mut powers = from_secret(5, 8);
+        powers.g1.swap(0, 1);
+        assert!(powers.check().is_err());
+    }
+
+    #[test]
+    fn test_compress_g1() {
+        // The generator is (1, 2) and 2 is the smallest square root.
+        let mut expected = [0; 32];
+        expected[0] = COMPRESSED_SMALLEST;
+        expected[31] = 1;
+        assert_eq!(compress_g1(&G1Affine::generator()), expected);
+
+        expected[0] = COMPRESSED_LARGEST;
+        assert_eq!(compress_g1(&-G1Affine::generator()), expected);
+
+        assert_eq!(compress_g1(&G1Affine::zero())[0], COMPRESSED_INFINITY);
+    }
+
+    #[test]
+    fn test_to_gnark_bytes() {
+        let powers = from_secret(5, 3);
+        let bytes = powers.to_gnark_bytes().unwrap();
+
+        assert_eq!(bytes.len(), 2 * 64 + 4 + 3 * 32);
+        assert_eq!(
+            bytes.get(..64),
+            Some(compress_g2(&G2Affine::generator()).as_slice())
+        );
+        assert_eq!(bytes.get(128..132), Some([0, 0, 0, 3].as_slice()));
+        assert_eq!(
+            bytes.get(132..164),
+            Some(compress_g1(&G1Affine::generator()).as_slice())
+        );
+    }
+}
@@ -0,0 +1,240 @@
+// Reads the powers of τ of a BN254 `.ptau` file, as written by snarkjs for the
+// Perpetual Powers of Tau ceremony.
+//
+// The file starts with the magic "ptau", a version and the number of sections,
+// each section starting with its type and its size in bytes. The header
+// section holds the size of the field elements, the base field modulus and
+// the power of two of the number of powers. The points are stored
+// uncompressed, their coordinates being little-endian in Montgomery form.
+
+use super::powers::Powers;
+use crate::gnark_backend_wrapper::GnarkBackendError;
+use ark_bn254::{Fq, Fq2, G1Affine, G2Affine};
+use ark_ff::{BigInt, Field, PrimeField, Zero};
+use std::io::{Read, Seek, SeekFrom};
+
+const HEADER_SECTION: u32 = 1;
+const TAU_G1_SECTION: u32 = 2;
+const TAU_G2_SECTION: u32 = 3;
+
+const G1_POINT_SIZE: u64 = 2 * 32;
+const G2_POINT_SIZE: u64 = 4 * 32;
+
+// Returns the first `num_g1_points` powers of τ in G1 and the first two in
+// G2, without checking them.
+pub(crate) fn read(
+    mut reader: impl Read + Seek,
+    num_g1_points: usize,
+) -> Result<Powers, GnarkBackendError> {
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic).map_err(io_error)?;
+    if &magic != b"ptau" {
+        return Err(GnarkBackendError::SRSError("not a ptau file".to_owned()));
+    }
+    let _version = read_u32(&mut reader)?;
+    let num_sections = read_u32(&mut reader)?;
+
+    // The offset and size of every section.
+    let mut sections = Vec::new();
+    for _ in 0..num_sections {
+        let section_type = read_u32(&mut reader)?;
+        let size = read_u64(&mut reader)?;
+        let offset = reader.stream_position().map_err(io_error)?;
+        sections.push((section_type, offset, size));
+        reader
+            .seek(SeekFrom::Current(
+                i64::try_from(size).map_err(|e| GnarkBackendError::SRSError(e.to_string()))?,
+            ))
+            .map_err(io_error)?;
+    }
+
+    seek_section(&mut reader, &sections, HEADER_SECTION)?;
+    let field_size = read_u32(&mut reader)?;
+    let mut modulus = [0; 32];
+    reader.read_exact(&mut modulus).map_err(io_error)?;
+    if field_size != 32 || BigInt::new(read_limbs_le(&modulus)) != Fq::MODULUS {
+        return Err(GnarkBackendError::SRSError(
+            "the file is not over BN254".to_owned(),
+        ));
+    }
+
+    // The inverse of R = 2²⁵⁶, to take the coordinates out of Montgomery form.
+    let r_inv = Fq::from(1_u64) / Fq::from(2_u64).pow([256]);
+
+    let size = seek_section(&mut reader, &sections, TAU_G1_SECTION)?;
+    let available = size / G1_POINT_SIZE;
+    if available
+        < u64::try_from(num_g1_points).map_err(|e| GnarkBackendError::SRSError(e.to_string()))?
+    {
+        return Err(GnarkBackendError::SRSError(format!(
+            "the file only holds {available} points but {num_g1_points} are needed"
+        )));
+    }
+    let mut g1 = Vec::with_capacity(num_g1_points);
+    for _ in 0..num_g1_points {
+        let x = read_fq(&mut reader, r_inv)?;
+        let y = read_fq(&mut reader, r_inv)?;
+        g1.push(g1_point(x, y));
+    }
+
+    let size = seek_section(&mut reader, &sections, TAU_G2_SECTION)?;
+    if size < 2 * G2_POINT_SIZE {
+        return Err(GnarkBackendError::SRSError(
+            "the file holds less than 2 points in G2".to_owned(),
+        ));
+    }
+    let mut g2 = [G2Affine::default(); 2];
+    for point in &mut g2 {
+        let x = Fq2::new(read_fq(&mut reader, r_inv)?, read_fq(&mut reader, r_inv)?);
+        let y = Fq2::new(read_fq(&mut reader, r_inv)?, read_fq(&mut reader, r_inv)?);
+        *point = g2_point(x, y);
+    }
+
+    Ok(Powers { g1, g2 })
+}
+
+// Moves to the start of the section and returns its size.
+fn seek_section(
+    reader: &mut impl Seek,
+    sections: &[(u32, u64, u64)],
+    section_type: u32,
+) -> Result<u64, GnarkBackendError> {
+    let (_, offset, size) = sections
+        .iter()
+        .find(|(t, _, _)| *t == section_type)
+        .ok_or_else(|| GnarkBackendError::SRSError(format!("missing section {section_type}")))?;
+    reader.seek(SeekFrom::Start(*offset)).map_err(io_error)?;
+    Ok(*size)
+}
+
+// The point at infinity is stored as (0, 0).
+fn g1_point(x: Fq, y: Fq) -> G1Affine {
+    if x.is_zero() && y.is_zero() {
+        return G1Affine::identity();
+    }
+    G1Affine::new_unchecked(x, y)
+}
+
+fn g2_point(x: Fq2, y: Fq2) -> G2Affine {
+    if x.is_zero() && y.is_zero() {
+        return G2Affine::identity();
+    }
+    G2Affine::new_unchecked(x, y)
+}
+
+fn read_fq(reader: &mut impl Read, r_inv: Fq) -> Result<Fq, GnarkBackendError> {
+    let mut bytes = [0; 32];
+    reader.read_exact(&mut bytes).map_err(io_error)?;
+    let montgomery = Fq::from_bigint(BigInt::new(read_limbs_le(&bytes))).ok_or_else(|| {
+        GnarkBackendError::SRSError("a coordinate is not a field element".to_owned())
+    })?;
+    Ok(montgomery * r_inv)
+}
+
+fn read_limbs_le(bytes: &[u8; 32]) -> [u64; 4] {
+    let mut limbs = [0; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        let mut limb_bytes = [0; 8];
+        limb_bytes.copy_from_slice(chunk);
+        *limb = u64::from_le_bytes(limb_bytes);
+    }
+    limbs
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, GnarkBackendError> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes).map_err(io_error)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> Result<u64, GnarkBackendError> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes).map_err(io_error)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn io_error(error: std::io::Error) -> GnarkBackendError {
+    GnarkBackendError::SRSError(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::powers::from_secret;
+    use super::*;
+    use ark_ec::AffineRepr;
+    use ark_ff::BigInteger;
+    use std::io::Cursor;
+
+    fn write_fq(bytes: &mut Vec<u8>, element: Fq) {
+        let r = Fq::from(2_u64).pow([256]);
+        bytes.extend((element * r).into_bigint().to_bytes_le());
+    }
+
+    fn write_section(bytes: &mut Vec<u8>, section_type: u32, data: &[u8]) {
+        bytes.extend(section_type.to_le_bytes());
+        bytes.extend(u64::try_from(data.len()).unwrap().to_le_bytes());
+        bytes.extend(data);
+    }
+
+    // A ptau file with the powers of 5, its sections out of order.
+    fn ptau_file(num_g1_points: usize, modulus: BigInt<4>) -> Vec<u8> {
+        let powers = from_secret(5, num_g1_points);
+
+        let mut tau_g1 = Vec::new();
+        for point in &powers.g1 {
+            let (x, y) = point.xy().unwrap();
+            write_fq(&mut tau_g1, *x);
+            write_fq(&mut tau_g1, *y);
+        }
+        let mut tau_g2 = Vec::new();
+        for point in &powers.g2 {
+            let (x, y) = point.xy().unwrap();
+            for coordinate in [x.c0, x.c1, y.c0, y.c1] {
+                write_fq(&mut tau_g2, coordinate);
+            }
+        }
+        let mut header = Vec::new();
+        header.extend(32_u32.to_le_bytes());
+        header.extend(modulus.to_bytes_le());
+        header.extend(2_u32.to_le_bytes());
+
+        let mut bytes = b"ptau".to_vec();
+        bytes.extend(1_u32.to_le_bytes());
+        bytes.extend(3_u32.to_le_bytes());
+        write_section(&mut bytes, TAU_G2_SECTION, &tau_g2);
+        write_section(&mut bytes, HEADER_SECTION, &header);
+        write_section(&mut bytes, TAU_G1_SECTION, &tau_g1);
+        bytes
+    }
+
+    #[test]
+    fn test_read() {
+        let file = ptau_file(7, Fq::MODULUS);
+
+        let powers = read(Cursor::new(file), 4).unwrap();
+
+        let expected = from_secret(5, 4);
+        assert_eq!(powers.g1, expected.g1);
+        assert_eq!(powers.g2, expected.g2);
+        assert!(powers.check().is_ok());
+    }
+
+    #[test]
+    fn test_read_fails_without_enough_points() {
+        let file = ptau_file(7, Fq::MODULUS);
+
+        assert!(matches!(
+            read(Cursor::new(file), 8),
+            Err(GnarkBackendError::SRSError(message))
+                if message == "the file only holds 7 points but 8 are needed"
+        ));
+    }
+
+    #[test]
+    fn test_read_fails_over_another_field() {
+        let file = ptau_file(7, ark_bn254::Fr::MODULUS);
+
+        assert!(read(Cursor::new(file), 4).is_err());
+        assert!(read(Cursor::new(b"ptax".to_vec()), 4).is_err());
+    }
+}
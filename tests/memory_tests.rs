@@ -0,0 +1,99 @@
+// The test measures the resident memory of the whole process, which only
+// Linux exposes through /proc, and is only run on request with
+// `cargo test --test memory_tests -- --ignored`.
+#![cfg(target_os = "linux")]
+
+use acvm::acir::circuit::{Circuit, Opcode, PublicInputs};
+use acvm::acir::native_types::{Expression, Witness};
+use acvm::FieldElement;
+use noir_backend_using_gnark::gnark_backend_wrapper::{CurveId, Srs};
+use noir_backend_using_gnark::Gnark;
+use std::collections::{BTreeMap, BTreeSet};
+
+const NUM_SQUARINGS: u32 = 4096;
+const WARM_UP_ITERATIONS: usize = 2;
+const ITERATIONS: usize = 8;
+
+// w_{i+1} = w_i * w_i for i in 1..=NUM_SQUARINGS, the last witness being
+// public. It is big enough for the keys to dwarf the allocator's noise.
+fn squarings_circuit() -> Circuit {
+    let opcodes = (1..=NUM_SQUARINGS)
+        .map(|i| {
+            Opcode::Arithmetic(Expression {
+                mul_terms: vec![(FieldElement::one(), Witness(i), Witness(i))],
+                linear_combinations: vec![(-FieldElement::one(), Witness(i + 1))],
+                q_c: FieldElement::zero(),
+            })
+        })
+        .collect();
+
+    Circuit {
+        current_witness_index: NUM_SQUARINGS + 1,
+        opcodes,
+        public_inputs: PublicInputs(BTreeSet::from([Witness(NUM_SQUARINGS + 1)])),
+    }
+}
+
+fn squarings_witness() -> BTreeMap<Witness, FieldElement> {
+    let mut witness_values = BTreeMap::new();
+    let mut value = FieldElement::from(2_u128);
+    for i in 1..=NUM_SQUARINGS + 1 {
+        witness_values.insert(Witness(i), value);
+        value = value * value;
+    }
+    witness_values
+}
+
+fn resident_set_size() -> usize {
+    // The VmRSS line of status holds the resident memory in kB, whatever the
+    // size of the pages.
+    let status = std::fs::read_to_string("/proc/self/status").unwrap();
+    let resident_kb: usize = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|rss| rss.trim().strip_suffix("kB"))
+        .unwrap()
+        .trim()
+        .parse()
+        .unwrap();
+    resident_kb * 1024
+}
+
+#[test]
+#[ignore = "slow and sensitive to the allocator: measures the resident memory of the process"]
+fn test_repeated_proving_does_not_leak_go_strings() {
+    let circuit = squarings_circuit();
+    let required_size = Gnark::default()
+        .try_required_srs_size(&circuit)
+        .unwrap()
+        .unwrap_or_default();
+    let gnark =
+        Gnark::default().with_srs(Srs::insecure_test_srs(CurveId::DEFAULT, required_size).unwrap());
+
+    let prove = || {
+        let (proving_key, verifying_key) = gnark.try_preprocess(&circuit).unwrap();
+        let proof = gnark
+            .try_prove_with_pk(&circuit, squarings_witness(), &proving_key)
+            .unwrap();
+        // Every call hands back hex encoded strings allocated by Go, the SRS
+        // being kept on the Go side and only referred to by its handle.
+        2 * (proving_key.len() + verifying_key.len() + proof.len())
+    };
+
+    // Let the Go runtime grow its heap to its steady state first.
+    for _ in 0..WARM_UP_ITERATIONS {
+        prove();
+    }
+
+    let rss_before = resident_set_size();
+    let mut returned_bytes = 0;
+    for _ in 0..ITERATIONS {
+        returned_bytes += prove();
+    }
+    let rss_growth = resident_set_size().saturating_sub(rss_before);
+
+    assert!(
+        rss_growth < returned_bytes / 2,
+        "resident memory grew {rss_growth} bytes while Go returned {returned_bytes} bytes"
+    );
+}
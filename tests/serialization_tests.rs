@@ -3,8 +3,11 @@
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "groth16")] {
+        use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
         use noir_backend_using_gnark::acvm;
-        use noir_backend_using_gnark::gnark_backend_wrapper::{AddTerm, MulTerm, RawGate, RawR1CS};
+        use noir_backend_using_gnark::gnark_backend_wrapper::{
+            self, AddTerm, MulTerm, RawGate, RawR1CS,
+        };
         use std::ffi;
 
         extern "C" {
@@ -55,6 +58,29 @@ cfg_if::cfg_if! {
             go_pre_serialized_element.to_str().unwrap().as_bytes()
         }
 
+        fn serialize_felt(felt: &gnark_backend_wrapper::Fr) -> Vec<u8> {
+            let mut serialized_felt = Vec::new();
+            felt.serialize_uncompressed(&mut serialized_felt).unwrap();
+            // Turn little-endian to big-endian.
+            serialized_felt.reverse();
+            serialized_felt
+        }
+
+        fn serialize_felts(felts: &[gnark_backend_wrapper::Fr]) -> Vec<u8> {
+            // gnark's fr.Vector binary encoding prefixes the felts with its
+            // length as a big-endian u32.
+            let mut serialized_felts = u32::try_from(felts.len()).unwrap().to_be_bytes().to_vec();
+            serialized_felts.extend(felts.iter().flat_map(serialize_felt));
+            serialized_felts
+        }
+
+        fn deserialize_felt(encoded_felt: &[u8]) -> gnark_backend_wrapper::Fr {
+            let mut decoded_felt = hex::decode(encoded_felt).unwrap();
+            // Turn big-endian to little-endian.
+            decoded_felt.reverse();
+            CanonicalDeserialize::deserialize_uncompressed(decoded_felt.as_slice()).unwrap()
+        }
+
         fn random_add_term() -> AddTerm {
             AddTerm {
                 coefficient: rand::random(),